@@ -0,0 +1,50 @@
+import { createServer } from 'node:http'
+
+/**
+ * 本地模拟更新服务，用于测试后台定时检查
+ *
+ * 用法：
+ *   node scripts/mock-update-server.mjs [version] [port]
+ *   KIT_UPDATE_ENDPOINT=http://127.0.0.1:8787/latest.json KIT_UPDATE_CHECK_INTERVAL=30 pnpm tauri:dev
+ *
+ * 清单只用于触发 `updater:available` 与系统通知，更新包与签名均为占位内容，无法实际安装
+ */
+const version = process.argv[2] || '99.0.0'
+const port = Number(process.argv[3] || 8787)
+
+const targets = [
+  'darwin-aarch64',
+  'darwin-x86_64',
+  'linux-x86_64',
+  'linux-aarch64',
+  'windows-x86_64',
+  'windows-aarch64'
+]
+
+const manifest = () => ({
+  version,
+  notes: `Mock release ${version}`,
+  pub_date: new Date().toISOString(),
+  platforms: Object.fromEntries(
+    targets.map((target) => [
+      target,
+      {
+        signature: 'mock-signature',
+        url: `http://127.0.0.1:${port}/package/${target}`
+      }
+    ])
+  )
+})
+
+createServer((req, res) => {
+  console.log(`${req.method} ${req.url}`)
+  if (req.url === '/latest.json') {
+    res.writeHead(200, { 'Content-Type': 'application/json' })
+    res.end(JSON.stringify(manifest(), null, 2))
+    return
+  }
+  res.writeHead(404, { 'Content-Type': 'text/plain' })
+  res.end('not found')
+}).listen(port, '127.0.0.1', () => {
+  console.log(`模拟更新服务已启动：http://127.0.0.1:${port}/latest.json（版本 ${version}）`)
+})
@@ -4,6 +4,9 @@ import { createHash } from 'node:crypto'
 import { existsSync } from 'node:fs'
 
 const octokit = getOctokit(process.env.GITHUB_TOKEN)
+const { owner, repo } = context.repo
+// 本次发布的版本 tag，更新包与 .sig 签名都已上传到该 release
+const tag = process.env.RELEASE_TAG || process.env.GITHUB_REF_NAME
 
 /**
  * 计算文件哈希值，用于增量更新检测
@@ -31,6 +34,93 @@ const generateDeltaInfo = async (currentVersion, previousVersion) => {
   return deltaInfo
 }
 
+/**
+ * 根据更新包文件名判断 latest.json 中的平台键
+ */
+const platformsOf = (name) => {
+  const arch = /aarch64|arm64/i.test(name) ? 'aarch64' : /x86_64|x64|amd64/i.test(name) ? 'x86_64' : null
+  if (name.endsWith('.app.tar.gz')) {
+    // macOS 更新包名不带架构，发布矩阵只在 Apple Silicon（macos-15）上构建
+    return [`darwin-${arch || 'aarch64'}`]
+  }
+  if (!arch) return null
+  if (name.endsWith('.AppImage')) return [`linux-${arch}`, `linux-${arch}-appimage`]
+  if (name.endsWith('-setup.exe')) return [`windows-${arch}`, `windows-${arch}-nsis`]
+  if (name.endsWith('.msi')) return [`windows-${arch}-msi`]
+  return null
+}
+
+/**
+ * 读取 release 附件的文本内容
+ */
+const readAsset = async (asset) => {
+  const { data } = await octokit.rest.repos.getReleaseAsset({
+    owner,
+    repo,
+    asset_id: asset.id,
+    headers: { accept: 'application/octet-stream' },
+  })
+  return Buffer.from(data).toString('utf-8').trim()
+}
+
+/**
+ * 收集版本 release 中的 .sig 签名，生成 latest.json
+ */
+const buildManifest = async () => {
+  const { data: release } = await octokit.rest.repos.getReleaseByTag({ owner, repo, tag })
+  const platforms = {}
+  for (const sig of release.assets.filter((item) => item.name.endsWith('.sig'))) {
+    const name = sig.name.slice(0, -'.sig'.length)
+    const artifact = release.assets.find((item) => item.name === name)
+    const keys = platformsOf(name)
+    if (!artifact || !keys) {
+      console.warn('⚠️ 跳过无法识别的签名:', sig.name)
+      continue
+    }
+    const signature = await readAsset(sig)
+    for (const key of keys) {
+      platforms[key] = { signature, url: artifact.browser_download_url }
+    }
+  }
+  // 没有 NSIS 安装包时通用的 Windows 平台键回落到 MSI
+  for (const key of Object.keys(platforms)) {
+    const generic = key.replace(/-msi$/, '')
+    if (generic !== key && !platforms[generic]) {
+      platforms[generic] = platforms[key]
+    }
+  }
+  if (Object.keys(platforms).length === 0) {
+    throw new Error(`发布 ${tag} 中没有找到更新包签名，请确认构建时配置了 TAURI_SIGNING_PRIVATE_KEY`)
+  }
+  return {
+    version: tag.replace(/^v/, ''),
+    notes: release.body || '',
+    pub_date: release.published_at || new Date().toISOString(),
+    platforms,
+  }
+}
+
+/**
+ * 获取 updater 渠道的 release，不存在时创建
+ */
+const getUpdaterRelease = async () => {
+  try {
+    const { data } = await octokit.rest.repos.getReleaseByTag({ owner, repo, tag: 'updater' })
+    return data
+  } catch (error) {
+    if (error.status !== 404) throw error
+    const { data } = await octokit.rest.repos.createRelease({
+      owner,
+      repo,
+      tag_name: 'updater',
+      name: 'Updater',
+      body: '自动更新清单，请勿删除',
+      prerelease: true,
+    })
+    return data
+  }
+}
+
 /**
  * 更新发布信息
  */
@@ -38,12 +128,15 @@ const updateRelease = async () => {
   try {
     console.log('🚀 开始更新发布信息...')
     
+    if (!tag) {
+      throw new Error('未指定发布版本（RELEASE_TAG）')
+    }
+
+    // 从版本 release 的签名生成清单
+    const data = await buildManifest()
+
     // 获取updater tag的release
-    const { data: release } = await octokit.rest.repos.getReleaseByTag({
-      owner: context.repo.owner,
-      repo: context.repo.repo,
-      tag: 'updater',
-    })
+    const release = await getUpdaterRelease()
     
     console.log('📦 找到updater release:', release.name)
     
@@ -53,34 +146,22 @@ const updateRelease = async () => {
       .map(async (item) => {
         console.log('🗑️ 删除旧的latest.json文件')
         await octokit.rest.repos.deleteReleaseAsset({
-          owner: context.repo.owner,
-          repo: context.repo.repo,
+          owner,
+          repo,
           asset_id: item.id,
         })
       })
     
     await Promise.all(deletePromises)
     
-    // 读取并处理latest.json文件
-    const file = await readFile('latest.json', { encoding: 'utf-8' })
-    const data = JSON.parse(file)
-    
-    // 确保macOS架构兼容性
-    if (data.platforms['darwin-x86_64']) {
-      data.platforms['darwin-aarch64'] = {
-        ...data.platforms['darwin-x86_64'],
-        url: data.platforms['darwin-x86_64'].url.replace('x86_64', 'aarch64')
-      }
-    }
-    
     // 添加增量更新信息
     const currentVersion = data.version
     console.log('📋 当前版本:', currentVersion)
     
     // 获取最近的几个版本用于增量更新
     const { data: releases } = await octokit.rest.repos.listReleases({
-      owner: context.repo.owner,
-      repo: context.repo.repo,
+      owner,
+      repo,
       per_page: 5
     })
     
@@ -118,8 +199,8 @@ const updateRelease = async () => {
     // 上传新的latest.json文件
     console.log('📤 上传新的latest.json文件')
     await octokit.rest.repos.uploadReleaseAsset({
-      owner: context.repo.owner,
-      repo: context.repo.repo,
+      owner,
+      repo,
       release_id: release.id,
       name: 'latest.json',
       data: JSON.stringify(data, null, 2),
@@ -0,0 +1,577 @@
+use std::collections::HashMap;
+use std::io::Read;
+
+use argon2::{Algorithm, Argon2, Params, Version};
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{AppHandle, Manager, State};
+
+use crate::history::{HistoryDb, HistoryEntry};
+use crate::settings::{self, Settings, SettingsStore};
+use crate::storage;
+
+/// 归档文件头：魔数 + 格式版本 + 标志位；加密时后接 KDF 参数、盐与随机数
+const MAGIC: &[u8; 8] = b"KITDATA\0";
+const FORMAT_VERSION: u8 = 1;
+const FLAG_ENCRYPTED: u8 = 1;
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 24;
+const KEY_LEN: usize = 32;
+/// Argon2id 参数：64 MiB 内存、3 轮、单线程
+const KDF_MEMORY_KIB: u32 = 64 * 1024;
+const KDF_ITERATIONS: u32 = 3;
+const KDF_PARALLELISM: u32 = 1;
+/// 导入时 KDF 参数来自文件头，属于不可信数据；超出上限直接拒绝，避免构造的归档耗尽内存或 CPU
+const KDF_MAX_MEMORY_KIB: u32 = 4 * KDF_MEMORY_KIB;
+const KDF_MAX_ITERATIONS: u32 = 10;
+const KDF_MAX_PARALLELISM: u32 = 4;
+
+/// 归档内容，对应前端 `AppData` 的全部字段
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct AppData {
+  pub app_version: String,
+  pub exported_at: i64,
+  /// 按原样保存，导入时先经过 `settings::migrate` 再反序列化，旧版本导出的归档也能导入
+  pub settings: Value,
+  pub history: Vec<HistoryEntry>,
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportStrategy {
+  /// 合并：本地已有的数据优先，新增导入中独有的部分
+  Merge,
+  /// 替换：用导入数据整体覆盖本地数据
+  Replace,
+}
+
+#[derive(Serialize, Clone, Copy, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictResolution {
+  KeepLocal,
+  UseIncoming,
+}
+
+/// 本地与导入数据不一致的条目
+#[derive(Serialize, Clone, Debug)]
+pub struct ImportConflict {
+  /// `preference` / `tool_config` / `history`
+  pub kind: &'static str,
+  pub key: String,
+  pub local: Value,
+  pub incoming: Value,
+  pub resolution: ConflictResolution,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ExportSummary {
+  pub path: String,
+  pub encrypted: bool,
+  pub history: usize,
+  pub size: u64,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ImportReport {
+  pub applied: bool,
+  pub app_version: String,
+  pub exported_at: i64,
+  pub history_incoming: usize,
+  pub history_added: usize,
+  pub favorites_added: usize,
+  pub tool_configs_added: usize,
+  pub conflicts: Vec<ImportConflict>,
+}
+
+fn derive_key(passphrase: &str, salt: &[u8], memory: u32, iterations: u32, parallelism: u32) -> Result<[u8; KEY_LEN], String> {
+  let params = Params::new(memory, iterations, parallelism, Some(KEY_LEN)).map_err(|e| e.to_string())?;
+  let mut key = [0_u8; KEY_LEN];
+  Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+    .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+    .map_err(|e| format!("Failed to derive key: {e}"))?;
+  Ok(key)
+}
+
+/// 序列化并压缩，有口令时用 Argon2id 派生密钥并以 XChaCha20-Poly1305 加密；文件头作为附加认证数据
+fn encode(data: &AppData, passphrase: Option<&str>) -> Result<Vec<u8>, String> {
+  let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+  serde_json::to_writer(&mut encoder, data).map_err(|e| e.to_string())?;
+  let payload = encoder.finish().map_err(|e| e.to_string())?;
+
+  let mut output = MAGIC.to_vec();
+  output.push(FORMAT_VERSION);
+  let Some(passphrase) = passphrase.filter(|passphrase| !passphrase.is_empty()) else {
+    output.push(0);
+    output.extend_from_slice(&payload);
+    return Ok(output);
+  };
+
+  output.push(FLAG_ENCRYPTED);
+  for value in [KDF_MEMORY_KIB, KDF_ITERATIONS, KDF_PARALLELISM] {
+    output.extend_from_slice(&value.to_le_bytes());
+  }
+  let mut salt = [0_u8; SALT_LEN];
+  OsRng.fill_bytes(&mut salt);
+  output.extend_from_slice(&salt);
+  let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+  output.extend_from_slice(&nonce);
+
+  let key = derive_key(passphrase, &salt, KDF_MEMORY_KIB, KDF_ITERATIONS, KDF_PARALLELISM)?;
+  let cipher = XChaCha20Poly1305::new(&key.into());
+  let ciphertext = cipher
+    .encrypt(
+      &nonce,
+      Payload {
+        msg: &payload,
+        aad: &output,
+      },
+    )
+    .map_err(|_| "Failed to encrypt archive".to_string())?;
+  output.extend_from_slice(&ciphertext);
+  Ok(output)
+}
+
+fn decode(bytes: &[u8], passphrase: Option<&str>) -> Result<AppData, String> {
+  let header_len = MAGIC.len() + 2;
+  if bytes.len() < header_len || &bytes[..MAGIC.len()] != MAGIC {
+    return Err("Not a Kit data archive".to_string());
+  }
+  let version = bytes[MAGIC.len()];
+  if version > FORMAT_VERSION {
+    return Err(format!("Archive format version {version} is not supported"));
+  }
+  let flags = bytes[MAGIC.len() + 1];
+
+  let payload = if flags & FLAG_ENCRYPTED != 0 {
+    let passphrase = passphrase
+      .filter(|passphrase| !passphrase.is_empty())
+      .ok_or_else(|| "This archive is encrypted; a passphrase is required".to_string())?;
+    let params_end = header_len + 12;
+    let nonce_start = params_end + SALT_LEN;
+    let body_start = nonce_start + NONCE_LEN;
+    if bytes.len() < body_start {
+      return Err("Archive header is truncated".to_string());
+    }
+    let param = |index: usize| {
+      let start = header_len + index * 4;
+      u32::from_le_bytes([bytes[start], bytes[start + 1], bytes[start + 2], bytes[start + 3]])
+    };
+    let (memory, iterations, parallelism) = (param(0), param(1), param(2));
+    if memory > KDF_MAX_MEMORY_KIB || iterations > KDF_MAX_ITERATIONS || parallelism > KDF_MAX_PARALLELISM {
+      return Err(format!(
+        "Archive key derivation parameters are too expensive (memory {memory} KiB, {iterations} iterations, parallelism {parallelism})"
+      ));
+    }
+    let key = derive_key(passphrase, &bytes[params_end..nonce_start], memory, iterations, parallelism)?;
+    let cipher = XChaCha20Poly1305::new(&key.into());
+    cipher
+      .decrypt(
+        XNonce::from_slice(&bytes[nonce_start..body_start]),
+        Payload {
+          msg: &bytes[body_start..],
+          aad: &bytes[..body_start],
+        },
+      )
+      .map_err(|_| "Wrong passphrase or corrupted archive".to_string())?
+  } else {
+    bytes[header_len..].to_vec()
+  };
+
+  let mut json = Vec::new();
+  GzDecoder::new(payload.as_slice())
+    .read_to_end(&mut json)
+    .map_err(|e| format!("Failed to decompress archive: {e}"))?;
+  serde_json::from_slice(&json).map_err(|e| format!("Invalid archive content: {e}"))
+}
+
+fn to_value<T: Serialize>(value: &T) -> Value {
+  serde_json::to_value(value).unwrap_or_default()
+}
+
+/// 把归档中的设置迁移到当前版本
+fn archived_settings(value: &Value) -> Result<Settings, String> {
+  let mut value = value.clone();
+  settings::migrate(&mut value)?;
+  serde_json::from_value(value).map_err(|e| format!("Invalid settings in archive: {e}"))
+}
+
+/// 合并设置：偏好与工具配置冲突时保留修改时间较新的一方（偏好无时间戳，保留本地），收藏与最近使用取并集
+fn merge_settings(local: &Settings, incoming: &Settings, report: &mut ImportReport) -> Settings {
+  let mut merged = local.clone();
+
+  let local_preferences = to_value(&local.preferences);
+  let incoming_preferences = to_value(&incoming.preferences);
+  if let (Some(local_map), Some(incoming_map)) = (local_preferences.as_object(), incoming_preferences.as_object()) {
+    for (key, incoming_value) in incoming_map {
+      match local_map.get(key) {
+        Some(local_value) if local_value != incoming_value => report.conflicts.push(ImportConflict {
+          kind: "preference",
+          key: key.clone(),
+          local: local_value.clone(),
+          incoming: incoming_value.clone(),
+          resolution: ConflictResolution::KeepLocal,
+        }),
+        _ => {}
+      }
+    }
+  }
+
+  for (slug, incoming_config) in &incoming.tool_configs {
+    match local.tool_configs.get(slug) {
+      None => {
+        merged.tool_configs.insert(slug.clone(), incoming_config.clone());
+        report.tool_configs_added += 1;
+      }
+      Some(local_config) if local_config != incoming_config => {
+        let use_incoming = incoming_config.last_modified > local_config.last_modified;
+        if use_incoming {
+          merged.tool_configs.insert(slug.clone(), incoming_config.clone());
+        }
+        report.conflicts.push(ImportConflict {
+          kind: "tool_config",
+          key: slug.clone(),
+          local: to_value(local_config),
+          incoming: to_value(incoming_config),
+          resolution: if use_incoming {
+            ConflictResolution::UseIncoming
+          } else {
+            ConflictResolution::KeepLocal
+          },
+        });
+      }
+      Some(_) => {}
+    }
+  }
+
+  for slug in &incoming.favorites {
+    if !merged.favorites.contains(slug) {
+      merged.favorites.push(slug.clone());
+      report.favorites_added += 1;
+    }
+  }
+  for slug in &incoming.recent_tools {
+    if !merged.recent_tools.contains(slug) {
+      merged.recent_tools.push(slug.clone());
+    }
+  }
+  for category in &incoming.custom_categories {
+    if !merged.custom_categories.contains(category) {
+      merged.custom_categories.push(category.clone());
+    }
+  }
+  merged
+}
+
+/// 合并历史：id 相同内容不同的记录保留本地版本
+fn merge_history(local: &[HistoryEntry], incoming: &[HistoryEntry], report: &mut ImportReport) -> Vec<HistoryEntry> {
+  let local_by_id: HashMap<&str, &HistoryEntry> = local.iter().map(|entry| (entry.id.as_str(), entry)).collect();
+  let mut added = Vec::new();
+  for entry in incoming {
+    match local_by_id.get(entry.id.as_str()) {
+      None => added.push(entry.clone()),
+      Some(existing) if *existing != entry => report.conflicts.push(ImportConflict {
+        kind: "history",
+        key: entry.id.clone(),
+        local: to_value(existing),
+        incoming: to_value(entry),
+        resolution: ConflictResolution::KeepLocal,
+      }),
+      Some(_) => {}
+    }
+  }
+  report.history_added = added.len();
+  added
+}
+
+/// 替换时报告将被覆盖的本地数据
+fn replace_conflicts(local: &Settings, incoming: &Settings, report: &mut ImportReport) {
+  if local.preferences != incoming.preferences {
+    report.conflicts.push(ImportConflict {
+      kind: "preference",
+      key: "preferences".to_string(),
+      local: to_value(&local.preferences),
+      incoming: to_value(&incoming.preferences),
+      resolution: ConflictResolution::UseIncoming,
+    });
+  }
+  for (slug, local_config) in &local.tool_configs {
+    if incoming.tool_configs.get(slug) != Some(local_config) {
+      report.conflicts.push(ImportConflict {
+        kind: "tool_config",
+        key: slug.clone(),
+        local: to_value(local_config),
+        incoming: incoming.tool_configs.get(slug).map(to_value).unwrap_or_default(),
+        resolution: ConflictResolution::UseIncoming,
+      });
+    }
+  }
+  report.tool_configs_added = incoming
+    .tool_configs
+    .keys()
+    .filter(|slug| !local.tool_configs.contains_key(*slug))
+    .count();
+  report.favorites_added = incoming
+    .favorites
+    .iter()
+    .filter(|slug| !local.favorites.contains(*slug))
+    .count();
+}
+
+/// 导出设置与历史到归档文件，提供口令时加密
+#[tauri::command]
+pub async fn data_export(
+  app_handle: AppHandle,
+  path: String,
+  passphrase: Option<String>,
+) -> Result<ExportSummary, String> {
+  let data = AppData {
+    app_version: app_handle.package_info().version.to_string(),
+    exported_at: chrono::Utc::now().timestamp_millis(),
+    settings: to_value(&app_handle.state::<SettingsStore>().get()),
+    history: app_handle.state::<HistoryDb>().all()?,
+  };
+  let history = data.history.len();
+  let encrypted = passphrase.as_deref().is_some_and(|passphrase| !passphrase.is_empty());
+  let bytes = tauri::async_runtime::spawn_blocking(move || encode(&data, passphrase.as_deref()))
+    .await
+    .map_err(|e| e.to_string())??;
+  storage::write_atomic(std::path::Path::new(&path), &bytes)?;
+
+  Ok(ExportSummary {
+    path,
+    encrypted,
+    history,
+    size: bytes.len() as u64,
+  })
+}
+
+/// 从归档导入。`dry_run` 为真时只返回冲突报告而不写入，前端确认后再以相同参数正式导入
+#[tauri::command]
+pub async fn data_import(
+  app_handle: AppHandle,
+  history_db: State<'_, HistoryDb>,
+  path: String,
+  passphrase: Option<String>,
+  strategy: ImportStrategy,
+  dry_run: Option<bool>,
+) -> Result<ImportReport, String> {
+  let bytes = std::fs::read(&path).map_err(|e| format!("Failed to read {path}: {e}"))?;
+  let data = tauri::async_runtime::spawn_blocking(move || decode(&bytes, passphrase.as_deref()))
+    .await
+    .map_err(|e| e.to_string())??;
+  let incoming_settings = archived_settings(&data.settings)?;
+
+  let local_settings = app_handle.state::<SettingsStore>().get();
+  let local_history = history_db.all()?;
+  let mut report = ImportReport {
+    applied: false,
+    app_version: data.app_version.clone(),
+    exported_at: data.exported_at,
+    history_incoming: data.history.len(),
+    history_added: 0,
+    favorites_added: 0,
+    tool_configs_added: 0,
+    conflicts: Vec::new(),
+  };
+
+  let (next_settings, history) = match strategy {
+    ImportStrategy::Merge => (
+      merge_settings(&local_settings, &incoming_settings, &mut report),
+      merge_history(&local_history, &data.history, &mut report),
+    ),
+    ImportStrategy::Replace => {
+      replace_conflicts(&local_settings, &incoming_settings, &mut report);
+      report.history_added = data.history.len();
+      (incoming_settings, data.history.clone())
+    }
+  };
+  if dry_run.unwrap_or(false) {
+    return Ok(report);
+  }
+
+  let value = serde_json::to_value(&next_settings).map_err(|e| e.to_string())?;
+  settings::set(&app_handle, None, value, None)?;
+  // 历史导入在事务中进行，失败时不会留下部分记录；此时把设置恢复为导入前的状态
+  report.history_added = match history_db.import(&history, strategy == ImportStrategy::Replace) {
+    Ok(added) => added,
+    Err(error) => {
+      if let Err(rollback) = settings::set(&app_handle, None, to_value(&local_settings), None) {
+        log::warn!("Failed to restore settings after history import error: {rollback}");
+      }
+      return Err(error);
+    }
+  };
+  report.applied = true;
+  Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::settings::ToolConfig;
+  use serde_json::json;
+
+  fn sample() -> AppData {
+    AppData {
+      app_version: "1.2.3".to_string(),
+      exported_at: 1_700_000_000_000,
+      settings: to_value(&Settings::default()),
+      history: Vec::new(),
+    }
+  }
+
+  fn report() -> ImportReport {
+    ImportReport {
+      applied: false,
+      app_version: String::new(),
+      exported_at: 0,
+      history_incoming: 0,
+      history_added: 0,
+      favorites_added: 0,
+      tool_configs_added: 0,
+      conflicts: Vec::new(),
+    }
+  }
+
+  fn config(value: i64, last_modified: i64) -> ToolConfig {
+    ToolConfig {
+      settings: json!({ "value": value }).as_object().cloned().unwrap(),
+      last_modified,
+    }
+  }
+
+  #[test]
+  fn round_trips_plain_and_encrypted_archives() {
+    let data = sample();
+    let plain = encode(&data, None).unwrap();
+    assert_eq!(plain[MAGIC.len() + 1], 0);
+    assert_eq!(to_value(&decode(&plain, Some("ignored")).unwrap()), to_value(&data));
+    // 空口令等同于不加密
+    assert_eq!(encode(&data, Some("")).unwrap()[MAGIC.len() + 1], 0);
+
+    let encrypted = encode(&data, Some("secret")).unwrap();
+    assert_eq!(encrypted[MAGIC.len() + 1], FLAG_ENCRYPTED);
+    assert_eq!(to_value(&decode(&encrypted, Some("secret")).unwrap()), to_value(&data));
+    assert!(decode(&encrypted, None).unwrap_err().contains("passphrase is required"));
+    assert!(decode(b"not an archive", None).is_err());
+  }
+
+  #[test]
+  fn rejects_wrong_passphrase_and_tampered_header() {
+    let encrypted = encode(&sample(), Some("secret")).unwrap();
+    assert!(decode(&encrypted, Some("wrong")).unwrap_err().contains("Wrong passphrase"));
+
+    // 格式版本与标志位不参与密钥派生，只受附加认证数据保护
+    let mut tampered = encrypted.clone();
+    tampered[MAGIC.len()] = 0;
+    assert!(decode(&tampered, Some("secret")).unwrap_err().contains("corrupted"));
+    let mut tampered = encrypted;
+    tampered[MAGIC.len() + 1] |= 0x80;
+    assert!(decode(&tampered, Some("secret")).unwrap_err().contains("corrupted"));
+  }
+
+  #[test]
+  fn rejects_expensive_key_derivation_parameters() {
+    let encrypted = encode(&sample(), Some("secret")).unwrap();
+    let header_len = MAGIC.len() + 2;
+    for (index, value) in [KDF_MAX_MEMORY_KIB + 1, KDF_MAX_ITERATIONS + 1, KDF_MAX_PARALLELISM + 1]
+      .into_iter()
+      .enumerate()
+    {
+      let mut tampered = encrypted.clone();
+      let start = header_len + index * 4;
+      tampered[start..start + 4].copy_from_slice(&value.to_le_bytes());
+      assert!(decode(&tampered, Some("secret")).unwrap_err().contains("too expensive"));
+    }
+  }
+
+  #[test]
+  fn migrates_archived_settings() {
+    let legacy = json!({
+      "preferences": { "historyLimit": 20, "compactMode": true },
+      "recentTools": [{ "slug": "json-pretty" }],
+      "configs": [{ "toolSlug": "base64-encode", "settings": { "url": true }, "lastModified": 5 }],
+    });
+    let settings = archived_settings(&legacy).unwrap();
+    assert_eq!(settings.version, settings::SETTINGS_VERSION);
+    assert_eq!(settings.preferences.history_limit, 20);
+    assert!(settings.preferences.compact_mode);
+    assert_eq!(settings.recent_tools, ["json-pretty"]);
+    assert_eq!(settings.tool_configs["base64-encode"].last_modified, 5);
+
+    assert!(archived_settings(&json!({ "version": settings::SETTINGS_VERSION + 1 })).is_err());
+  }
+
+  #[test]
+  fn merge_resolves_conflicts_by_last_modified() {
+    let mut local = Settings::default();
+    local.tool_configs.insert("newer-local".into(), config(1, 20));
+    local.tool_configs.insert("newer-incoming".into(), config(1, 10));
+    local.tool_configs.insert("same".into(), config(1, 10));
+    local.favorites = vec!["a".into()];
+
+    let mut incoming = Settings::default();
+    incoming.preferences.compact_mode = true;
+    incoming.tool_configs.insert("newer-local".into(), config(2, 10));
+    incoming.tool_configs.insert("newer-incoming".into(), config(2, 20));
+    incoming.tool_configs.insert("same".into(), config(1, 10));
+    incoming.tool_configs.insert("added".into(), config(3, 1));
+    incoming.favorites = vec!["b".into(), "a".into()];
+
+    let mut report = report();
+    let merged = merge_settings(&local, &incoming, &mut report);
+    assert_eq!(merged.tool_configs["newer-local"], config(1, 20));
+    assert_eq!(merged.tool_configs["newer-incoming"], config(2, 20));
+    assert_eq!(merged.tool_configs["added"], config(3, 1));
+    assert_eq!(merged.favorites, ["a", "b"]);
+    // 偏好没有修改时间，保留本地
+    assert!(!merged.preferences.compact_mode);
+    assert_eq!((report.tool_configs_added, report.favorites_added), (1, 1));
+
+    let resolutions: Vec<(&str, &str, Value)> = report
+      .conflicts
+      .iter()
+      .map(|conflict| (conflict.kind, conflict.key.as_str(), to_value(&conflict.resolution)))
+      .collect();
+    assert_eq!(
+      resolutions,
+      [
+        ("preference", "compact_mode", json!("keep_local")),
+        ("tool_config", "newer-incoming", json!("use_incoming")),
+        ("tool_config", "newer-local", json!("keep_local")),
+      ]
+    );
+  }
+
+  #[test]
+  fn merge_keeps_local_history_on_conflict() {
+    let entry = |id: &str, success| HistoryEntry {
+      id: id.to_string(),
+      tool_slug: "json".to_string(),
+      tool_name: "JSON".to_string(),
+      timestamp: 1,
+      input_data: None,
+      output_data: None,
+      duration: None,
+      success,
+      pinned: false,
+    };
+    let mut report = report();
+    let added = merge_history(
+      &[entry("shared", true), entry("same", true)],
+      &[entry("shared", false), entry("same", true), entry("new", true)],
+      &mut report,
+    );
+    assert_eq!(added, [entry("new", true)]);
+    assert_eq!(report.history_added, 1);
+    assert_eq!(report.conflicts.len(), 1);
+    assert_eq!(report.conflicts[0].key, "shared");
+    assert!(matches!(report.conflicts[0].resolution, ConflictResolution::KeepLocal));
+  }
+}
@@ -0,0 +1,580 @@
+use std::collections::HashSet;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc};
+use std::time::Duration;
+
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, State};
+use walkdir::WalkDir;
+
+use crate::hash::{hash_reader, HashAlgorithm};
+use crate::image_ops::{process_file, ImageOperation};
+use crate::task::TaskRegistry;
+
+const MAX_CONCURRENCY: usize = 16;
+/// JSON 清单每处理这么多个文件落盘一次，CSV 清单逐行追加
+const JSON_FLUSH_EVERY: usize = 20;
+
+/// 批处理可用的原生操作
+#[derive(Deserialize, Clone, Debug)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum BatchOperation {
+  /// 缩放、转换、压缩等图片操作；`extension` 用于转换格式时替换输出文件扩展名
+  Image {
+    operations: Vec<ImageOperation>,
+    extension: Option<String>,
+  },
+  Hash {
+    algorithms: Vec<HashAlgorithm>,
+  },
+  /// 文本转码，编码名称遵循 WHATWG 标签（如 gbk、shift_jis、utf-8）
+  Transcode { from: String, to: String },
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct BatchRequest {
+  pub root: String,
+  #[serde(default)]
+  pub include: Vec<String>,
+  #[serde(default)]
+  pub exclude: Vec<String>,
+  #[serde(default = "default_recursive")]
+  pub recursive: bool,
+  pub operation: BatchOperation,
+  pub output_dir: Option<String>,
+  /// 以 `.csv` 结尾写 CSV，其余写 JSON
+  pub manifest: Option<String>,
+  #[serde(default)]
+  pub resume: bool,
+  pub concurrency: Option<usize>,
+}
+
+fn default_recursive() -> bool {
+  true
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ManifestEntry {
+  pub path: String,
+  pub status: String,
+  pub output: Option<String>,
+  pub detail: Option<String>,
+  pub error: Option<String>,
+}
+
+#[derive(Serialize, Clone)]
+struct BatchItemEvent<'a> {
+  task_id: &'a str,
+  entry: &'a ManifestEntry,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct BatchSummary {
+  pub total: usize,
+  pub succeeded: usize,
+  pub failed: usize,
+  pub skipped: usize,
+  pub cancelled: bool,
+  pub manifest: Option<String>,
+}
+
+fn build_globs(patterns: &[String]) -> Result<Option<GlobSet>, String> {
+  if patterns.is_empty() {
+    return Ok(None);
+  }
+  let mut builder = GlobSetBuilder::new();
+  for pattern in patterns {
+    builder.add(Glob::new(pattern).map_err(|e| format!("Invalid glob {pattern}: {e}"))?);
+  }
+  builder.build().map(Some).map_err(|e| e.to_string())
+}
+
+/// 遍历目录并按 include/exclude 过滤，返回相对路径（统一使用 `/` 分隔）；
+/// `skip` 中的目录或文件（如位于 root 内的输出目录与清单）不参与遍历
+pub fn collect_files(
+  root: &Path,
+  include: &[String],
+  exclude: &[String],
+  recursive: bool,
+  skip: &[PathBuf],
+) -> Result<Vec<String>, String> {
+  let include = build_globs(include)?;
+  let exclude = build_globs(exclude)?;
+  let skip: Vec<PathBuf> = skip
+    .iter()
+    .filter_map(|path| std::fs::canonicalize(path).ok())
+    .collect();
+  let mut walker = WalkDir::new(root).follow_links(false).sort_by_file_name();
+  if !recursive {
+    walker = walker.max_depth(1);
+  }
+
+  let mut files = Vec::new();
+  let walker = walker.into_iter().filter_entry(|entry| {
+    skip.is_empty()
+      || match std::fs::canonicalize(entry.path()) {
+        Ok(path) => !skip.contains(&path),
+        Err(_) => true,
+      }
+  });
+  for entry in walker {
+    let entry = entry.map_err(|e| e.to_string())?;
+    if !entry.file_type().is_file() {
+      continue;
+    }
+    let relative = entry
+      .path()
+      .strip_prefix(root)
+      .map_err(|e| e.to_string())?
+      .to_string_lossy()
+      .replace('\\', "/");
+    if include.as_ref().is_some_and(|set| !set.is_match(&relative)) {
+      continue;
+    }
+    if exclude.as_ref().is_some_and(|set| set.is_match(&relative)) {
+      continue;
+    }
+    files.push(relative);
+  }
+  Ok(files)
+}
+
+fn is_csv(path: &str) -> bool {
+  path.to_ascii_lowercase().ends_with(".csv")
+}
+
+/// 读取已有清单，用于中断后续跑
+pub fn read_manifest(path: &str) -> Result<Vec<ManifestEntry>, String> {
+  if !Path::new(path).exists() {
+    return Ok(Vec::new());
+  }
+  if is_csv(path) {
+    let mut reader = csv::Reader::from_path(path).map_err(|e| e.to_string())?;
+    reader
+      .deserialize()
+      .collect::<Result<Vec<ManifestEntry>, _>>()
+      .map_err(|e| e.to_string())
+  } else {
+    let content = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    serde_json::from_str(&content).map_err(|e| e.to_string())
+  }
+}
+
+struct ManifestWriter {
+  path: Option<String>,
+  entries: Vec<ManifestEntry>,
+  csv: Option<csv::Writer<File>>,
+  pending: usize,
+}
+
+impl ManifestWriter {
+  fn open(path: Option<String>, existing: Vec<ManifestEntry>) -> Result<Self, String> {
+    let mut csv = None;
+    if let Some(path) = path.as_deref().filter(|path| is_csv(path)) {
+      if let Some(parent) = Path::new(path).parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+      }
+      // 续跑时重写整个报告：只保留此前成功的记录，失败记录由本次结果取代
+      let file = File::create(path).map_err(|e| e.to_string())?;
+      let mut writer = csv::Writer::from_writer(file);
+      for entry in &existing {
+        writer.serialize(entry).map_err(|e| e.to_string())?;
+      }
+      writer.flush().map_err(|e| e.to_string())?;
+      csv = Some(writer);
+    }
+    Ok(Self {
+      path,
+      entries: existing,
+      csv,
+      pending: 0,
+    })
+  }
+
+  fn push(&mut self, entry: ManifestEntry) -> Result<(), String> {
+    if let Some(writer) = self.csv.as_mut() {
+      writer.serialize(&entry).map_err(|e| e.to_string())?;
+      writer.flush().map_err(|e| e.to_string())?;
+    }
+    self.entries.push(entry);
+    self.pending += 1;
+    if self.pending >= JSON_FLUSH_EVERY {
+      self.flush_json()?;
+    }
+    Ok(())
+  }
+
+  fn flush_json(&mut self) -> Result<(), String> {
+    self.pending = 0;
+    let Some(path) = self.path.as_deref().filter(|path| !is_csv(path)) else {
+      return Ok(());
+    };
+    if let Some(parent) = Path::new(path).parent() {
+      std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let content = serde_json::to_string_pretty(&self.entries).map_err(|e| e.to_string())?;
+    // 先写临时文件再改名，避免中断时留下半截清单
+    let temp = format!("{path}.tmp");
+    std::fs::write(&temp, content).map_err(|e| e.to_string())?;
+    std::fs::rename(&temp, path).map_err(|e| e.to_string())
+  }
+}
+
+/// 续跑时保留成功记录，失败的文件重新处理；返回保留的记录与待处理文件
+fn plan_resume(files: &[String], existing: Vec<ManifestEntry>) -> (Vec<ManifestEntry>, Vec<String>) {
+  let existing: Vec<ManifestEntry> = existing
+    .into_iter()
+    .filter(|entry| entry.status == "ok")
+    .collect();
+  let done: HashSet<&str> = existing.iter().map(|entry| entry.path.as_str()).collect();
+  let pending = files
+    .iter()
+    .filter(|path| !done.contains(path.as_str()))
+    .cloned()
+    .collect();
+  (existing, pending)
+}
+
+fn output_path(output_dir: Option<&str>, relative: &str, extension: Option<&str>) -> Result<PathBuf, String> {
+  let output_dir = output_dir.ok_or("An output directory is required for this operation")?;
+  let mut path = Path::new(output_dir).join(relative);
+  if let Some(extension) = extension.filter(|ext| !ext.is_empty()) {
+    path.set_extension(extension.trim_start_matches('.'));
+  }
+  Ok(path)
+}
+
+fn transcode(input: &Path, output: &Path, from: &str, to: &str) -> Result<String, String> {
+  let source = encoding_rs::Encoding::for_label(from.as_bytes())
+    .ok_or_else(|| format!("Unknown encoding: {from}"))?;
+  let target = encoding_rs::Encoding::for_label(to.as_bytes())
+    .ok_or_else(|| format!("Unknown encoding: {to}"))?;
+  let data = std::fs::read(input).map_err(|e| e.to_string())?;
+  let (text, _, had_errors) = source.decode(&data);
+  if had_errors {
+    return Err(format!("Input is not valid {}", source.name()));
+  }
+  let (encoded, _, unmappable) = target.encode(&text);
+  if unmappable {
+    return Err(format!("Some characters cannot be represented in {}", target.name()));
+  }
+  if let Some(parent) = output.parent() {
+    std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+  }
+  std::fs::write(output, &encoded).map_err(|e| e.to_string())?;
+  Ok(format!("{} -> {}", source.name(), target.name()))
+}
+
+/// 对单个文件执行批处理操作，返回输出路径与摘要信息
+fn run_one(
+  request: &BatchRequest,
+  relative: &str,
+  stop: &AtomicBool,
+) -> Result<(Option<String>, Option<String>), String> {
+  let input = Path::new(&request.root).join(relative);
+  let output_dir = request.output_dir.as_deref();
+  match &request.operation {
+    BatchOperation::Image {
+      operations,
+      extension,
+    } => {
+      let output = output_path(output_dir, relative, extension.as_deref())?;
+      let result = process_file(
+        &input.to_string_lossy(),
+        &output.to_string_lossy(),
+        operations,
+      )?;
+      Ok((
+        Some(result.output),
+        Some(format!(
+          "{}x{} {}B -> {}x{} {}B",
+          result.before.width,
+          result.before.height,
+          result.before.size,
+          result.after.width,
+          result.after.height,
+          result.after.size
+        )),
+      ))
+    }
+    BatchOperation::Hash { algorithms } => {
+      let file = File::open(&input).map_err(|e| e.to_string())?;
+      let results = hash_reader(file, algorithms, |_| !stop.load(Ordering::Relaxed))?
+        .ok_or("Cancelled")?;
+      let detail = results
+        .iter()
+        .map(|result| {
+          format!(
+            "{}:{}",
+            serde_json::to_value(result.algorithm)
+              .ok()
+              .and_then(|value| value.as_str().map(str::to_string))
+              .unwrap_or_default(),
+            result.hex
+          )
+        })
+        .collect::<Vec<_>>()
+        .join(";");
+      Ok((None, Some(detail)))
+    }
+    BatchOperation::Transcode { from, to } => {
+      let output = output_path(output_dir, relative, None)?;
+      let detail = transcode(&input, &output, from, to)?;
+      Ok((Some(output.to_string_lossy().into_owned()), Some(detail)))
+    }
+  }
+}
+
+/// 批量处理目录：有界工作线程池执行，逐个文件推送 `batch:item` 事件并写入清单
+#[tauri::command]
+pub async fn batch_run(
+  app_handle: AppHandle,
+  tasks: State<'_, TaskRegistry>,
+  task_id: Option<String>,
+  request: BatchRequest,
+) -> Result<BatchSummary, String> {
+  let mut task = tasks.start(task_id, "batch")?;
+  tauri::async_runtime::spawn_blocking(move || {
+    let result = (|| {
+      task.progress("scanning", None);
+      let skip: Vec<PathBuf> = request
+        .output_dir
+        .iter()
+        .chain(request.manifest.iter())
+        .map(PathBuf::from)
+        .collect();
+      let files = collect_files(
+        Path::new(&request.root),
+        &request.include,
+        &request.exclude,
+        request.recursive,
+        &skip,
+      )?;
+
+      let existing = match (&request.manifest, request.resume) {
+        (Some(path), true) => read_manifest(path)?,
+        _ => Vec::new(),
+      };
+      let (existing, pending) = plan_resume(&files, existing);
+      let skipped = files.len() - pending.len();
+      let mut manifest = ManifestWriter::open(request.manifest.clone(), existing)?;
+      if manifest.csv.is_none() {
+        manifest.flush_json()?;
+      }
+
+      let concurrency = request
+        .concurrency
+        .unwrap_or_else(|| {
+          std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(4)
+        })
+        .clamp(1, MAX_CONCURRENCY);
+      let request = Arc::new(request);
+      let pending = Arc::new(pending);
+      let next = Arc::new(AtomicUsize::new(0));
+      let stop = Arc::new(AtomicBool::new(false));
+      let (sender, receiver) = mpsc::channel::<ManifestEntry>();
+
+      let workers: Vec<_> = (0..concurrency.min(pending.len().max(1)))
+        .map(|_| {
+          let request = request.clone();
+          let pending = pending.clone();
+          let next = next.clone();
+          let stop = stop.clone();
+          let sender = sender.clone();
+          std::thread::spawn(move || loop {
+            if stop.load(Ordering::Relaxed) {
+              break;
+            }
+            let index = next.fetch_add(1, Ordering::Relaxed);
+            let Some(relative) = pending.get(index) else {
+              break;
+            };
+            let entry = match run_one(&request, relative, &stop) {
+              Ok((output, detail)) => ManifestEntry {
+                path: relative.clone(),
+                status: "ok".to_string(),
+                output,
+                detail,
+                error: None,
+              },
+              Err(error) => ManifestEntry {
+                path: relative.clone(),
+                status: "error".to_string(),
+                output: None,
+                detail: None,
+                error: Some(error),
+              },
+            };
+            if sender.send(entry).is_err() {
+              break;
+            }
+          })
+        })
+        .collect();
+      drop(sender);
+
+      let task_key = task.id().to_string();
+      let (mut succeeded, mut failed, mut processed) = (0, 0, 0);
+      loop {
+        if task.is_cancelled() {
+          stop.store(true, Ordering::Relaxed);
+        }
+        match receiver.recv_timeout(Duration::from_millis(200)) {
+          Ok(entry) => {
+            processed += 1;
+            // 取消时正在处理的文件会以错误返回，不计入清单以便续跑
+            if stop.load(Ordering::Relaxed) && entry.status != "ok" {
+              continue;
+            }
+            if entry.status == "ok" {
+              succeeded += 1;
+            } else {
+              failed += 1;
+            }
+            let _ = app_handle.emit(
+              "batch:item",
+              BatchItemEvent {
+                task_id: &task_key,
+                entry: &entry,
+              },
+            );
+            manifest.push(entry)?;
+            task.progress_ratio("processing", processed as u64, pending.len() as u64);
+          }
+          Err(mpsc::RecvTimeoutError::Timeout) => {}
+          Err(mpsc::RecvTimeoutError::Disconnected) => break,
+        }
+      }
+      for worker in workers {
+        let _ = worker.join();
+      }
+      manifest.flush_json()?;
+
+      Ok(BatchSummary {
+        total: files.len(),
+        succeeded,
+        failed,
+        skipped,
+        cancelled: stop.load(Ordering::Relaxed),
+        manifest: request.manifest.clone(),
+      })
+    })();
+    task.settle(&result);
+    result
+  })
+  .await
+  .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn touch(root: &Path, relative: &str, content: &[u8]) {
+    let path = root.join(relative);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, content).unwrap();
+  }
+
+  fn entry(path: &str, status: &str) -> ManifestEntry {
+    ManifestEntry {
+      path: path.to_string(),
+      status: status.to_string(),
+      output: None,
+      detail: None,
+      error: (status != "ok").then(|| "failed".to_string()),
+    }
+  }
+
+  fn patterns(values: &[&str]) -> Vec<String> {
+    values.iter().map(|value| value.to_string()).collect()
+  }
+
+  fn request(root: &Path, operation: BatchOperation) -> BatchRequest {
+    BatchRequest {
+      root: root.to_string_lossy().into_owned(),
+      include: Vec::new(),
+      exclude: Vec::new(),
+      recursive: true,
+      operation,
+      output_dir: Some(root.join("out").to_string_lossy().into_owned()),
+      manifest: None,
+      resume: false,
+      concurrency: None,
+    }
+  }
+
+  #[test]
+  fn collects_files_with_globs_and_skip() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    for relative in ["a.png", "b.txt", "nested/c.png", "nested/deep/d.PNG", "out/a.png", "report.json"] {
+      touch(root, relative, b"x");
+    }
+    let skip = [root.join("out"), root.join("report.json")];
+
+    let all = collect_files(root, &[], &[], true, &skip).unwrap();
+    assert_eq!(all, ["a.png", "b.txt", "nested/c.png", "nested/deep/d.PNG"]);
+
+    let png = collect_files(root, &patterns(&["**/*.png"]), &patterns(&["nested/deep/**"]), true, &skip).unwrap();
+    assert_eq!(png, ["a.png", "nested/c.png"]);
+
+    let top = collect_files(root, &patterns(&["*.png"]), &[], false, &skip).unwrap();
+    assert_eq!(top, ["a.png"]);
+
+    assert!(collect_files(root, &patterns(&["[unclosed"]), &[], true, &[]).is_err());
+  }
+
+  #[test]
+  fn resume_keeps_successes_and_retries_failures() {
+    let files = patterns(&["a", "b", "c"]);
+    let (kept, pending) = plan_resume(&files, vec![entry("a", "ok"), entry("b", "error"), entry("gone", "ok")]);
+    assert_eq!(
+      kept.iter().map(|entry| entry.path.as_str()).collect::<Vec<_>>(),
+      ["a", "gone"]
+    );
+    assert_eq!(pending, ["b", "c"]);
+  }
+
+  #[test]
+  fn manifests_round_trip() {
+    let dir = tempfile::tempdir().unwrap();
+    assert!(read_manifest(&dir.path().join("missing.json").to_string_lossy()).unwrap().is_empty());
+
+    for name in ["reports/manifest.json", "reports/manifest.csv"] {
+      let path = dir.path().join(name).to_string_lossy().into_owned();
+      let mut writer = ManifestWriter::open(Some(path.clone()), vec![entry("a", "ok")]).unwrap();
+      writer.push(entry("b", "error")).unwrap();
+      writer.flush_json().unwrap();
+      drop(writer);
+
+      let entries = read_manifest(&path).unwrap();
+      assert_eq!(entries.len(), 2, "{name}");
+      assert_eq!(entries[1].path, "b");
+      assert_eq!(entries[1].error.as_deref(), Some("failed"));
+    }
+  }
+
+  #[test]
+  fn transcodes_into_output_directory() {
+    let dir = tempfile::tempdir().unwrap();
+    let (gbk, _, _) = encoding_rs::GBK.encode("中文");
+    touch(dir.path(), "docs/a.txt", &gbk);
+    let request = request(
+      dir.path(),
+      BatchOperation::Transcode {
+        from: "gbk".to_string(),
+        to: "utf-8".to_string(),
+      },
+    );
+
+    let (output, detail) = run_one(&request, "docs/a.txt", &AtomicBool::new(false)).unwrap();
+    assert_eq!(std::fs::read_to_string(output.unwrap()).unwrap(), "中文");
+    assert_eq!(detail.as_deref(), Some("GBK -> UTF-8"));
+  }
+}
@@ -0,0 +1,353 @@
+use std::fs::File;
+use std::io::Read;
+
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::hash::{hash_reader, HashAlgorithm};
+use crate::image_ops::{process_file, ImageOperation, ResizeFilter};
+use crate::ops::{self, CodecMode};
+
+/// 退出码：成功、操作失败、用法错误
+pub const EXIT_OK: i32 = 0;
+pub const EXIT_FAILURE: i32 = 1;
+pub const EXIT_USAGE: i32 = 2;
+
+const USAGE: &str = "Usage: kit run <operation> [options]
+
+Operations:
+  hash [--algo sha256[,md5,...]] <file>...
+  base64 <encode|decode> [--url-safe] [text]     (reads stdin when text is omitted)
+  url <encode|decode> [text]
+  uuid [--version 4|7] [--count N]
+  json [--minify] [--indent N] [file]
+  timestamp [value]                              (unix seconds/ms, RFC 3339 or YYYY-MM-DD)
+  image [--resize WxH] [--fit] [--crop x,y,w,h] [--rotate deg] [--quality N] [--strip] <input> <output>";
+
+#[derive(Debug)]
+enum CliError {
+  Usage(String),
+  Failed(String),
+}
+
+impl From<String> for CliError {
+  fn from(message: String) -> Self {
+    CliError::Failed(message)
+  }
+}
+
+/// 命令行参数：位置参数与 `--flag value` / `--flag=value` / 布尔开关
+struct Args {
+  positional: Vec<String>,
+  options: Vec<(String, Option<String>)>,
+}
+
+impl Args {
+  /// `switches` 为不带取值的布尔开关；其余选项缺少取值时视为用法错误
+  fn parse(args: &[String], switches: &[&str]) -> Result<Self, CliError> {
+    let mut positional = Vec::new();
+    let mut options = Vec::new();
+    let mut iter = args.iter().peekable();
+    while let Some(arg) = iter.next() {
+      if let Some(flag) = arg.strip_prefix("--") {
+        match flag.split_once('=') {
+          Some((name, value)) => options.push((name.to_string(), Some(value.to_string()))),
+          None if switches.contains(&flag) => options.push((flag.to_string(), None)),
+          None => {
+            let value = iter
+              .next_if(|value| !value.starts_with("--"))
+              .ok_or_else(|| CliError::Usage(format!("Missing value for --{flag}")))?;
+            options.push((flag.to_string(), Some(value.clone())));
+          }
+        }
+      } else {
+        positional.push(arg.clone());
+      }
+    }
+    Ok(Self {
+      positional,
+      options,
+    })
+  }
+
+  fn flag(&self, name: &str) -> bool {
+    self.options.iter().any(|(key, _)| key == name)
+  }
+
+  fn value(&self, name: &str) -> Option<&str> {
+    self
+      .options
+      .iter()
+      .rev()
+      .find(|(key, _)| key == name)
+      .and_then(|(_, value)| value.as_deref())
+  }
+
+  fn parsed<T: std::str::FromStr>(&self, name: &str) -> Result<Option<T>, CliError> {
+    self
+      .value(name)
+      .map(|value| {
+        value
+          .parse()
+          .map_err(|_| CliError::Usage(format!("Invalid value for --{name}: {value}")))
+      })
+      .transpose()
+  }
+}
+
+fn read_stdin() -> Result<String, CliError> {
+  let mut input = String::new();
+  std::io::stdin()
+    .read_to_string(&mut input)
+    .map_err(|e| CliError::Failed(format!("Failed to read stdin: {e}")))?;
+  Ok(input)
+}
+
+fn text_or_stdin(text: Option<&String>) -> Result<String, CliError> {
+  match text {
+    Some(text) => Ok(text.clone()),
+    None => read_stdin(),
+  }
+}
+
+fn codec_mode(value: Option<&String>) -> Result<CodecMode, CliError> {
+  match value.map(String::as_str) {
+    Some("encode") => Ok(CodecMode::Encode),
+    Some("decode") => Ok(CodecMode::Decode),
+    _ => Err(CliError::Usage("Expected 'encode' or 'decode'".to_string())),
+  }
+}
+
+fn to_value<T: Serialize>(value: T) -> Result<Value, CliError> {
+  serde_json::to_value(value).map_err(|e| CliError::Failed(e.to_string()))
+}
+
+fn run_hash(args: &Args) -> Result<Value, CliError> {
+  let algorithms = args
+    .value("algo")
+    .unwrap_or("sha256")
+    .split(',')
+    .map(|name| {
+      serde_json::from_value::<HashAlgorithm>(Value::String(name.trim().to_lowercase()))
+        .map_err(|_| CliError::Usage(format!("Unknown hash algorithm: {name}")))
+    })
+    .collect::<Result<Vec<_>, _>>()?;
+  if args.positional.is_empty() {
+    return Err(CliError::Usage("hash requires at least one file".to_string()));
+  }
+
+  let mut results = Vec::new();
+  for path in &args.positional {
+    let file = File::open(path).map_err(|e| format!("Failed to open {path}: {e}"))?;
+    let hashes = hash_reader(file, &algorithms, |_| true)?.unwrap_or_default();
+    let digests: serde_json::Map<String, Value> = hashes
+      .into_iter()
+      .filter_map(|result| {
+        let name = serde_json::to_value(result.algorithm).ok()?.as_str()?.to_string();
+        Some((name, Value::String(result.hex)))
+      })
+      .collect();
+    results.push(json!({ "file": path, "hashes": digests }));
+  }
+  Ok(Value::Array(results))
+}
+
+fn parse_pair(value: &str, separator: char) -> Option<(u32, u32)> {
+  let (a, b) = value.split_once(separator)?;
+  Some((a.trim().parse().ok()?, b.trim().parse().ok()?))
+}
+
+fn run_image(args: &Args) -> Result<Value, CliError> {
+  let [input, output] = args.positional.as_slice() else {
+    return Err(CliError::Usage("image requires <input> <output>".to_string()));
+  };
+
+  let mut operations = Vec::new();
+  if let Some(crop) = args.value("crop") {
+    let parts: Vec<u32> = crop
+      .split(',')
+      .map(|part| part.trim().parse())
+      .collect::<Result<_, _>>()
+      .map_err(|_| CliError::Usage(format!("Invalid --crop value: {crop}")))?;
+    let [x, y, width, height] = parts.as_slice() else {
+      return Err(CliError::Usage(format!("Invalid --crop value: {crop}")));
+    };
+    operations.push(ImageOperation::Crop {
+      x: *x,
+      y: *y,
+      width: *width,
+      height: *height,
+    });
+  }
+  if let Some(degrees) = args.parsed::<i32>("rotate")? {
+    operations.push(ImageOperation::Rotate { degrees });
+  }
+  if let Some(size) = args.value("resize") {
+    let (width, height) = parse_pair(&size.to_lowercase(), 'x')
+      .ok_or_else(|| CliError::Usage(format!("Invalid --resize value: {size}")))?;
+    operations.push(ImageOperation::Resize {
+      width: (width > 0).then_some(width),
+      height: (height > 0).then_some(height),
+      filter: ResizeFilter::Lanczos,
+      fit: args.flag("fit"),
+    });
+  }
+  if let Some(value) = args.parsed::<u8>("quality")? {
+    operations.push(ImageOperation::Quality { value });
+  }
+  if args.flag("strip") {
+    operations.push(ImageOperation::StripMetadata);
+  }
+
+  to_value(process_file(input, output, &operations)?)
+}
+
+fn run(operation: &str, rest: &[String]) -> Result<Value, CliError> {
+  match operation {
+    "hash" => run_hash(&Args::parse(rest, &[])?),
+    "base64" => {
+      let args = Args::parse(rest, &["url-safe"])?;
+      let mode = codec_mode(args.positional.first())?;
+      let input = text_or_stdin(args.positional.get(1))?;
+      to_value(ops::base64(
+        input.trim_end_matches(['\r', '\n']).as_bytes(),
+        mode,
+        args.flag("url-safe"),
+      )?)
+    }
+    "url" => {
+      let args = Args::parse(rest, &[])?;
+      let mode = codec_mode(args.positional.first())?;
+      let input = text_or_stdin(args.positional.get(1))?;
+      Ok(Value::String(ops::url(input.trim_end_matches(['\r', '\n']), mode)?))
+    }
+    "uuid" => {
+      let args = Args::parse(rest, &[])?;
+      let version = args.parsed::<u8>("version")?.unwrap_or(4);
+      let count = args.parsed::<usize>("count")?.unwrap_or(1);
+      to_value(ops::uuid(version, count)?)
+    }
+    "json" => {
+      let args = Args::parse(rest, &["minify"])?;
+      let input = match args.positional.first() {
+        Some(path) => std::fs::read_to_string(path).map_err(|e| format!("Failed to read {path}: {e}"))?,
+        None => read_stdin()?,
+      };
+      let indent = args.parsed::<usize>("indent")?;
+      Ok(Value::String(ops::json_format(&input, indent, args.flag("minify"))?))
+    }
+    "timestamp" => {
+      let args = Args::parse(rest, &[])?;
+      to_value(ops::timestamp(args.positional.first().map(String::as_str))?)
+    }
+    "image" => run_image(&Args::parse(rest, &["fit", "strip"])?),
+    other => Err(CliError::Usage(format!("Unknown operation: {other}"))),
+  }
+}
+
+/// 若参数为 `kit run ...` 则进入无界面模式并返回退出码，否则返回 None 继续启动 GUI
+pub fn try_run(args: &[String]) -> Option<i32> {
+  if args.get(1).map(String::as_str) != Some("run") {
+    return None;
+  }
+  attach_console();
+
+  let Some(operation) = args.get(2) else {
+    eprintln!("{USAGE}");
+    return Some(EXIT_USAGE);
+  };
+  if operation == "--help" || operation == "-h" || operation == "help" {
+    println!("{USAGE}");
+    return Some(EXIT_OK);
+  }
+
+  let (code, output) = respond(run(operation, &args[3..]));
+  println!("{output}");
+  if code == EXIT_USAGE {
+    eprintln!("{USAGE}");
+  }
+  Some(code)
+}
+
+/// 把执行结果转换为退出码与输出的 JSON
+fn respond(result: Result<Value, CliError>) -> (i32, Value) {
+  match result {
+    Ok(result) => (EXIT_OK, json!({ "ok": true, "result": result })),
+    Err(CliError::Failed(error)) => (EXIT_FAILURE, json!({ "ok": false, "error": error })),
+    Err(CliError::Usage(error)) => (EXIT_USAGE, json!({ "ok": false, "error": error })),
+  }
+}
+
+/// 发布版在 Windows 上使用 GUI 子系统，需要挂接到父进程的控制台才能输出
+#[cfg(windows)]
+fn attach_console() {
+  use windows_sys::Win32::System::Console::{AttachConsole, ATTACH_PARENT_PROCESS};
+  unsafe {
+    AttachConsole(ATTACH_PARENT_PROCESS);
+  }
+}
+
+#[cfg(not(windows))]
+fn attach_console() {}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn strings(args: &[&str]) -> Vec<String> {
+    args.iter().map(|arg| arg.to_string()).collect()
+  }
+
+  fn run_args(args: &[&str]) -> (i32, Value) {
+    respond(run(args[0], &strings(&args[1..])))
+  }
+
+  #[test]
+  fn parses_flags_switches_and_positionals() {
+    let args = Args::parse(
+      &strings(&["in.png", "--resize=10x20", "--fit", "--quality", "80", "out.png", "--quality", "90"]),
+      &["fit"],
+    )
+    .unwrap();
+    assert_eq!(args.positional, ["in.png", "out.png"]);
+    assert_eq!(args.value("resize"), Some("10x20"));
+    assert!(args.flag("fit"));
+    assert!(!args.flag("strip"));
+    // 重复的选项以最后一次为准
+    assert_eq!(args.parsed::<u8>("quality").unwrap(), Some(90));
+    assert!(matches!(args.parsed::<u8>("resize"), Err(CliError::Usage(_))));
+  }
+
+  #[test]
+  fn rejects_options_without_values() {
+    assert!(matches!(Args::parse(&strings(&["--indent"]), &[]), Err(CliError::Usage(_))));
+    assert!(matches!(
+      Args::parse(&strings(&["--algo", "--minify"]), &["minify"]),
+      Err(CliError::Usage(_))
+    ));
+    let args = Args::parse(&strings(&["--rotate", "-90"]), &[]).unwrap();
+    assert_eq!(args.parsed::<i32>("rotate").unwrap(), Some(-90));
+  }
+
+  #[test]
+  fn maps_results_to_exit_codes() {
+    let (code, output) = run_args(&["base64", "encode", "hi"]);
+    assert_eq!(code, EXIT_OK);
+    assert_eq!(output, json!({ "ok": true, "result": "aGk=" }));
+
+    let (code, output) = run_args(&["base64", "decode", "@@@"]);
+    assert_eq!(code, EXIT_FAILURE);
+    assert_eq!(output["ok"], false);
+
+    assert_eq!(run_args(&["bogus"]).0, EXIT_USAGE);
+    assert_eq!(run_args(&["base64", "reverse", "hi"]).0, EXIT_USAGE);
+    assert_eq!(run_args(&["uuid", "--count", "many"]).0, EXIT_USAGE);
+    assert_eq!(run_args(&["json", "--indent"]).0, EXIT_USAGE);
+    assert_eq!(run_args(&["hash", "/nonexistent/kit-cli-test"]).0, EXIT_FAILURE);
+  }
+
+  #[test]
+  fn gui_launch_is_not_handled() {
+    assert_eq!(try_run(&strings(&["kit", "--tool", "json-pretty"])), None);
+  }
+}
@@ -0,0 +1,614 @@
+use std::io::{Read, Write};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, TcpStream, ToSocketAddrs, UdpSocket};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_TIMEOUT_MS: u64 = 5000;
+const MAX_UDP_SIZE: usize = 4096;
+
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum DnsRecordType {
+  A,
+  Aaaa,
+  Mx,
+  Txt,
+  Ns,
+  Cname,
+  Soa,
+  Caa,
+  Srv,
+}
+
+impl DnsRecordType {
+  fn code(self) -> u16 {
+    match self {
+      DnsRecordType::A => 1,
+      DnsRecordType::Ns => 2,
+      DnsRecordType::Cname => 5,
+      DnsRecordType::Soa => 6,
+      DnsRecordType::Mx => 15,
+      DnsRecordType::Txt => 16,
+      DnsRecordType::Aaaa => 28,
+      DnsRecordType::Srv => 33,
+      DnsRecordType::Caa => 257,
+    }
+  }
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum DnsTransport {
+  /// 先走 UDP，响应被截断时自动改用 TCP
+  #[default]
+  Udp,
+  Tcp,
+}
+
+/// 解析后的记录数据，按记录类型区分
+#[derive(Serialize, Clone, PartialEq, Debug)]
+#[serde(tag = "type", content = "data")]
+pub enum DnsRecordData {
+  A(Ipv4Addr),
+  #[serde(rename = "AAAA")]
+  Aaaa(Ipv6Addr),
+  #[serde(rename = "MX")]
+  Mx { preference: u16, exchange: String },
+  #[serde(rename = "TXT")]
+  Txt(Vec<String>),
+  #[serde(rename = "NS")]
+  Ns(String),
+  #[serde(rename = "CNAME")]
+  Cname(String),
+  #[serde(rename = "SOA")]
+  Soa {
+    mname: String,
+    rname: String,
+    serial: u32,
+    refresh: u32,
+    retry: u32,
+    expire: u32,
+    minimum: u32,
+  },
+  #[serde(rename = "CAA")]
+  Caa { flags: u8, tag: String, value: String },
+  #[serde(rename = "SRV")]
+  Srv {
+    priority: u16,
+    weight: u16,
+    port: u16,
+    target: String,
+  },
+  Unknown { code: u16, hex: String },
+}
+
+#[derive(Serialize, Clone, PartialEq, Debug)]
+pub struct DnsRecord {
+  pub name: String,
+  pub ttl: u32,
+  #[serde(flatten)]
+  pub data: DnsRecordData,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct DnsResponse {
+  pub server: String,
+  pub rcode: String,
+  pub truncated: bool,
+  pub via_tcp: bool,
+  pub answers: Vec<DnsRecord>,
+  pub authorities: Vec<DnsRecord>,
+  pub elapsed_ms: u64,
+}
+
+/// 构造标准递归查询报文
+pub fn build_query(id: u16, name: &str, record_type: DnsRecordType) -> Result<Vec<u8>, String> {
+  let mut packet = Vec::with_capacity(512);
+  packet.extend_from_slice(&id.to_be_bytes());
+  // RD = 1
+  packet.extend_from_slice(&0x0100_u16.to_be_bytes());
+  packet.extend_from_slice(&1_u16.to_be_bytes());
+  packet.extend_from_slice(&[0, 0, 0, 0, 0, 0]);
+
+  for label in name.trim_end_matches('.').split('.') {
+    if label.is_empty() {
+      if name.trim_end_matches('.').is_empty() {
+        break;
+      }
+      return Err(format!("Invalid domain name: {name}"));
+    }
+    if label.len() > 63 {
+      return Err(format!("Label too long in domain name: {label}"));
+    }
+    packet.push(label.len() as u8);
+    packet.extend_from_slice(label.as_bytes());
+  }
+  packet.push(0);
+  packet.extend_from_slice(&record_type.code().to_be_bytes());
+  // IN
+  packet.extend_from_slice(&1_u16.to_be_bytes());
+  Ok(packet)
+}
+
+struct Reader<'a> {
+  packet: &'a [u8],
+  pos: usize,
+}
+
+impl<'a> Reader<'a> {
+  fn u8(&mut self) -> Result<u8, String> {
+    let value = *self.packet.get(self.pos).ok_or("Unexpected end of DNS message")?;
+    self.pos += 1;
+    Ok(value)
+  }
+
+  fn u16(&mut self) -> Result<u16, String> {
+    Ok(u16::from_be_bytes([self.u8()?, self.u8()?]))
+  }
+
+  fn u32(&mut self) -> Result<u32, String> {
+    Ok(u32::from_be_bytes([self.u8()?, self.u8()?, self.u8()?, self.u8()?]))
+  }
+
+  fn bytes(&mut self, len: usize) -> Result<&'a [u8], String> {
+    let end = self.pos + len;
+    let slice = self.packet.get(self.pos..end).ok_or("Unexpected end of DNS message")?;
+    self.pos = end;
+    Ok(slice)
+  }
+
+  /// 读取域名，支持压缩指针
+  fn name(&mut self) -> Result<String, String> {
+    let mut labels: Vec<String> = Vec::new();
+    let mut pos = self.pos;
+    let mut jumped = false;
+    let mut jumps = 0;
+
+    loop {
+      let len = *self.packet.get(pos).ok_or("Unexpected end of DNS message")? as usize;
+      if len & 0xC0 == 0xC0 {
+        let next = *self.packet.get(pos + 1).ok_or("Unexpected end of DNS message")? as usize;
+        if !jumped {
+          self.pos = pos + 2;
+        }
+        jumped = true;
+        jumps += 1;
+        if jumps > 32 {
+          return Err("Too many compression pointers in DNS message".to_string());
+        }
+        pos = ((len & 0x3F) << 8) | next;
+        continue;
+      }
+      pos += 1;
+      if len == 0 {
+        break;
+      }
+      let label = self
+        .packet
+        .get(pos..pos + len)
+        .ok_or("Unexpected end of DNS message")?;
+      labels.push(String::from_utf8_lossy(label).into_owned());
+      pos += len;
+    }
+
+    if !jumped {
+      self.pos = pos;
+    }
+    Ok(if labels.is_empty() {
+      ".".to_string()
+    } else {
+      labels.join(".")
+    })
+  }
+}
+
+fn rcode_name(code: u16) -> String {
+  match code {
+    0 => "NOERROR".to_string(),
+    1 => "FORMERR".to_string(),
+    2 => "SERVFAIL".to_string(),
+    3 => "NXDOMAIN".to_string(),
+    4 => "NOTIMP".to_string(),
+    5 => "REFUSED".to_string(),
+    other => format!("RCODE{other}"),
+  }
+}
+
+fn parse_record_data(reader: &mut Reader, code: u16, len: usize) -> Result<DnsRecordData, String> {
+  let end = reader.pos + len;
+  let data = match code {
+    1 if len == 4 => {
+      let b = reader.bytes(4)?;
+      DnsRecordData::A(Ipv4Addr::new(b[0], b[1], b[2], b[3]))
+    }
+    28 if len == 16 => {
+      let mut octets = [0_u8; 16];
+      octets.copy_from_slice(reader.bytes(16)?);
+      DnsRecordData::Aaaa(Ipv6Addr::from(octets))
+    }
+    2 => DnsRecordData::Ns(reader.name()?),
+    5 => DnsRecordData::Cname(reader.name()?),
+    6 => DnsRecordData::Soa {
+      mname: reader.name()?,
+      rname: reader.name()?,
+      serial: reader.u32()?,
+      refresh: reader.u32()?,
+      retry: reader.u32()?,
+      expire: reader.u32()?,
+      minimum: reader.u32()?,
+    },
+    15 => DnsRecordData::Mx {
+      preference: reader.u16()?,
+      exchange: reader.name()?,
+    },
+    16 => {
+      let mut strings = Vec::new();
+      while reader.pos < end {
+        let size = reader.u8()? as usize;
+        strings.push(String::from_utf8_lossy(reader.bytes(size)?).into_owned());
+      }
+      DnsRecordData::Txt(strings)
+    }
+    33 => DnsRecordData::Srv {
+      priority: reader.u16()?,
+      weight: reader.u16()?,
+      port: reader.u16()?,
+      target: reader.name()?,
+    },
+    257 if len >= 2 => {
+      let flags = reader.u8()?;
+      let tag_len = reader.u8()? as usize;
+      let tag = String::from_utf8_lossy(reader.bytes(tag_len)?).into_owned();
+      let value_len = end.checked_sub(reader.pos).ok_or("Malformed CAA record")?;
+      let value = String::from_utf8_lossy(reader.bytes(value_len)?).into_owned();
+      DnsRecordData::Caa { flags, tag, value }
+    }
+    _ => DnsRecordData::Unknown {
+      code,
+      hex: hex::encode(reader.bytes(len)?),
+    },
+  };
+  reader.pos = end;
+  Ok(data)
+}
+
+fn parse_records(reader: &mut Reader, count: u16) -> Result<Vec<DnsRecord>, String> {
+  let mut records = Vec::with_capacity(count as usize);
+  for _ in 0..count {
+    let name = reader.name()?;
+    let code = reader.u16()?;
+    let _class = reader.u16()?;
+    let ttl = reader.u32()?;
+    let len = reader.u16()? as usize;
+    let data = parse_record_data(reader, code, len)?;
+    records.push(DnsRecord { name, ttl, data });
+  }
+  Ok(records)
+}
+
+/// 解析响应报文，校验事务 id
+pub fn parse_response(
+  packet: &[u8],
+  expected_id: u16,
+) -> Result<(String, bool, Vec<DnsRecord>, Vec<DnsRecord>), String> {
+  let mut reader = Reader { packet, pos: 0 };
+  let id = reader.u16()?;
+  if id != expected_id {
+    return Err("DNS response id does not match the query".to_string());
+  }
+  let flags = reader.u16()?;
+  let question_count = reader.u16()?;
+  let answer_count = reader.u16()?;
+  let authority_count = reader.u16()?;
+  let _additional_count = reader.u16()?;
+
+  for _ in 0..question_count {
+    reader.name()?;
+    reader.u32()?;
+  }
+
+  let truncated = flags & 0x0200 != 0;
+  // 截断的报文可能只包含部分记录，交给调用方改用 TCP 重试
+  if truncated {
+    return Ok((rcode_name(flags & 0x000F), true, Vec::new(), Vec::new()));
+  }
+  let answers = parse_records(&mut reader, answer_count)?;
+  let authorities = parse_records(&mut reader, authority_count)?;
+  Ok((rcode_name(flags & 0x000F), false, answers, authorities))
+}
+
+/// 解析 "8.8.8.8"、"[::1]:5353"、"127.0.0.1:5353" 等服务器写法，缺省端口 53
+pub fn parse_server(server: &str) -> Result<SocketAddr, String> {
+  let server = server.trim();
+  if let Ok(ip) = server.parse::<IpAddr>() {
+    return Ok(SocketAddr::new(ip, 53));
+  }
+  if let Ok(addr) = server.parse::<SocketAddr>() {
+    return Ok(addr);
+  }
+  let with_port = if server.contains(':') {
+    server.to_string()
+  } else {
+    format!("{server}:53")
+  };
+  with_port
+    .to_socket_addrs()
+    .map_err(|e| format!("Invalid DNS server {server}: {e}"))?
+    .next()
+    .ok_or_else(|| format!("Invalid DNS server {server}"))
+}
+
+/// UDP 查询；事务 id 不匹配的报文（迟到的旧响应或伪造报文）直接丢弃，继续等待直到超时
+fn query_udp(addr: SocketAddr, query: &[u8], id: u16, timeout: Duration) -> Result<Vec<u8>, String> {
+  let bind: SocketAddr = if addr.is_ipv4() {
+    "0.0.0.0:0".parse().unwrap()
+  } else {
+    "[::]:0".parse().unwrap()
+  };
+  let socket = UdpSocket::bind(bind).map_err(|e| e.to_string())?;
+  socket.connect(addr).map_err(|e| e.to_string())?;
+  socket.send(query).map_err(|e| e.to_string())?;
+  let deadline = Instant::now() + timeout;
+  let mut buffer = vec![0_u8; MAX_UDP_SIZE];
+  loop {
+    let remaining = deadline
+      .checked_duration_since(Instant::now())
+      .filter(|remaining| !remaining.is_zero())
+      .ok_or("DNS query over UDP timed out")?;
+    socket.set_read_timeout(Some(remaining)).map_err(|e| e.to_string())?;
+    let size = socket
+      .recv(&mut buffer)
+      .map_err(|e| format!("DNS query over UDP failed: {e}"))?;
+    if size >= 2 && u16::from_be_bytes([buffer[0], buffer[1]]) == id {
+      buffer.truncate(size);
+      return Ok(buffer);
+    }
+  }
+}
+
+fn query_tcp(addr: SocketAddr, query: &[u8], timeout: Duration) -> Result<Vec<u8>, String> {
+  let mut stream = TcpStream::connect_timeout(&addr, timeout).map_err(|e| e.to_string())?;
+  stream.set_read_timeout(Some(timeout)).map_err(|e| e.to_string())?;
+  stream.set_write_timeout(Some(timeout)).map_err(|e| e.to_string())?;
+  stream
+    .write_all(&(query.len() as u16).to_be_bytes())
+    .and_then(|_| stream.write_all(query))
+    .map_err(|e| format!("DNS query over TCP failed: {e}"))?;
+  let mut len = [0_u8; 2];
+  stream
+    .read_exact(&mut len)
+    .map_err(|e| format!("DNS query over TCP failed: {e}"))?;
+  let mut buffer = vec![0_u8; u16::from_be_bytes(len) as usize];
+  stream
+    .read_exact(&mut buffer)
+    .map_err(|e| format!("DNS query over TCP failed: {e}"))?;
+  Ok(buffer)
+}
+
+/// 向指定服务器发起一次查询（阻塞）
+pub fn resolve(
+  server: &str,
+  name: &str,
+  record_type: DnsRecordType,
+  transport: DnsTransport,
+  timeout: Duration,
+) -> Result<DnsResponse, String> {
+  if timeout.is_zero() {
+    return Err("Timeout must be greater than zero".to_string());
+  }
+  let addr = parse_server(server)?;
+  let id = SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map(|d| d.subsec_nanos() as u16)
+    .unwrap_or(0)
+    ^ std::process::id() as u16;
+  let query = build_query(id, name, record_type)?;
+  let started = Instant::now();
+
+  let mut via_tcp = transport == DnsTransport::Tcp;
+  let mut packet = if via_tcp {
+    query_tcp(addr, &query, timeout)?
+  } else {
+    query_udp(addr, &query, id, timeout)?
+  };
+  let mut parsed = parse_response(&packet, id)?;
+  if parsed.1 && !via_tcp {
+    via_tcp = true;
+    packet = query_tcp(addr, &query, timeout)?;
+    parsed = parse_response(&packet, id)?;
+  }
+
+  let (rcode, truncated, answers, authorities) = parsed;
+  Ok(DnsResponse {
+    server: addr.to_string(),
+    rcode,
+    truncated,
+    via_tcp,
+    answers,
+    authorities,
+    elapsed_ms: started.elapsed().as_millis() as u64,
+  })
+}
+
+/// DNS 查询，供 dns-lookup 工具使用
+#[tauri::command]
+pub async fn dns_query(
+  server: String,
+  name: String,
+  record_type: DnsRecordType,
+  transport: Option<DnsTransport>,
+  timeout_ms: Option<u64>,
+) -> Result<DnsResponse, String> {
+  let timeout = Duration::from_millis(timeout_ms.unwrap_or(DEFAULT_TIMEOUT_MS));
+  tauri::async_runtime::spawn_blocking(move || {
+    resolve(
+      &server,
+      &name,
+      record_type,
+      transport.unwrap_or_default(),
+      timeout,
+    )
+  })
+  .await
+  .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::net::TcpListener;
+  use std::thread::JoinHandle;
+
+  /// 按查询构造一条 A 记录响应；`truncated` 时只返回带 TC 标志的空响应
+  fn stub_response(query: &[u8], id: u16, ip: Ipv4Addr, truncated: bool) -> Vec<u8> {
+    let mut packet = Vec::new();
+    packet.extend_from_slice(&id.to_be_bytes());
+    let flags: u16 = if truncated { 0x8380 } else { 0x8180 };
+    packet.extend_from_slice(&flags.to_be_bytes());
+    packet.extend_from_slice(&1_u16.to_be_bytes());
+    packet.extend_from_slice(&(if truncated { 0_u16 } else { 1 }).to_be_bytes());
+    packet.extend_from_slice(&[0, 0, 0, 0]);
+    packet.extend_from_slice(&query[12..]);
+    if !truncated {
+      // 指向问题区域中的域名
+      packet.extend_from_slice(&[0xC0, 0x0C]);
+      packet.extend_from_slice(&1_u16.to_be_bytes());
+      packet.extend_from_slice(&1_u16.to_be_bytes());
+      packet.extend_from_slice(&300_u32.to_be_bytes());
+      packet.extend_from_slice(&4_u16.to_be_bytes());
+      packet.extend_from_slice(&ip.octets());
+    }
+    packet
+  }
+
+  fn query_id(query: &[u8]) -> u16 {
+    u16::from_be_bytes([query[0], query[1]])
+  }
+
+  /// UDP 桩服务：先回一个 id 错误的报文，再回正确的响应
+  fn udp_stub(socket: UdpSocket, ip: Ipv4Addr, truncated: bool) -> JoinHandle<()> {
+    std::thread::spawn(move || {
+      let mut buffer = [0_u8; 512];
+      let (size, peer) = socket.recv_from(&mut buffer).unwrap();
+      let query = &buffer[..size];
+      let id = query_id(query);
+      socket
+        .send_to(&stub_response(query, id.wrapping_add(1), Ipv4Addr::new(6, 6, 6, 6), false), peer)
+        .unwrap();
+      socket.send_to(&stub_response(query, id, ip, truncated), peer).unwrap();
+    })
+  }
+
+  fn tcp_stub(listener: TcpListener, ip: Ipv4Addr) -> JoinHandle<()> {
+    std::thread::spawn(move || {
+      let (mut stream, _) = listener.accept().unwrap();
+      let mut len = [0_u8; 2];
+      stream.read_exact(&mut len).unwrap();
+      let mut query = vec![0_u8; u16::from_be_bytes(len) as usize];
+      stream.read_exact(&mut query).unwrap();
+      let response = stub_response(&query, query_id(&query), ip, false);
+      stream.write_all(&(response.len() as u16).to_be_bytes()).unwrap();
+      stream.write_all(&response).unwrap();
+    })
+  }
+
+  fn first_a(response: &DnsResponse) -> Option<Ipv4Addr> {
+    response.answers.iter().find_map(|record| match record.data {
+      DnsRecordData::A(ip) => Some(ip),
+      _ => None,
+    })
+  }
+
+  #[test]
+  fn udp_ignores_mismatched_ids() {
+    let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let server = socket.local_addr().unwrap().to_string();
+    let stub = udp_stub(socket, Ipv4Addr::new(10, 0, 0, 1), false);
+
+    let response = resolve(
+      &server,
+      "example.com",
+      DnsRecordType::A,
+      DnsTransport::Udp,
+      Duration::from_secs(2),
+    )
+    .unwrap();
+    stub.join().unwrap();
+    assert!(!response.via_tcp);
+    assert_eq!(response.rcode, "NOERROR");
+    assert_eq!(response.answers[0].name, "example.com");
+    assert_eq!(first_a(&response), Some(Ipv4Addr::new(10, 0, 0, 1)));
+  }
+
+  #[test]
+  fn tcp_query() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let server = listener.local_addr().unwrap().to_string();
+    let stub = tcp_stub(listener, Ipv4Addr::new(10, 0, 0, 2));
+
+    let response = resolve(
+      &server,
+      "example.com",
+      DnsRecordType::A,
+      DnsTransport::Tcp,
+      Duration::from_secs(2),
+    )
+    .unwrap();
+    stub.join().unwrap();
+    assert!(response.via_tcp);
+    assert_eq!(first_a(&response), Some(Ipv4Addr::new(10, 0, 0, 2)));
+  }
+
+  #[test]
+  fn truncated_udp_falls_back_to_tcp() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let socket = UdpSocket::bind(addr).unwrap();
+    let udp = udp_stub(socket, Ipv4Addr::new(10, 0, 0, 3), true);
+    let tcp = tcp_stub(listener, Ipv4Addr::new(10, 0, 0, 4));
+
+    let response = resolve(
+      &addr.to_string(),
+      "example.com",
+      DnsRecordType::A,
+      DnsTransport::Udp,
+      Duration::from_secs(2),
+    )
+    .unwrap();
+    udp.join().unwrap();
+    tcp.join().unwrap();
+    assert!(response.via_tcp);
+    assert!(!response.truncated);
+    assert_eq!(first_a(&response), Some(Ipv4Addr::new(10, 0, 0, 4)));
+  }
+
+  #[test]
+  fn udp_times_out_without_matching_reply() {
+    let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let server = socket.local_addr().unwrap().to_string();
+    let error = resolve(
+      &server,
+      "example.com",
+      DnsRecordType::A,
+      DnsTransport::Udp,
+      Duration::from_millis(200),
+    )
+    .unwrap_err();
+    assert!(error.contains("UDP"), "{error}");
+    drop(socket);
+  }
+
+  #[test]
+  fn zero_timeout_is_rejected() {
+    let error = resolve(
+      "127.0.0.1:53",
+      "example.com",
+      DnsRecordType::A,
+      DnsTransport::Udp,
+      Duration::ZERO,
+    )
+    .unwrap_err();
+    assert!(error.contains("Timeout"));
+  }
+}
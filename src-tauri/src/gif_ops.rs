@@ -0,0 +1,431 @@
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use gif::{ColorOutput, DecodeOptions, DisposalMethod, Encoder, Frame, Repeat};
+use image::imageops::FilterType;
+use image::RgbaImage;
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::task::{TaskHandle, TaskRegistry};
+
+/// NeuQuant 量化速度（1 最慢质量最好，30 最快）
+const DEFAULT_QUANTIZE_SPEED: i32 = 10;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum FrameDisposal {
+  Any,
+  Keep,
+  Background,
+  Previous,
+}
+
+impl From<DisposalMethod> for FrameDisposal {
+  fn from(method: DisposalMethod) -> Self {
+    match method {
+      DisposalMethod::Any => FrameDisposal::Any,
+      DisposalMethod::Keep => FrameDisposal::Keep,
+      DisposalMethod::Background => FrameDisposal::Background,
+      DisposalMethod::Previous => FrameDisposal::Previous,
+    }
+  }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct GifInfo {
+  pub width: u16,
+  pub height: u16,
+  pub frame_count: usize,
+  /// 0 表示无限循环，None 表示只播放一次
+  pub loop_count: Option<u16>,
+  pub duration_ms: u64,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct GifFrameInfo {
+  pub index: usize,
+  pub path: String,
+  pub delay_ms: u32,
+  pub disposal: FrameDisposal,
+  pub left: u16,
+  pub top: u16,
+  pub width: u16,
+  pub height: u16,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct GifSplitResult {
+  pub output_dir: String,
+  pub info: GifInfo,
+  pub frames: Vec<GifFrameInfo>,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct GifAssembleFrame {
+  pub path: String,
+  pub delay_ms: u32,
+}
+
+fn open_decoder(path: &str) -> Result<gif::Decoder<BufReader<File>>, String> {
+  let file = File::open(path).map_err(|e| format!("Failed to open {path}: {e}"))?;
+  let mut options = DecodeOptions::new();
+  options.set_color_output(ColorOutput::RGBA);
+  options
+    .read_info(BufReader::new(file))
+    .map_err(|e| format!("Failed to decode {path}: {e}"))
+}
+
+fn repeat_to_loop_count(repeat: Repeat) -> Option<u16> {
+  match repeat {
+    Repeat::Infinite => Some(0),
+    Repeat::Finite(0) => None,
+    Repeat::Finite(count) => Some(count),
+  }
+}
+
+/// 读取 GIF 概要信息；逐帧解码但不保留像素
+pub fn info(path: &str) -> Result<GifInfo, String> {
+  let mut decoder = open_decoder(path)?;
+  let mut frame_count = 0;
+  let mut duration_ms = 0_u64;
+  while let Some(frame) = decoder.next_frame_info().map_err(|e| e.to_string())? {
+    frame_count += 1;
+    duration_ms += frame.delay as u64 * 10;
+  }
+  Ok(GifInfo {
+    width: decoder.width(),
+    height: decoder.height(),
+    frame_count,
+    loop_count: repeat_to_loop_count(decoder.repeat()),
+    duration_ms,
+  })
+}
+
+fn default_output_dir(path: &str) -> PathBuf {
+  let stem = Path::new(path)
+    .file_stem()
+    .map(|stem| stem.to_string_lossy().into_owned())
+    .unwrap_or_else(|| "gif".to_string());
+  let stamp = SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map(|d| d.as_millis())
+    .unwrap_or(0);
+  std::env::temp_dir().join(format!("kit-gif-{stem}-{stamp}"))
+}
+
+/// 把帧矩形内的像素合成到画布上，透明像素保留画布原值
+fn blit(canvas: &mut RgbaImage, frame: &Frame, canvas_width: u32, canvas_height: u32) {
+  for y in 0..frame.height as u32 {
+    for x in 0..frame.width as u32 {
+      let (cx, cy) = (frame.left as u32 + x, frame.top as u32 + y);
+      if cx >= canvas_width || cy >= canvas_height {
+        continue;
+      }
+      let offset = ((y * frame.width as u32 + x) * 4) as usize;
+      let pixel = &frame.buffer[offset..offset + 4];
+      if pixel[3] != 0 {
+        canvas.put_pixel(cx, cy, image::Rgba([pixel[0], pixel[1], pixel[2], pixel[3]]));
+      }
+    }
+  }
+}
+
+fn clear_rect(canvas: &mut RgbaImage, frame: &Frame, canvas_width: u32, canvas_height: u32) {
+  for y in frame.top as u32..(frame.top as u32 + frame.height as u32).min(canvas_height) {
+    for x in frame.left as u32..(frame.left as u32 + frame.width as u32).min(canvas_width) {
+      canvas.put_pixel(x, y, image::Rgba([0, 0, 0, 0]));
+    }
+  }
+}
+
+/// 拆分 GIF 为 PNG 帧。合成模式下每帧都是完整画面（已应用 disposal），
+/// 否则只输出帧矩形本身；帧逐个写入磁盘，内存中最多保留两张画布
+pub fn split(
+  path: &str,
+  output_dir: Option<PathBuf>,
+  composite: bool,
+  task: &mut TaskHandle,
+) -> Result<GifSplitResult, String> {
+  let summary = info(path)?;
+  let output_dir = output_dir.unwrap_or_else(|| default_output_dir(path));
+  std::fs::create_dir_all(&output_dir).map_err(|e| e.to_string())?;
+
+  let mut decoder = open_decoder(path)?;
+  let (width, height) = (decoder.width() as u32, decoder.height() as u32);
+  let mut canvas = RgbaImage::new(width, height);
+  let mut frames = Vec::with_capacity(summary.frame_count);
+
+  while let Some(frame) = decoder.read_next_frame().map_err(|e| e.to_string())? {
+    if task.is_cancelled() {
+      return Err("Cancelled".to_string());
+    }
+    let index = frames.len();
+    task.progress_ratio("decoding", index as u64, summary.frame_count as u64);
+    let frame_path = output_dir.join(format!("frame-{:05}.png", index + 1));
+
+    if composite {
+      let previous = (frame.dispose == DisposalMethod::Previous).then(|| canvas.clone());
+      blit(&mut canvas, frame, width, height);
+      canvas.save(&frame_path).map_err(|e| e.to_string())?;
+      match frame.dispose {
+        DisposalMethod::Background => clear_rect(&mut canvas, frame, width, height),
+        DisposalMethod::Previous => {
+          if let Some(previous) = previous {
+            canvas = previous;
+          }
+        }
+        _ => {}
+      }
+    } else {
+      let image = RgbaImage::from_raw(
+        frame.width as u32,
+        frame.height as u32,
+        frame.buffer.to_vec(),
+      )
+      .ok_or("Invalid frame buffer")?;
+      image.save(&frame_path).map_err(|e| e.to_string())?;
+    }
+
+    frames.push(GifFrameInfo {
+      index,
+      path: frame_path.to_string_lossy().into_owned(),
+      delay_ms: frame.delay as u32 * 10,
+      disposal: frame.dispose.into(),
+      left: frame.left,
+      top: frame.top,
+      width: frame.width,
+      height: frame.height,
+    });
+  }
+
+  Ok(GifSplitResult {
+    output_dir: output_dir.to_string_lossy().into_owned(),
+    info: summary,
+    frames,
+  })
+}
+
+/// 拆分 GIF 为 PNG 帧，默认输出合成后的完整画面
+#[tauri::command]
+pub async fn gif_split(
+  tasks: State<'_, TaskRegistry>,
+  task_id: Option<String>,
+  path: String,
+  output_dir: Option<String>,
+  composite: Option<bool>,
+) -> Result<GifSplitResult, String> {
+  let composite = composite.unwrap_or(true);
+  let mut task = tasks.start(task_id, "gif_split")?;
+  tauri::async_runtime::spawn_blocking(move || {
+    let result = split(&path, output_dir.map(PathBuf::from), composite, &mut task);
+    task.settle(&result);
+    result
+  })
+  .await
+  .map_err(|e| e.to_string())?
+}
+
+/// 由图片帧合成 GIF；各帧逐个读入并量化，尺寸与首帧不一致时缩放到首帧大小
+pub fn assemble(
+  frames: &[GifAssembleFrame],
+  output: &str,
+  loop_count: Option<u16>,
+  speed: i32,
+  task: &mut TaskHandle,
+) -> Result<GifInfo, String> {
+  if frames.is_empty() {
+    return Err("No frames to assemble".to_string());
+  }
+  let load = |path: &str| {
+    image::open(path)
+      .map(|image| image.to_rgba8())
+      .map_err(|e| format!("Failed to open {path}: {e}"))
+  };
+  let first = load(&frames[0].path)?;
+  let (width, height) = first.dimensions();
+  if width > u16::MAX as u32 || height > u16::MAX as u32 {
+    return Err("Frame is too large for GIF".to_string());
+  }
+
+  if let Some(parent) = Path::new(output).parent() {
+    std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+  }
+  let file = File::create(output).map_err(|e| format!("Failed to create {output}: {e}"))?;
+  let mut encoder = Encoder::new(BufWriter::new(file), width as u16, height as u16, &[])
+    .map_err(|e| e.to_string())?;
+  let repeat = match loop_count {
+    None => Repeat::Finite(0),
+    Some(0) => Repeat::Infinite,
+    Some(count) => Repeat::Finite(count),
+  };
+  encoder.set_repeat(repeat).map_err(|e| e.to_string())?;
+
+  let mut duration_ms = 0_u64;
+  let mut first = Some(first);
+  for (index, item) in frames.iter().enumerate() {
+    if task.is_cancelled() {
+      return Err("Cancelled".to_string());
+    }
+    task.progress_ratio("encoding", index as u64, frames.len() as u64);
+
+    let mut pixels = match first.take() {
+      Some(image) => image,
+      None => {
+        let image = load(&item.path)?;
+        if image.dimensions() == (width, height) {
+          image
+        } else {
+          image::imageops::resize(&image, width, height, FilterType::Lanczos3)
+        }
+      }
+    }
+    .into_raw();
+    let mut frame = Frame::from_rgba_speed(width as u16, height as u16, &mut pixels, speed);
+    // GIF 延时单位为 1/100 秒
+    frame.delay = (item.delay_ms / 10).min(u16::MAX as u32) as u16;
+    frame.dispose = DisposalMethod::Background;
+    encoder.write_frame(&frame).map_err(|e| e.to_string())?;
+    duration_ms += frame.delay as u64 * 10;
+  }
+
+  Ok(GifInfo {
+    width: width as u16,
+    height: height as u16,
+    frame_count: frames.len(),
+    loop_count: repeat_to_loop_count(repeat),
+    duration_ms,
+  })
+}
+
+/// 由图片帧合成 GIF
+#[tauri::command]
+pub async fn gif_assemble(
+  tasks: State<'_, TaskRegistry>,
+  task_id: Option<String>,
+  frames: Vec<GifAssembleFrame>,
+  output: String,
+  loop_count: Option<u16>,
+  quantize_speed: Option<i32>,
+) -> Result<GifInfo, String> {
+  if frames.is_empty() {
+    return Err("No frames to assemble".to_string());
+  }
+  let speed = quantize_speed.unwrap_or(DEFAULT_QUANTIZE_SPEED).clamp(1, 30);
+  let mut task = tasks.start(task_id, "gif_assemble")?;
+  tauri::async_runtime::spawn_blocking(move || {
+    let result = assemble(&frames, &output, loop_count, speed, &mut task);
+    task.settle(&result);
+    result
+  })
+  .await
+  .map_err(|e| e.to_string())?
+}
+
+/// 读取 GIF 信息
+#[tauri::command]
+pub async fn gif_info(path: String) -> Result<GifInfo, String> {
+  tauri::async_runtime::spawn_blocking(move || info(&path))
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::task::{TaskEmitter, TaskInfo};
+  use std::sync::Arc;
+
+  struct Silent;
+
+  impl TaskEmitter for Silent {
+    fn emit_progress(&self, _: &TaskInfo) {}
+  }
+
+  fn task() -> TaskHandle {
+    TaskRegistry::new(Arc::new(Silent)).start(None, "test").unwrap()
+  }
+
+  fn solid(width: u16, height: u16, rgba: [u8; 4]) -> Vec<u8> {
+    rgba.repeat(width as usize * height as usize)
+  }
+
+  /// 4x4 红色首帧，第二帧只在右下角覆盖 2x2 蓝色区域
+  fn write_source(path: &Path) {
+    let file = File::create(path).unwrap();
+    let mut encoder = Encoder::new(file, 4, 4, &[]).unwrap();
+    encoder.set_repeat(Repeat::Infinite).unwrap();
+
+    let mut red = solid(4, 4, [255, 0, 0, 255]);
+    let mut first = Frame::from_rgba(4, 4, &mut red);
+    first.delay = 5;
+    first.dispose = DisposalMethod::Keep;
+    encoder.write_frame(&first).unwrap();
+
+    let mut blue = solid(2, 2, [0, 0, 255, 255]);
+    let mut second = Frame::from_rgba(2, 2, &mut blue);
+    second.left = 2;
+    second.top = 2;
+    second.delay = 10;
+    encoder.write_frame(&second).unwrap();
+  }
+
+  fn assert_close(actual: &image::Rgba<u8>, expected: [u8; 4]) {
+    for (a, e) in actual.0.iter().zip(expected) {
+      assert!(a.abs_diff(e) <= 8, "{actual:?} != {expected:?}");
+    }
+  }
+
+  #[test]
+  fn split_then_assemble_round_trip() {
+    let dir = tempfile::tempdir().unwrap();
+    let source = dir.path().join("source.gif");
+    write_source(&source);
+
+    let summary = info(&source.to_string_lossy()).unwrap();
+    assert_eq!((summary.width, summary.height, summary.frame_count), (4, 4, 2));
+    assert_eq!((summary.loop_count, summary.duration_ms), (Some(0), 150));
+
+    let raw = split(&source.to_string_lossy(), Some(dir.path().join("raw")), false, &mut task()).unwrap();
+    assert_eq!((raw.frames[1].left, raw.frames[1].width), (2, 2));
+    assert_eq!(image::open(&raw.frames[1].path).unwrap().to_rgba8().dimensions(), (2, 2));
+
+    let composite = split(&source.to_string_lossy(), Some(dir.path().join("frames")), true, &mut task()).unwrap();
+    assert_eq!(composite.frames.len(), 2);
+    assert_eq!(composite.frames[0].disposal, FrameDisposal::Keep);
+    let composed = image::open(&composite.frames[1].path).unwrap().to_rgba8();
+    assert_eq!(composed.dimensions(), (4, 4));
+    assert_close(composed.get_pixel(0, 0), [255, 0, 0, 255]);
+    assert_close(composed.get_pixel(3, 3), [0, 0, 255, 255]);
+
+    let frames: Vec<GifAssembleFrame> = composite
+      .frames
+      .iter()
+      .map(|frame| GifAssembleFrame {
+        path: frame.path.clone(),
+        delay_ms: frame.delay_ms,
+      })
+      .collect();
+    let output = dir.path().join("out/assembled.gif");
+    let assembled = assemble(&frames, &output.to_string_lossy(), Some(0), 1, &mut task()).unwrap();
+    let reread = info(&output.to_string_lossy()).unwrap();
+    for result in [&assembled, &reread] {
+      assert_eq!((result.width, result.height, result.frame_count), (4, 4, 2));
+      assert_eq!((result.loop_count, result.duration_ms), (Some(0), 150));
+    }
+
+    let again = split(&output.to_string_lossy(), Some(dir.path().join("again")), true, &mut task()).unwrap();
+    let last = image::open(&again.frames[1].path).unwrap().to_rgba8();
+    assert_close(last.get_pixel(0, 0), [255, 0, 0, 255]);
+    assert_close(last.get_pixel(3, 3), [0, 0, 255, 255]);
+  }
+
+  #[test]
+  fn assemble_rejects_empty_frames() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("empty.gif");
+    assert!(assemble(&[], &output.to_string_lossy(), None, 10, &mut task()).is_err());
+  }
+}
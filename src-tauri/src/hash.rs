@@ -0,0 +1,204 @@
+use std::fs::File;
+use std::io::Read;
+use std::time::{Duration, Instant};
+
+use md5::Md5;
+use serde::{Deserialize, Serialize};
+use sha1::Sha1;
+use sha2::{Digest, Sha256, Sha512};
+use tauri::{AppHandle, Emitter, State};
+
+use crate::task::{self, TaskRegistry};
+
+const CHUNK_SIZE: usize = 1024 * 1024;
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+/// 支持的摘要算法，与前端 file-hash / md5-hash / sha256-hash 工具的选项一致
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum HashAlgorithm {
+  Md5,
+  Sha1,
+  Sha256,
+  Sha512,
+  Blake3,
+  Crc32,
+}
+
+#[derive(Serialize, Clone)]
+pub struct HashResult {
+  pub algorithm: HashAlgorithm,
+  pub hex: String,
+}
+
+/// `hash:progress` 事件，与通用的 `task:progress` 同时推送，兼容按哈希事件编写的前端
+#[derive(Serialize, Clone)]
+#[serde(tag = "event", content = "data")]
+enum HashProgressEvent {
+  Started {
+    task_id: String,
+    total: u64,
+  },
+  Progress {
+    task_id: String,
+    processed: u64,
+    total: u64,
+  },
+  Finished {
+    task_id: String,
+  },
+  Cancelled {
+    task_id: String,
+  },
+}
+
+enum Hasher {
+  Md5(Md5),
+  Sha1(Sha1),
+  Sha256(Sha256),
+  Sha512(Sha512),
+  Blake3(Box<blake3::Hasher>),
+  Crc32(crc32fast::Hasher),
+}
+
+impl Hasher {
+  fn new(algorithm: HashAlgorithm) -> Self {
+    match algorithm {
+      HashAlgorithm::Md5 => Hasher::Md5(Md5::new()),
+      HashAlgorithm::Sha1 => Hasher::Sha1(Sha1::new()),
+      HashAlgorithm::Sha256 => Hasher::Sha256(Sha256::new()),
+      HashAlgorithm::Sha512 => Hasher::Sha512(Sha512::new()),
+      HashAlgorithm::Blake3 => Hasher::Blake3(Box::new(blake3::Hasher::new())),
+      HashAlgorithm::Crc32 => Hasher::Crc32(crc32fast::Hasher::new()),
+    }
+  }
+
+  fn update(&mut self, data: &[u8]) {
+    match self {
+      Hasher::Md5(h) => h.update(data),
+      Hasher::Sha1(h) => h.update(data),
+      Hasher::Sha256(h) => h.update(data),
+      Hasher::Sha512(h) => h.update(data),
+      Hasher::Blake3(h) => {
+        h.update(data);
+      }
+      Hasher::Crc32(h) => h.update(data),
+    }
+  }
+
+  fn finalize(self) -> String {
+    match self {
+      Hasher::Md5(h) => hex::encode(h.finalize()),
+      Hasher::Sha1(h) => hex::encode(h.finalize()),
+      Hasher::Sha256(h) => hex::encode(h.finalize()),
+      Hasher::Sha512(h) => hex::encode(h.finalize()),
+      Hasher::Blake3(h) => h.finalize().to_hex().to_string(),
+      Hasher::Crc32(h) => format!("{:08x}", h.finalize()),
+    }
+  }
+}
+
+/// 单次读取文件并同时计算多个摘要；`on_progress` 返回 false 时中止
+pub fn hash_reader<R: Read>(
+  mut reader: R,
+  algorithms: &[HashAlgorithm],
+  mut on_progress: impl FnMut(u64) -> bool,
+) -> Result<Option<Vec<HashResult>>, String> {
+  let mut hashers: Vec<(HashAlgorithm, Hasher)> = algorithms
+    .iter()
+    .map(|algorithm| (*algorithm, Hasher::new(*algorithm)))
+    .collect();
+  let mut buffer = vec![0_u8; CHUNK_SIZE];
+  let mut processed = 0_u64;
+
+  loop {
+    let read = reader.read(&mut buffer).map_err(|e| e.to_string())?;
+    if read == 0 {
+      break;
+    }
+    for (_, hasher) in hashers.iter_mut() {
+      hasher.update(&buffer[..read]);
+    }
+    processed += read as u64;
+    if !on_progress(processed) {
+      return Ok(None);
+    }
+  }
+
+  Ok(Some(
+    hashers
+      .into_iter()
+      .map(|(algorithm, hasher)| HashResult {
+        algorithm,
+        hex: hasher.finalize(),
+      })
+      .collect(),
+  ))
+}
+
+/// 流式计算文件摘要，推送 `hash:progress` 与任务注册表的 `task:progress` 进度，取消时返回 None
+#[tauri::command]
+pub async fn hash_file(
+  app_handle: AppHandle,
+  tasks: State<'_, TaskRegistry>,
+  task_id: String,
+  path: String,
+  algorithms: Vec<HashAlgorithm>,
+) -> Result<Option<Vec<HashResult>>, String> {
+  if algorithms.is_empty() {
+    return Err("No hash algorithm selected".to_string());
+  }
+
+  let mut task = tasks.start(Some(task_id.clone()), "hash")?;
+  tauri::async_runtime::spawn_blocking(move || {
+    let result = (|| -> Result<Option<Vec<HashResult>>, String> {
+      let file = File::open(&path).map_err(|e| format!("Failed to open {path}: {e}"))?;
+      let total = file.metadata().map(|m| m.len()).unwrap_or(0);
+      let _ = app_handle.emit(
+        "hash:progress",
+        HashProgressEvent::Started {
+          task_id: task_id.clone(),
+          total,
+        },
+      );
+
+      let mut last_emit = Instant::now();
+      let result = hash_reader(file, &algorithms, |processed| {
+        if task.is_cancelled() {
+          return false;
+        }
+        task.progress_ratio("hashing", processed, total);
+        if last_emit.elapsed() >= PROGRESS_INTERVAL {
+          last_emit = Instant::now();
+          let _ = app_handle.emit(
+            "hash:progress",
+            HashProgressEvent::Progress {
+              task_id: task_id.clone(),
+              processed,
+              total,
+            },
+          );
+        }
+        true
+      })?;
+
+      let event = if result.is_some() {
+        HashProgressEvent::Finished { task_id }
+      } else {
+        HashProgressEvent::Cancelled { task_id }
+      };
+      let _ = app_handle.emit("hash:progress", event);
+      Ok(result)
+    })();
+    task.settle(&result);
+    result
+  })
+  .await
+  .map_err(|e| e.to_string())?
+}
+
+/// 取消指定的哈希任务，等价于 `task_cancel`
+#[tauri::command]
+pub fn hash_cancel(tasks: State<'_, TaskRegistry>, task_id: String) -> bool {
+  task::task_cancel(tasks, task_id)
+}
@@ -0,0 +1,770 @@
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use rusqlite::types::Value as SqlValue;
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::settings::SettingsStore;
+use crate::storage;
+
+const HISTORY_DB: &str = "history.db";
+/// 保留策略中表示默认策略的 slug
+const DEFAULT_POLICY: &str = "*";
+const MAX_PAGE_SIZE: u32 = 500;
+
+/// 数据库结构版本，保存在 `PRAGMA user_version` 中
+const SCHEMA_VERSION: i64 = 1;
+
+/// `secure_delete` 让删除的数据页被清零；FTS 表使用外部内容并通过触发器同步。
+/// trigram 分词支持任意子串检索，中文等不以空格分词的文本也能搜索
+const SCHEMA: &str = "
+PRAGMA journal_mode = WAL;
+PRAGMA secure_delete = ON;
+PRAGMA foreign_keys = ON;
+
+CREATE TABLE IF NOT EXISTS history (
+  id TEXT PRIMARY KEY,
+  tool_slug TEXT NOT NULL,
+  tool_name TEXT NOT NULL,
+  timestamp INTEGER NOT NULL,
+  input_data TEXT,
+  output_data TEXT,
+  duration REAL,
+  success INTEGER NOT NULL,
+  pinned INTEGER NOT NULL DEFAULT 0
+);
+CREATE INDEX IF NOT EXISTS history_tool_time ON history (tool_slug, timestamp DESC);
+CREATE INDEX IF NOT EXISTS history_time ON history (timestamp DESC);
+
+CREATE VIRTUAL TABLE IF NOT EXISTS history_fts USING fts5 (
+  input_data, output_data, content = 'history', content_rowid = 'rowid', tokenize = 'trigram'
+);
+CREATE TRIGGER IF NOT EXISTS history_ai AFTER INSERT ON history BEGIN
+  INSERT INTO history_fts (rowid, input_data, output_data) VALUES (new.rowid, new.input_data, new.output_data);
+END;
+CREATE TRIGGER IF NOT EXISTS history_ad AFTER DELETE ON history BEGIN
+  INSERT INTO history_fts (history_fts, rowid, input_data, output_data)
+    VALUES ('delete', old.rowid, old.input_data, old.output_data);
+END;
+CREATE TRIGGER IF NOT EXISTS history_au AFTER UPDATE OF input_data, output_data ON history BEGIN
+  INSERT INTO history_fts (history_fts, rowid, input_data, output_data)
+    VALUES ('delete', old.rowid, old.input_data, old.output_data);
+  INSERT INTO history_fts (rowid, input_data, output_data) VALUES (new.rowid, new.input_data, new.output_data);
+END;
+
+CREATE TABLE IF NOT EXISTS retention (
+  tool_slug TEXT PRIMARY KEY,
+  max_entries INTEGER,
+  max_age_days INTEGER
+);
+";
+
+/// 历史记录，对应前端的 `ToolHistory`
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct HistoryEntry {
+  pub id: String,
+  pub tool_slug: String,
+  pub tool_name: String,
+  pub timestamp: i64,
+  pub input_data: Option<Value>,
+  pub output_data: Option<Value>,
+  pub duration: Option<f64>,
+  pub success: bool,
+  pub pinned: bool,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct NewHistoryEntry {
+  pub id: Option<String>,
+  pub tool_slug: String,
+  pub tool_name: String,
+  pub timestamp: Option<i64>,
+  pub input_data: Option<Value>,
+  pub output_data: Option<Value>,
+  pub duration: Option<f64>,
+  pub success: bool,
+}
+
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct HistoryQuery {
+  pub tool_slug: Option<String>,
+  /// 全文检索输入与输出
+  pub search: Option<String>,
+  pub pinned_only: bool,
+  pub success: Option<bool>,
+  /// 从 0 开始的页码
+  pub page: u32,
+  pub page_size: Option<u32>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct HistoryPage {
+  pub entries: Vec<HistoryEntry>,
+  pub total: u64,
+  pub page: u32,
+  pub page_size: u32,
+}
+
+/// 保留策略；`tool_slug` 为空表示默认策略。置顶的记录不受保留策略影响
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RetentionPolicy {
+  pub tool_slug: Option<String>,
+  pub max_entries: Option<u32>,
+  pub max_age_days: Option<u32>,
+}
+
+pub struct HistoryDb {
+  conn: Mutex<Connection>,
+}
+
+impl HistoryDb {
+  pub fn open(path: &std::path::Path) -> Result<Self, String> {
+    let conn = Connection::open(path).map_err(|e| format!("Failed to open history database: {e}"))?;
+    migrate(&conn)?;
+    Ok(Self {
+      conn: Mutex::new(conn),
+    })
+  }
+}
+
+/// 创建或升级表结构。版本 0 的全文索引使用默认分词器，升级时删除后按 trigram 重建
+fn migrate(conn: &Connection) -> Result<(), String> {
+  let version: i64 = conn
+    .query_row("PRAGMA user_version", [], |row| row.get(0))
+    .map_err(|e| e.to_string())?;
+  if version > SCHEMA_VERSION {
+    return Err(format!(
+      "History database version {version} is newer than supported version {SCHEMA_VERSION}"
+    ));
+  }
+  if version < 1 {
+    conn
+      .execute_batch("DROP TABLE IF EXISTS history_fts")
+      .map_err(|e| e.to_string())?;
+  }
+  conn.execute_batch(SCHEMA).map_err(|e| e.to_string())?;
+  if version < 1 {
+    conn
+      .execute("INSERT INTO history_fts (history_fts) VALUES ('rebuild')", [])
+      .map_err(|e| e.to_string())?;
+  }
+  conn
+    .pragma_update(None, "user_version", SCHEMA_VERSION)
+    .map_err(|e| e.to_string())
+}
+
+fn now_ms() -> i64 {
+  SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map(|time| time.as_millis() as i64)
+    .unwrap_or_default()
+}
+
+fn new_id() -> String {
+  uuid::Uuid::now_v7().to_string()
+}
+
+fn to_json_text(value: &Option<Value>) -> Option<String> {
+  value.as_ref().map(Value::to_string)
+}
+
+fn from_json_text(text: Option<String>) -> Option<Value> {
+  text.map(|text| serde_json::from_str(&text).unwrap_or(Value::String(text)))
+}
+
+fn read_entry(row: &Row) -> rusqlite::Result<HistoryEntry> {
+  Ok(HistoryEntry {
+    id: row.get("id")?,
+    tool_slug: row.get("tool_slug")?,
+    tool_name: row.get("tool_name")?,
+    timestamp: row.get("timestamp")?,
+    input_data: from_json_text(row.get("input_data")?),
+    output_data: from_json_text(row.get("output_data")?),
+    duration: row.get("duration")?,
+    success: row.get("success")?,
+    pinned: row.get("pinned")?,
+  })
+}
+
+fn read_policy(row: &Row) -> rusqlite::Result<RetentionPolicy> {
+  let slug: String = row.get(0)?;
+  Ok(RetentionPolicy {
+    tool_slug: (slug != DEFAULT_POLICY).then_some(slug),
+    max_entries: row.get(1)?,
+    max_age_days: row.get(2)?,
+  })
+}
+
+/// 把用户输入转换为检索条件，各词之间为“且”的关系。trigram 索引只能匹配至少 3 个字符的词，
+/// 更短的词改用 LIKE 做子串匹配
+fn search_conditions(search: &str, conditions: &mut Vec<&'static str>, values: &mut Vec<SqlValue>) {
+  let mut phrases = Vec::new();
+  for term in search.split_whitespace() {
+    if term.chars().count() >= 3 {
+      phrases.push(format!("\"{}\"", term.replace('"', "\"\"")));
+    } else {
+      let pattern = format!("%{}%", term.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_"));
+      conditions.push("(input_data LIKE ? ESCAPE '\\' OR output_data LIKE ? ESCAPE '\\')");
+      values.push(SqlValue::Text(pattern.clone()));
+      values.push(SqlValue::Text(pattern));
+    }
+  }
+  if !phrases.is_empty() {
+    conditions.push("rowid IN (SELECT rowid FROM history_fts WHERE history_fts MATCH ?)");
+    values.push(SqlValue::Text(phrases.join(" ")));
+  }
+}
+
+/// 合并 FTS 段并截断 WAL，确保被删除的内容不再残留在磁盘上
+fn scrub(conn: &Connection) -> Result<(), String> {
+  conn
+    .execute("INSERT INTO history_fts (history_fts) VALUES ('optimize')", [])
+    .map_err(|e| e.to_string())?;
+  conn
+    .query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))
+    .map_err(|e| e.to_string())
+}
+
+fn policy_for(conn: &Connection, tool_slug: &str, default_limit: u32) -> Result<RetentionPolicy, String> {
+  let row = conn
+    .query_row(
+      "SELECT tool_slug, max_entries, max_age_days FROM retention
+       WHERE tool_slug IN (?1, ?2) ORDER BY tool_slug = ?1 DESC LIMIT 1",
+      params![tool_slug, DEFAULT_POLICY],
+      read_policy,
+    )
+    .optional()
+    .map_err(|e| e.to_string())?;
+  Ok(row.unwrap_or(RetentionPolicy {
+    tool_slug: None,
+    max_entries: Some(default_limit),
+    max_age_days: None,
+  }))
+}
+
+/// 按保留策略清理某个工具的未置顶记录，返回删除条数
+fn apply_retention(conn: &Connection, tool_slug: &str, default_limit: u32) -> Result<usize, String> {
+  let policy = policy_for(conn, tool_slug, default_limit)?;
+  let mut removed = 0;
+  if let Some(days) = policy.max_age_days {
+    let cutoff = now_ms() - i64::from(days) * 24 * 60 * 60 * 1000;
+    removed += conn
+      .execute(
+        "DELETE FROM history WHERE tool_slug = ?1 AND pinned = 0 AND timestamp < ?2",
+        params![tool_slug, cutoff],
+      )
+      .map_err(|e| e.to_string())?;
+  }
+  if let Some(max_entries) = policy.max_entries {
+    removed += conn
+      .execute(
+        "DELETE FROM history WHERE rowid IN (
+           SELECT rowid FROM history WHERE tool_slug = ?1 AND pinned = 0
+           ORDER BY timestamp DESC, rowid DESC LIMIT -1 OFFSET ?2
+         )",
+        params![tool_slug, max_entries],
+      )
+      .map_err(|e| e.to_string())?;
+  }
+  Ok(removed)
+}
+
+/// 写入一条记录；`ignore_existing` 为真时跳过已存在的 id
+fn insert(conn: &Connection, entry: &HistoryEntry, ignore_existing: bool) -> Result<usize, String> {
+  let verb = if ignore_existing { "INSERT OR IGNORE" } else { "INSERT" };
+  conn
+    .execute(
+      &format!(
+        "{verb} INTO history (id, tool_slug, tool_name, timestamp, input_data, output_data, duration, success, pinned)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)"
+      ),
+      params![
+        entry.id,
+        entry.tool_slug,
+        entry.tool_name,
+        entry.timestamp,
+        to_json_text(&entry.input_data),
+        to_json_text(&entry.output_data),
+        entry.duration,
+        entry.success,
+        entry.pinned,
+      ],
+    )
+    .map_err(|e| e.to_string())
+}
+
+impl HistoryDb {
+  /// 写入一条记录，并按该工具的保留策略清理旧记录
+  pub fn add(&self, entry: NewHistoryEntry, default_limit: u32) -> Result<HistoryEntry, String> {
+    let entry = HistoryEntry {
+      id: entry.id.unwrap_or_else(new_id),
+      tool_slug: entry.tool_slug,
+      tool_name: entry.tool_name,
+      timestamp: entry.timestamp.unwrap_or_else(now_ms),
+      input_data: entry.input_data,
+      output_data: entry.output_data,
+      duration: entry.duration,
+      success: entry.success,
+      pinned: false,
+    };
+    let mut conn = self.conn.lock().map_err(|e| e.to_string())?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    insert(&tx, &entry, false)?;
+    let removed = apply_retention(&tx, &entry.tool_slug, default_limit)?;
+    tx.commit().map_err(|e| e.to_string())?;
+    // 被保留策略挤掉的记录同样需要清除磁盘残留
+    if removed > 0 {
+      scrub(&conn)?;
+    }
+    Ok(entry)
+  }
+
+  /// 分页查询，按时间倒序
+  pub fn query(&self, query: &HistoryQuery) -> Result<HistoryPage, String> {
+    let page_size = query.page_size.unwrap_or(50).clamp(1, MAX_PAGE_SIZE);
+    let mut conditions = Vec::new();
+    let mut values: Vec<SqlValue> = Vec::new();
+
+    if let Some(slug) = &query.tool_slug {
+      conditions.push("tool_slug = ?");
+      values.push(SqlValue::Text(slug.clone()));
+    }
+    if let Some(search) = &query.search {
+      search_conditions(search, &mut conditions, &mut values);
+    }
+    if query.pinned_only {
+      conditions.push("pinned = 1");
+    }
+    if let Some(success) = query.success {
+      conditions.push("success = ?");
+      values.push(SqlValue::Integer(i64::from(success)));
+    }
+    let filter = if conditions.is_empty() {
+      String::new()
+    } else {
+      format!("WHERE {}", conditions.join(" AND "))
+    };
+
+    let conn = self.conn.lock().map_err(|e| e.to_string())?;
+    let total: u64 = conn
+      .query_row(
+        &format!("SELECT COUNT(*) FROM history {filter}"),
+        params_from_iter(values.iter()),
+        |row| row.get(0),
+      )
+      .map_err(|e| e.to_string())?;
+
+    values.push(SqlValue::Integer(i64::from(page_size)));
+    values.push(SqlValue::Integer(i64::from(query.page) * i64::from(page_size)));
+    let mut statement = conn
+      .prepare(&format!(
+        "SELECT * FROM history {filter} ORDER BY timestamp DESC, rowid DESC LIMIT ? OFFSET ?"
+      ))
+      .map_err(|e| e.to_string())?;
+    let entries = statement
+      .query_map(params_from_iter(values.iter()), read_entry)
+      .map_err(|e| e.to_string())?
+      .collect::<rusqlite::Result<Vec<_>>>()
+      .map_err(|e| e.to_string())?;
+
+    Ok(HistoryPage {
+      entries,
+      total,
+      page: query.page,
+      page_size,
+    })
+  }
+
+  pub fn pin(&self, id: &str, pinned: bool) -> Result<(), String> {
+    let conn = self.conn.lock().map_err(|e| e.to_string())?;
+    let updated = conn
+      .execute("UPDATE history SET pinned = ?1 WHERE id = ?2", params![pinned, id])
+      .map_err(|e| e.to_string())?;
+    if updated == 0 {
+      return Err(format!("History entry not found: {id}"));
+    }
+    Ok(())
+  }
+
+  /// 删除记录并清除其在磁盘上的残留，返回删除条数
+  pub fn delete(&self, ids: &[String]) -> Result<usize, String> {
+    let mut conn = self.conn.lock().map_err(|e| e.to_string())?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let mut removed = 0;
+    for id in ids {
+      removed += tx
+        .execute("DELETE FROM history WHERE id = ?1", params![id])
+        .map_err(|e| e.to_string())?;
+    }
+    tx.commit().map_err(|e| e.to_string())?;
+    scrub(&conn)?;
+    Ok(removed)
+  }
+
+  /// 清空记录（可限定工具），`include_pinned` 为假时保留置顶记录
+  pub fn clear(&self, tool_slug: Option<&str>, include_pinned: bool) -> Result<usize, String> {
+    let conn = self.conn.lock().map_err(|e| e.to_string())?;
+    let removed = conn
+      .execute(
+        "DELETE FROM history WHERE (?1 IS NULL OR tool_slug = ?1) AND (?2 OR pinned = 0)",
+        params![tool_slug, include_pinned],
+      )
+      .map_err(|e| e.to_string())?;
+    scrub(&conn)?;
+    Ok(removed)
+  }
+
+  /// 保存保留策略并立即应用，返回删除条数
+  pub fn set_retention(&self, policy: &RetentionPolicy, default_limit: u32) -> Result<usize, String> {
+    let key = policy.tool_slug.as_deref().unwrap_or(DEFAULT_POLICY);
+    let conn = self.conn.lock().map_err(|e| e.to_string())?;
+    conn
+      .execute(
+        "INSERT INTO retention (tool_slug, max_entries, max_age_days) VALUES (?1, ?2, ?3)
+         ON CONFLICT (tool_slug) DO UPDATE SET max_entries = excluded.max_entries, max_age_days = excluded.max_age_days",
+        params![key, policy.max_entries, policy.max_age_days],
+      )
+      .map_err(|e| e.to_string())?;
+
+    let slugs: Vec<String> = match &policy.tool_slug {
+      Some(slug) => vec![slug.clone()],
+      None => {
+        let mut statement = conn
+          .prepare("SELECT DISTINCT tool_slug FROM history")
+          .map_err(|e| e.to_string())?;
+        let slugs = statement
+          .query_map([], |row| row.get(0))
+          .map_err(|e| e.to_string())?
+          .collect::<rusqlite::Result<Vec<_>>>()
+          .map_err(|e| e.to_string())?;
+        slugs
+      }
+    };
+    let mut removed = 0;
+    for slug in &slugs {
+      removed += apply_retention(&conn, slug, default_limit)?;
+    }
+    if removed > 0 {
+      scrub(&conn)?;
+    }
+    Ok(removed)
+  }
+
+  pub fn retention(&self) -> Result<Vec<RetentionPolicy>, String> {
+    let conn = self.conn.lock().map_err(|e| e.to_string())?;
+    let mut statement = conn
+      .prepare("SELECT tool_slug, max_entries, max_age_days FROM retention ORDER BY tool_slug")
+      .map_err(|e| e.to_string())?;
+    let policies = statement
+      .query_map([], read_policy)
+      .map_err(|e| e.to_string())?
+      .collect::<rusqlite::Result<Vec<_>>>()
+      .map_err(|e| e.to_string())?;
+    Ok(policies)
+  }
+
+  /// 读取全部记录，供数据导出使用
+  pub fn all(&self) -> Result<Vec<HistoryEntry>, String> {
+    let conn = self.conn.lock().map_err(|e| e.to_string())?;
+    let mut statement = conn
+      .prepare("SELECT * FROM history ORDER BY timestamp DESC, rowid DESC")
+      .map_err(|e| e.to_string())?;
+    let entries = statement
+      .query_map([], read_entry)
+      .map_err(|e| e.to_string())?
+      .collect::<rusqlite::Result<Vec<_>>>()
+      .map_err(|e| e.to_string())?;
+    Ok(entries)
+  }
+
+  /// 导入记录：`replace` 为真时先清空现有记录，否则跳过 id 已存在的记录；返回写入条数
+  pub fn import(&self, entries: &[HistoryEntry], replace: bool) -> Result<usize, String> {
+    let mut conn = self.conn.lock().map_err(|e| e.to_string())?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    if replace {
+      tx.execute("DELETE FROM history", []).map_err(|e| e.to_string())?;
+    }
+    let mut inserted = 0;
+    for entry in entries {
+      inserted += insert(&tx, entry, true)?;
+    }
+    tx.commit().map_err(|e| e.to_string())?;
+    if replace {
+      scrub(&conn)?;
+    }
+    Ok(inserted)
+  }
+}
+
+fn default_limit<R: Runtime>(app: &AppHandle<R>) -> u32 {
+  app
+    .try_state::<SettingsStore>()
+    .map(|store| store.get().preferences.history_limit)
+    .unwrap_or(100)
+}
+
+/// 打开历史数据库并注册到应用状态
+pub fn init<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+  let path = storage::data_file(app, HISTORY_DB)?;
+  app.manage(HistoryDb::open(&path)?);
+  Ok(())
+}
+
+/// 在阻塞线程池中访问数据库，避免 SQLite 读写和清理占用异步运行时
+async fn with_db<T, F>(app_handle: AppHandle, job: F) -> Result<T, String>
+where
+  T: Send + 'static,
+  F: FnOnce(&HistoryDb) -> Result<T, String> + Send + 'static,
+{
+  tauri::async_runtime::spawn_blocking(move || job(&app_handle.state::<HistoryDb>()))
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 添加一条历史记录，并按该工具的保留策略清理旧记录
+#[tauri::command]
+pub async fn history_add(app_handle: AppHandle, entry: NewHistoryEntry) -> Result<HistoryEntry, String> {
+  let limit = default_limit(&app_handle);
+  with_db(app_handle, move |db| db.add(entry, limit)).await
+}
+
+/// 分页查询历史记录，按时间倒序
+#[tauri::command]
+pub async fn history_query(app_handle: AppHandle, query: HistoryQuery) -> Result<HistoryPage, String> {
+  with_db(app_handle, move |db| db.query(&query)).await
+}
+
+/// 置顶或取消置顶
+#[tauri::command]
+pub async fn history_pin(app_handle: AppHandle, id: String, pinned: bool) -> Result<(), String> {
+  with_db(app_handle, move |db| db.pin(&id, pinned)).await
+}
+
+/// 删除记录并清除其在磁盘上的残留，返回删除条数
+#[tauri::command]
+pub async fn history_delete(app_handle: AppHandle, ids: Vec<String>) -> Result<usize, String> {
+  with_db(app_handle, move |db| db.delete(&ids)).await
+}
+
+/// 清空历史（可限定工具），默认保留置顶记录
+#[tauri::command]
+pub async fn history_clear(
+  app_handle: AppHandle,
+  tool_slug: Option<String>,
+  include_pinned: Option<bool>,
+) -> Result<usize, String> {
+  with_db(app_handle, move |db| {
+    db.clear(tool_slug.as_deref(), include_pinned.unwrap_or(false))
+  })
+  .await
+}
+
+/// 设置保留策略并立即应用；`tool_slug` 为空时设置默认策略
+#[tauri::command]
+pub async fn history_set_retention(app_handle: AppHandle, policy: RetentionPolicy) -> Result<usize, String> {
+  let limit = default_limit(&app_handle);
+  with_db(app_handle, move |db| db.set_retention(&policy, limit)).await
+}
+
+/// 列出已配置的保留策略
+#[tauri::command]
+pub async fn history_get_retention(app_handle: AppHandle) -> Result<Vec<RetentionPolicy>, String> {
+  with_db(app_handle, |db| db.retention()).await
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use serde_json::json;
+
+  const DAY_MS: i64 = 24 * 60 * 60 * 1000;
+
+  fn open() -> (tempfile::TempDir, HistoryDb) {
+    let dir = tempfile::tempdir().unwrap();
+    let db = HistoryDb::open(&dir.path().join(HISTORY_DB)).unwrap();
+    (dir, db)
+  }
+
+  fn entry(id: &str, slug: &str, timestamp: i64, input: &str) -> NewHistoryEntry {
+    NewHistoryEntry {
+      id: Some(id.to_string()),
+      tool_slug: slug.to_string(),
+      tool_name: slug.to_string(),
+      timestamp: Some(timestamp),
+      input_data: Some(json!({ "text": input })),
+      output_data: None,
+      duration: None,
+      success: true,
+    }
+  }
+
+  fn ids(page: &HistoryPage) -> Vec<&str> {
+    page.entries.iter().map(|entry| entry.id.as_str()).collect()
+  }
+
+  fn search(db: &HistoryDb, text: &str) -> Vec<String> {
+    let query = HistoryQuery {
+      search: Some(text.to_string()),
+      ..Default::default()
+    };
+    ids(&db.query(&query).unwrap()).into_iter().map(str::to_string).collect()
+  }
+
+  #[test]
+  fn retention_keeps_pinned_entries() {
+    let (_dir, db) = open();
+    let now = now_ms();
+    for i in 0..3 {
+      db.add(entry(&format!("a{i}"), "json", now + i, "x"), 3).unwrap();
+    }
+    db.pin("a0", true).unwrap();
+    for i in 3..6 {
+      db.add(entry(&format!("a{i}"), "json", now + i, "x"), 3).unwrap();
+    }
+    db.add(entry("other", "base64", now, "x"), 3).unwrap();
+
+    let json = db
+      .query(&HistoryQuery {
+        tool_slug: Some("json".into()),
+        ..Default::default()
+      })
+      .unwrap();
+    // 未置顶的记录只保留最新 3 条，置顶记录不计入也不会被删除
+    assert_eq!(ids(&json), ["a5", "a4", "a3", "a0"]);
+
+    let old = now - 10 * DAY_MS;
+    db.add(entry("stale", "json", old, "x"), 100).unwrap();
+    db.add(entry("stale-pinned", "json", old, "x"), 100).unwrap();
+    db.pin("stale-pinned", true).unwrap();
+    let policy = RetentionPolicy {
+      tool_slug: Some("json".into()),
+      max_entries: None,
+      max_age_days: Some(7),
+    };
+    assert_eq!(db.set_retention(&policy, 100).unwrap(), 1);
+    let json = db
+      .query(&HistoryQuery {
+        tool_slug: Some("json".into()),
+        ..Default::default()
+      })
+      .unwrap();
+    assert_eq!(ids(&json), ["a5", "a4", "a3", "a0", "stale-pinned"]);
+    assert_eq!(db.retention().unwrap().len(), 1);
+    // 其他工具的记录不受影响
+    assert_eq!(db.all().unwrap().len(), 6);
+  }
+
+  #[test]
+  fn search_matches_substrings_and_forgets_deleted_entries() {
+    let (_dir, db) = open();
+    let now = now_ms();
+    db.add(entry("en", "text", now, "hello world"), 100).unwrap();
+    db.add(entry("zh", "text", now + 1, "你好世界"), 100).unwrap();
+    db.add(entry("sym", "text", now + 2, "50%_off"), 100).unwrap();
+
+    assert_eq!(search(&db, "ello"), ["en"]);
+    assert_eq!(search(&db, "WORLD hello"), ["en"]);
+    assert_eq!(search(&db, "好世界"), ["zh"]);
+    // 少于 3 个字符的词走 LIKE 匹配，通配符按字面处理
+    assert_eq!(search(&db, "世界"), ["zh"]);
+    assert_eq!(search(&db, "%_"), ["sym"]);
+    assert!(search(&db, "hello 世界").is_empty());
+    assert!(search(&db, "\"quoted\"").is_empty());
+
+    assert_eq!(db.delete(&["zh".to_string()]).unwrap(), 1);
+    assert!(search(&db, "好世界").is_empty());
+    assert!(search(&db, "世界").is_empty());
+    assert_eq!(db.clear(Some("text"), false).unwrap(), 2);
+    assert!(search(&db, "ello").is_empty());
+  }
+
+  #[test]
+  fn pages_in_reverse_chronological_order() {
+    let (_dir, db) = open();
+    let now = now_ms();
+    for i in 0..7 {
+      db.add(entry(&format!("e{i}"), "json", now + i, "x"), 100).unwrap();
+    }
+    let page = |page| {
+      db.query(&HistoryQuery {
+        page,
+        page_size: Some(3),
+        ..Default::default()
+      })
+      .unwrap()
+    };
+    let first = page(0);
+    assert_eq!((first.total, first.page, first.page_size), (7, 0, 3));
+    assert_eq!(ids(&first), ["e6", "e5", "e4"]);
+    assert_eq!(ids(&page(1)), ["e3", "e2", "e1"]);
+    assert_eq!(ids(&page(2)), ["e0"]);
+    assert!(page(3).entries.is_empty());
+
+    let pinned = db
+      .query(&HistoryQuery {
+        pinned_only: true,
+        ..Default::default()
+      })
+      .unwrap();
+    assert_eq!(pinned.total, 0);
+    assert!(db.pin("missing", true).is_err());
+  }
+
+  #[test]
+  fn import_merges_or_replaces() {
+    let (_dir, db) = open();
+    let now = now_ms();
+    let kept = db.add(entry("kept", "json", now, "local"), 100).unwrap();
+    let shared = db.add(entry("shared", "json", now + 1, "local"), 100).unwrap();
+
+    let mut incoming = shared.clone();
+    incoming.input_data = Some(json!({ "text": "imported" }));
+    let mut fresh = kept.clone();
+    fresh.id = "fresh".into();
+
+    // 合并时跳过已存在的 id，保留本地内容
+    assert_eq!(db.import(&[incoming.clone(), fresh.clone()], false).unwrap(), 1);
+    let all = db.all().unwrap();
+    assert_eq!(all.len(), 3);
+    assert_eq!(all.iter().find(|entry| entry.id == "shared"), Some(&shared));
+
+    assert_eq!(db.import(&[incoming.clone()], true).unwrap(), 1);
+    assert_eq!(db.all().unwrap(), [incoming]);
+    assert!(search(&db, "local").is_empty());
+    assert_eq!(search(&db, "imported"), ["shared"]);
+  }
+
+  #[test]
+  fn migrates_old_full_text_index() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join(HISTORY_DB);
+    {
+      // 版本 0 使用默认分词器
+      let old = SCHEMA.replace(", tokenize = 'trigram'", "");
+      let conn = Connection::open(&path).unwrap();
+      conn.execute_batch(&old).unwrap();
+      conn
+        .execute(
+          "INSERT INTO history (id, tool_slug, tool_name, timestamp, input_data, success) VALUES ('old', 'text', 'text', 1, ?1, 1)",
+          params![json!({ "text": "你好世界" }).to_string()],
+        )
+        .unwrap();
+    }
+    let db = HistoryDb::open(&path).unwrap();
+    assert_eq!(search(&db, "好世界"), ["old"]);
+    drop(db);
+    // 再次打开不会重复迁移
+    let db = HistoryDb::open(&path).unwrap();
+    assert_eq!(search(&db, "好世界"), ["old"]);
+
+    let conn = db.conn.lock().unwrap();
+    conn.pragma_update(None, "user_version", SCHEMA_VERSION + 1).unwrap();
+    drop(conn);
+    drop(db);
+    assert!(HistoryDb::open(&path).is_err());
+  }
+}
@@ -0,0 +1,344 @@
+use std::time::Duration;
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use curl::easy::{Easy, List};
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_TIMEOUT_MS: u64 = 30_000;
+const DEFAULT_MAX_REDIRECTS: u32 = 10;
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct HttpHeader {
+  pub name: String,
+  pub value: String,
+}
+
+/// 请求体；二进制内容由前端以 base64 传入
+#[derive(Deserialize, Clone, Debug)]
+#[serde(tag = "kind", content = "data", rename_all = "lowercase")]
+pub enum HttpRequestBody {
+  Text(String),
+  Base64(String),
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct HttpRequest {
+  pub method: String,
+  pub url: String,
+  #[serde(default)]
+  pub headers: Vec<HttpHeader>,
+  pub body: Option<HttpRequestBody>,
+  pub follow_redirects: Option<bool>,
+  pub max_redirects: Option<u32>,
+  pub proxy: Option<String>,
+  pub verify_tls: Option<bool>,
+  pub timeout_ms: Option<u64>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(tag = "kind", content = "data", rename_all = "lowercase")]
+pub enum HttpResponseBody {
+  Text(String),
+  Base64(String),
+}
+
+/// 各阶段耗时（毫秒），均为该阶段自身的时长而非累计值
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct HttpTiming {
+  pub dns_ms: f64,
+  pub connect_ms: f64,
+  pub tls_ms: f64,
+  pub ttfb_ms: f64,
+  pub download_ms: f64,
+  pub total_ms: f64,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct HttpResponse {
+  pub status: u32,
+  pub status_text: String,
+  pub http_version: String,
+  pub url: String,
+  pub redirect_count: u32,
+  pub headers: Vec<HttpHeader>,
+  pub body: HttpResponseBody,
+  pub size: u64,
+  pub timing: HttpTiming,
+}
+
+fn millis(duration: Duration) -> f64 {
+  duration.as_secs_f64() * 1000.0
+}
+
+fn is_text_content(headers: &[HttpHeader]) -> bool {
+  headers
+    .iter()
+    .find(|header| header.name.eq_ignore_ascii_case("content-type"))
+    .map(|header| {
+      let value = header.value.to_ascii_lowercase();
+      value.starts_with("text/")
+        || value.contains("json")
+        || value.contains("xml")
+        || value.contains("javascript")
+        || value.contains("x-www-form-urlencoded")
+    })
+    .unwrap_or(true)
+}
+
+/// 以阻塞方式执行一次 HTTP 请求，不受 webview 的 CORS 限制
+pub fn execute(request: HttpRequest) -> Result<HttpResponse, String> {
+  // curl 把 0 视为不限时，这里与 DNS 查询保持一致直接拒绝
+  let timeout = Duration::from_millis(request.timeout_ms.unwrap_or(DEFAULT_TIMEOUT_MS));
+  if timeout.is_zero() {
+    return Err("Timeout must be greater than zero".to_string());
+  }
+  let mut easy = Easy::new();
+  easy.url(&request.url).map_err(|e| e.to_string())?;
+
+  let method = request.method.trim().to_ascii_uppercase();
+  match method.as_str() {
+    "GET" => easy.get(true),
+    "HEAD" => easy.nobody(true),
+    _ => easy.custom_request(&method),
+  }
+  .map_err(|e| e.to_string())?;
+
+  let mut header_list = List::new();
+  for header in &request.headers {
+    header_list
+      .append(&format!("{}: {}", header.name, header.value))
+      .map_err(|e| e.to_string())?;
+  }
+  easy.http_headers(header_list).map_err(|e| e.to_string())?;
+
+  if let Some(body) = &request.body {
+    let bytes = match body {
+      HttpRequestBody::Text(text) => text.as_bytes().to_vec(),
+      HttpRequestBody::Base64(data) => BASE64
+        .decode(data)
+        .map_err(|e| format!("Invalid base64 request body: {e}"))?,
+    };
+    easy.post_fields_copy(&bytes).map_err(|e| e.to_string())?;
+    // post_fields_copy 会把方法改成 POST，这里恢复用户指定的方法
+    if method != "POST" {
+      easy.custom_request(&method).map_err(|e| e.to_string())?;
+    }
+  }
+
+  easy
+    .follow_location(request.follow_redirects.unwrap_or(true))
+    .map_err(|e| e.to_string())?;
+  easy
+    .max_redirections(request.max_redirects.unwrap_or(DEFAULT_MAX_REDIRECTS))
+    .map_err(|e| e.to_string())?;
+  if let Some(proxy) = request.proxy.as_deref().filter(|p| !p.is_empty()) {
+    easy.proxy(proxy).map_err(|e| e.to_string())?;
+  }
+  let verify_tls = request.verify_tls.unwrap_or(true);
+  easy.ssl_verify_peer(verify_tls).map_err(|e| e.to_string())?;
+  easy.ssl_verify_host(verify_tls).map_err(|e| e.to_string())?;
+  easy.timeout(timeout).map_err(|e| e.to_string())?;
+  easy.accept_encoding("").map_err(|e| e.to_string())?;
+
+  let mut body = Vec::new();
+  let mut headers: Vec<HttpHeader> = Vec::new();
+  let mut status_line = String::new();
+  {
+    let mut transfer = easy.transfer();
+    transfer
+      .header_function(|line| {
+        let line = String::from_utf8_lossy(line);
+        let line = line.trim_end();
+        // 跟随重定向时每一跳都会回调状态行，只保留最后一个响应的头部
+        if line.starts_with("HTTP/") {
+          status_line = line.to_string();
+          headers.clear();
+        } else if let Some((name, value)) = line.split_once(':') {
+          headers.push(HttpHeader {
+            name: name.trim().to_string(),
+            value: value.trim().to_string(),
+          });
+        }
+        true
+      })
+      .map_err(|e| e.to_string())?;
+    transfer
+      .write_function(|data| {
+        body.extend_from_slice(data);
+        Ok(data.len())
+      })
+      .map_err(|e| e.to_string())?;
+    transfer
+      .perform()
+      .map_err(|e| format!("HTTP request failed: {e}"))?;
+  }
+
+  let namelookup = easy.namelookup_time().map_err(|e| e.to_string())?;
+  let connect = easy.connect_time().map_err(|e| e.to_string())?;
+  let appconnect = easy.appconnect_time().map_err(|e| e.to_string())?;
+  let pretransfer = easy.pretransfer_time().map_err(|e| e.to_string())?;
+  let starttransfer = easy.starttransfer_time().map_err(|e| e.to_string())?;
+  let total = easy.total_time().map_err(|e| e.to_string())?;
+  // 复用连接或经过重定向时部分阶段可能为 0，统一用 saturating_sub 避免负值
+  let handshake_end = if appconnect.is_zero() { connect } else { appconnect };
+  let timing = HttpTiming {
+    dns_ms: millis(namelookup),
+    connect_ms: millis(connect.saturating_sub(namelookup)),
+    tls_ms: if appconnect.is_zero() {
+      0.0
+    } else {
+      millis(appconnect.saturating_sub(connect))
+    },
+    ttfb_ms: millis(starttransfer.saturating_sub(pretransfer.max(handshake_end))),
+    download_ms: millis(total.saturating_sub(starttransfer)),
+    total_ms: millis(total),
+  };
+
+  let mut status_parts = status_line.splitn(3, ' ');
+  let http_version = status_parts.next().unwrap_or_default().to_string();
+  let _ = status_parts.next();
+  let status_text = status_parts.next().unwrap_or_default().to_string();
+
+  let size = body.len() as u64;
+  let body = if is_text_content(&headers) {
+    match String::from_utf8(body) {
+      Ok(text) => HttpResponseBody::Text(text),
+      Err(error) => HttpResponseBody::Base64(BASE64.encode(error.into_bytes())),
+    }
+  } else {
+    HttpResponseBody::Base64(BASE64.encode(&body))
+  };
+
+  Ok(HttpResponse {
+    status: easy.response_code().map_err(|e| e.to_string())?,
+    status_text,
+    http_version,
+    url: easy
+      .effective_url()
+      .map_err(|e| e.to_string())?
+      .unwrap_or(request.url.as_str())
+      .to_string(),
+    redirect_count: easy.redirect_count().map_err(|e| e.to_string())?,
+    headers,
+    body,
+    size,
+    timing,
+  })
+}
+
+/// 原生 HTTP 请求，供 api-tester 工具绕过 CORS 使用
+#[tauri::command]
+pub async fn http_request(request: HttpRequest) -> Result<HttpResponse, String> {
+  tauri::async_runtime::spawn_blocking(move || execute(request))
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::io::{BufRead, BufReader, Read, Write};
+  use std::net::TcpListener;
+  use std::thread::JoinHandle;
+
+  fn request(url: &str) -> HttpRequest {
+    HttpRequest {
+      method: "GET".to_string(),
+      url: url.to_string(),
+      headers: Vec::new(),
+      body: None,
+      follow_redirects: None,
+      max_redirects: None,
+      proxy: None,
+      verify_tls: None,
+      timeout_ms: Some(5_000),
+    }
+  }
+
+  fn header(name: &str, value: &str) -> HttpHeader {
+    HttpHeader {
+      name: name.to_string(),
+      value: value.to_string(),
+    }
+  }
+
+  /// 只处理一个连接的 HTTP 桩服务器，返回收到的请求行与请求体
+  fn stub_server(response: &'static [u8]) -> (String, JoinHandle<(String, Vec<u8>)>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/echo", listener.local_addr().unwrap());
+    let handle = std::thread::spawn(move || {
+      let (stream, _) = listener.accept().unwrap();
+      let mut reader = BufReader::new(stream);
+      let mut request_line = String::new();
+      reader.read_line(&mut request_line).unwrap();
+      let mut content_length = 0;
+      loop {
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        let line = line.trim_end();
+        if line.is_empty() {
+          break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+          if name.eq_ignore_ascii_case("content-length") {
+            content_length = value.trim().parse().unwrap();
+          }
+        }
+      }
+      let mut body = vec![0; content_length];
+      reader.read_exact(&mut body).unwrap();
+      reader.get_mut().write_all(response).unwrap();
+      (request_line.trim_end().to_string(), body)
+    });
+    (url, handle)
+  }
+
+  #[test]
+  fn rejects_zero_timeout() {
+    let mut zero = request("http://127.0.0.1:9/");
+    zero.timeout_ms = Some(0);
+    assert_eq!(
+      execute(zero).unwrap_err(),
+      "Timeout must be greater than zero"
+    );
+  }
+
+  #[test]
+  fn detects_text_content_types() {
+    assert!(is_text_content(&[]));
+    assert!(is_text_content(&[header("Content-Type", "text/html; charset=utf-8")]));
+    assert!(is_text_content(&[header("content-type", "application/problem+json")]));
+    assert!(!is_text_content(&[header("Content-Type", "image/png")]));
+  }
+
+  #[test]
+  fn sends_custom_method_with_body() {
+    let (url, server) = stub_server(
+      b"HTTP/1.1 201 Created\r\nContent-Type: application/json\r\nContent-Length: 11\r\nConnection: close\r\n\r\n{\"ok\":true}",
+    );
+    let mut put = request(&url);
+    put.method = "put".to_string();
+    put.body = Some(HttpRequestBody::Base64(BASE64.encode(b"payload")));
+    let response = execute(put).unwrap();
+    let (request_line, body) = server.join().unwrap();
+
+    assert_eq!(request_line, "PUT /echo HTTP/1.1");
+    assert_eq!(body, b"payload");
+    assert_eq!(response.status, 201);
+    assert_eq!(response.status_text, "Created");
+    assert_eq!(response.size, 11);
+    assert!(matches!(response.body, HttpResponseBody::Text(ref text) if text == "{\"ok\":true}"));
+  }
+
+  #[test]
+  fn encodes_binary_responses_as_base64() {
+    let (url, server) = stub_server(
+      b"HTTP/1.1 200 OK\r\nContent-Type: application/octet-stream\r\nContent-Length: 2\r\nConnection: close\r\n\r\n\xff\x00",
+    );
+    let response = execute(request(&url)).unwrap();
+    server.join().unwrap();
+
+    assert!(matches!(response.body, HttpResponseBody::Base64(ref data) if data == "/wA="));
+  }
+}
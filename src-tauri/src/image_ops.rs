@@ -0,0 +1,406 @@
+use std::fs::File;
+use std::io::{BufWriter, Cursor, Write};
+use std::path::Path;
+
+use image::codecs::avif::AvifEncoder;
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::png::{CompressionType, FilterType as PngFilter, PngEncoder};
+use image::codecs::webp::WebPEncoder;
+use image::imageops::FilterType;
+use image::metadata::Orientation;
+use image::{DynamicImage, ImageDecoder, ImageFormat, ImageReader};
+use img_parts::{Bytes, DynImage, ImageEXIF, ImageICC};
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_QUALITY: u8 = 85;
+
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum ImageOutputFormat {
+  Png,
+  Jpeg,
+  Webp,
+  Avif,
+  Gif,
+  Bmp,
+  Tiff,
+}
+
+impl ImageOutputFormat {
+  fn image_format(self) -> ImageFormat {
+    match self {
+      ImageOutputFormat::Png => ImageFormat::Png,
+      ImageOutputFormat::Jpeg => ImageFormat::Jpeg,
+      ImageOutputFormat::Webp => ImageFormat::WebP,
+      ImageOutputFormat::Avif => ImageFormat::Avif,
+      ImageOutputFormat::Gif => ImageFormat::Gif,
+      ImageOutputFormat::Bmp => ImageFormat::Bmp,
+      ImageOutputFormat::Tiff => ImageFormat::Tiff,
+    }
+  }
+
+  fn from_image_format(format: ImageFormat) -> Option<Self> {
+    Some(match format {
+      ImageFormat::Png => ImageOutputFormat::Png,
+      ImageFormat::Jpeg => ImageOutputFormat::Jpeg,
+      ImageFormat::WebP => ImageOutputFormat::Webp,
+      ImageFormat::Avif => ImageOutputFormat::Avif,
+      ImageFormat::Gif => ImageOutputFormat::Gif,
+      ImageFormat::Bmp => ImageOutputFormat::Bmp,
+      ImageFormat::Tiff => ImageOutputFormat::Tiff,
+      _ => return None,
+    })
+  }
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ResizeFilter {
+  #[default]
+  Lanczos,
+  Bilinear,
+  Nearest,
+}
+
+/// 按顺序执行的图片操作
+#[derive(Deserialize, Clone, Debug)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum ImageOperation {
+  Crop {
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+  },
+  /// 只给出宽或高时按比例缩放；`fit` 为真时在给定框内等比缩放
+  Resize {
+    width: Option<u32>,
+    height: Option<u32>,
+    #[serde(default)]
+    filter: ResizeFilter,
+    #[serde(default)]
+    fit: bool,
+  },
+  Rotate {
+    degrees: i32,
+  },
+  Convert {
+    format: ImageOutputFormat,
+  },
+  /// 有损格式的质量（1-100），对 PNG 映射为压缩级别；WebP 仅支持无损编码，输出 WebP 时指定质量会报错
+  Quality {
+    value: u8,
+  },
+  StripMetadata,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ImageStats {
+  pub width: u32,
+  pub height: u32,
+  pub size: u64,
+  pub format: Option<ImageOutputFormat>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ImageProcessResult {
+  pub input: String,
+  pub output: String,
+  pub before: ImageStats,
+  pub after: ImageStats,
+}
+
+struct Metadata {
+  exif: Option<Bytes>,
+  icc: Option<Bytes>,
+}
+
+fn read_metadata(data: &[u8]) -> Metadata {
+  match DynImage::from_bytes(Bytes::copy_from_slice(data)) {
+    Ok(Some(image)) => Metadata {
+      exif: image.exif(),
+      icc: image.icc_profile(),
+    },
+    _ => Metadata {
+      exif: None,
+      icc: None,
+    },
+  }
+}
+
+/// 把 EXIF（TIFF 结构）IFD0 中的 Orientation 原地改为 1（正常方向）
+fn reset_orientation(exif: &[u8]) -> Vec<u8> {
+  let mut exif = exif.to_vec();
+  let little_endian = match exif.get(0..2) {
+    Some(b"II") => true,
+    Some(b"MM") => false,
+    _ => return exif,
+  };
+  let read_u16 = |data: &[u8], at: usize| -> Option<u16> {
+    let bytes: [u8; 2] = data.get(at..at + 2)?.try_into().ok()?;
+    Some(if little_endian {
+      u16::from_le_bytes(bytes)
+    } else {
+      u16::from_be_bytes(bytes)
+    })
+  };
+  let Some(ifd) = exif.get(4..8).and_then(|bytes| <[u8; 4]>::try_from(bytes).ok()).map(|bytes| {
+    if little_endian {
+      u32::from_le_bytes(bytes)
+    } else {
+      u32::from_be_bytes(bytes)
+    }
+  }) else {
+    return exif;
+  };
+  let ifd = ifd as usize;
+  let count = read_u16(&exif, ifd).unwrap_or(0) as usize;
+  for index in 0..count {
+    let entry = ifd + 2 + index * 12;
+    // 0x0112 Orientation，类型 SHORT(3)，值直接存放在条目内
+    if read_u16(&exif, entry) == Some(0x0112) && read_u16(&exif, entry + 2) == Some(3) {
+      let one = if little_endian { 1_u16.to_le_bytes() } else { 1_u16.to_be_bytes() };
+      if let Some(value) = exif.get_mut(entry + 8..entry + 10) {
+        value.copy_from_slice(&one);
+      }
+      break;
+    }
+  }
+  exif
+}
+
+/// 把源图的 EXIF 与 ICC 写回编码结果；不支持的容器原样返回
+fn apply_metadata(encoded: Vec<u8>, metadata: &Metadata) -> Result<Vec<u8>, String> {
+  if metadata.exif.is_none() && metadata.icc.is_none() {
+    return Ok(encoded);
+  }
+  let Ok(Some(mut image)) = DynImage::from_bytes(Bytes::from(encoded.clone())) else {
+    return Ok(encoded);
+  };
+  image.set_exif(metadata.exif.clone());
+  image.set_icc_profile(metadata.icc.clone());
+  let mut output = Vec::with_capacity(encoded.len());
+  image
+    .encoder()
+    .write_to(&mut output)
+    .map_err(|e| e.to_string())?;
+  Ok(output)
+}
+
+fn resize(
+  image: DynamicImage,
+  width: Option<u32>,
+  height: Option<u32>,
+  filter: ResizeFilter,
+  fit: bool,
+) -> Result<DynamicImage, String> {
+  let filter = match filter {
+    ResizeFilter::Lanczos => FilterType::Lanczos3,
+    ResizeFilter::Bilinear => FilterType::Triangle,
+    ResizeFilter::Nearest => FilterType::Nearest,
+  };
+  let (source_width, source_height) = (image.width(), image.height());
+  let (width, height) = match (width, height) {
+    (Some(width), Some(height)) => (width, height),
+    (Some(width), None) => (
+      width,
+      ((source_height as u64 * width as u64) / source_width.max(1) as u64).max(1) as u32,
+    ),
+    (None, Some(height)) => (
+      ((source_width as u64 * height as u64) / source_height.max(1) as u64).max(1) as u32,
+      height,
+    ),
+    (None, None) => return Err("Resize requires a width or a height".to_string()),
+  };
+  if width == 0 || height == 0 {
+    return Err("Resize dimensions must be positive".to_string());
+  }
+  Ok(if fit {
+    image.resize(width, height, filter)
+  } else {
+    image.resize_exact(width, height, filter)
+  })
+}
+
+fn encode(image: &DynamicImage, format: ImageOutputFormat, quality: u8) -> Result<Vec<u8>, String> {
+  let mut buffer = Vec::new();
+  match format {
+    ImageOutputFormat::Jpeg => {
+      // JPEG 不支持透明通道
+      let rgb = DynamicImage::ImageRgb8(image.to_rgb8());
+      rgb
+        .write_with_encoder(JpegEncoder::new_with_quality(&mut buffer, quality))
+        .map_err(|e| e.to_string())?;
+    }
+    ImageOutputFormat::Png => {
+      let compression = if quality >= 90 {
+        CompressionType::Fast
+      } else if quality >= 50 {
+        CompressionType::Default
+      } else {
+        CompressionType::Best
+      };
+      image
+        .write_with_encoder(PngEncoder::new_with_quality(
+          &mut buffer,
+          compression,
+          PngFilter::Adaptive,
+        ))
+        .map_err(|e| e.to_string())?;
+    }
+    ImageOutputFormat::Webp => {
+      let rgba = DynamicImage::ImageRgba8(image.to_rgba8());
+      rgba
+        .write_with_encoder(WebPEncoder::new_lossless(&mut buffer))
+        .map_err(|e| e.to_string())?;
+    }
+    ImageOutputFormat::Avif => {
+      let rgba = DynamicImage::ImageRgba8(image.to_rgba8());
+      rgba
+        .write_with_encoder(AvifEncoder::new_with_speed_quality(&mut buffer, 6, quality))
+        .map_err(|e| e.to_string())?;
+    }
+    _ => {
+      image
+        .write_to(&mut Cursor::new(&mut buffer), format.image_format())
+        .map_err(|e| e.to_string())?;
+    }
+  }
+  Ok(buffer)
+}
+
+fn stats(image: &DynamicImage, size: u64, format: Option<ImageOutputFormat>) -> ImageStats {
+  ImageStats {
+    width: image.width(),
+    height: image.height(),
+    size,
+    format,
+  }
+}
+
+/// 处理单个文件：解码、依次执行操作、编码并写出，供单文件命令与批处理共用
+pub fn process_file(
+  input: &str,
+  output: &str,
+  operations: &[ImageOperation],
+) -> Result<ImageProcessResult, String> {
+  let data = std::fs::read(input).map_err(|e| format!("Failed to read {input}: {e}"))?;
+  let reader = ImageReader::new(Cursor::new(&data))
+    .with_guessed_format()
+    .map_err(|e| e.to_string())?;
+  let source_format = reader.format().and_then(ImageOutputFormat::from_image_format);
+  let mut decoder = reader
+    .into_decoder()
+    .map_err(|e| format!("Failed to decode {input}: {e}"))?;
+  // 先按 EXIF 方向摆正像素，后续操作的坐标与尺寸均以显示方向为准
+  let orientation = decoder.orientation().unwrap_or(Orientation::NoTransforms);
+  let mut image = DynamicImage::from_decoder(decoder).map_err(|e| format!("Failed to decode {input}: {e}"))?;
+  image.apply_orientation(orientation);
+  let before = stats(&image, data.len() as u64, source_format);
+
+  let mut format = ImageFormat::from_path(output)
+    .ok()
+    .and_then(ImageOutputFormat::from_image_format)
+    .or(source_format)
+    .unwrap_or(ImageOutputFormat::Png);
+  let mut quality = DEFAULT_QUALITY;
+  let mut quality_set = false;
+  let mut strip = false;
+  let mut transformed = orientation != Orientation::NoTransforms;
+
+  for operation in operations {
+    image = match operation {
+      ImageOperation::Crop {
+        x,
+        y,
+        width,
+        height,
+      } => {
+        if *width == 0
+          || *height == 0
+          || x.saturating_add(*width) > image.width()
+          || y.saturating_add(*height) > image.height()
+        {
+          return Err("Crop area is outside the image".to_string());
+        }
+        transformed = true;
+        image.crop_imm(*x, *y, *width, *height)
+      }
+      ImageOperation::Resize {
+        width,
+        height,
+        filter,
+        fit,
+      } => resize(image, *width, *height, *filter, *fit)?,
+      ImageOperation::Rotate { degrees } => {
+        let rotated = match degrees.rem_euclid(360) {
+          0 => image,
+          90 => image.rotate90(),
+          180 => image.rotate180(),
+          270 => image.rotate270(),
+          _ => return Err("Rotation must be a multiple of 90 degrees".to_string()),
+        };
+        transformed = true;
+        rotated
+      }
+      ImageOperation::Convert { format: target } => {
+        format = *target;
+        image
+      }
+      ImageOperation::Quality { value } => {
+        quality = (*value).clamp(1, 100);
+        quality_set = true;
+        image
+      }
+      ImageOperation::StripMetadata => {
+        strip = true;
+        image
+      }
+    };
+  }
+
+  if quality_set && format == ImageOutputFormat::Webp {
+    return Err("WebP output is lossless only; remove the quality operation or choose another format".to_string());
+  }
+
+  let encoded = encode(&image, format, quality)?;
+  let encoded = if strip {
+    encoded
+  } else {
+    let mut metadata = read_metadata(&data);
+    // 像素已被摆正或变换，保留原方向标记会让查看器再旋转一次
+    if transformed {
+      metadata.exif = metadata.exif.map(|exif| Bytes::from(reset_orientation(&exif)));
+    }
+    apply_metadata(encoded, &metadata)?
+  };
+
+  if let Some(parent) = Path::new(output).parent() {
+    std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+  }
+  let file = File::create(output).map_err(|e| format!("Failed to create {output}: {e}"))?;
+  let mut writer = BufWriter::new(file);
+  writer
+    .write_all(&encoded)
+    .and_then(|_| writer.flush())
+    .map_err(|e| format!("Failed to write {output}: {e}"))?;
+
+  Ok(ImageProcessResult {
+    input: input.to_string(),
+    output: output.to_string(),
+    before,
+    after: stats(&image, encoded.len() as u64, Some(format)),
+  })
+}
+
+/// 原生图片处理，供 image-compress / image-resize / image-convert / image-crop 使用
+#[tauri::command]
+pub async fn image_process(
+  input: String,
+  output: String,
+  operations: Vec<ImageOperation>,
+) -> Result<ImageProcessResult, String> {
+  tauri::async_runtime::spawn_blocking(move || process_file(&input, &output, &operations))
+    .await
+    .map_err(|e| e.to_string())?
+}
@@ -0,0 +1,185 @@
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::Mutex;
+
+use tauri::{AppHandle, Manager, Runtime, State, Url};
+use tauri_plugin_deep_link::DeepLinkExt;
+
+use crate::tray::{self, NavigatePayload};
+
+const URL_SCHEME: &str = "kit";
+/// `launch_read_file` 读取文本的上限
+const MAX_FILE_SIZE: u64 = 16 * 1024 * 1024;
+
+/// 冷启动时携带的导航请求，等前端就绪后通过 `launch_take_pending` 取走
+#[derive(Default)]
+pub struct LaunchState {
+  pending: Mutex<Option<NavigatePayload>>,
+  /// 命令行或深链接传入的文件；前端只能读取这些文件
+  files: Mutex<HashSet<String>>,
+}
+
+pub fn is_valid_slug(slug: &str) -> bool {
+  !slug.is_empty()
+    && slug
+      .chars()
+      .all(|ch| ch.is_ascii_lowercase() || ch.is_ascii_digit() || ch == '-')
+}
+
+fn resolve_file(file: String, cwd: Option<&str>) -> String {
+  let path = Path::new(&file);
+  match cwd {
+    Some(cwd) if path.is_relative() => Path::new(cwd).join(path).to_string_lossy().into_owned(),
+    _ => file,
+  }
+}
+
+/// 解析 `kit://tool/<slug>?input=...&file=...`
+pub fn parse_url(url: &Url) -> Option<NavigatePayload> {
+  if url.scheme() != URL_SCHEME || url.host_str() != Some("tool") {
+    return None;
+  }
+  let slug = url.path().trim_matches('/').to_string();
+  if !is_valid_slug(&slug) {
+    return None;
+  }
+  let mut payload = NavigatePayload {
+    slug,
+    ..Default::default()
+  };
+  for (key, value) in url.query_pairs() {
+    match key.as_ref() {
+      "input" => payload.input = Some(value.into_owned()),
+      "file" => payload.file = Some(value.into_owned()),
+      _ => {}
+    }
+  }
+  Some(payload)
+}
+
+/// 解析命令行参数：`--tool <slug> [--file <path>] [--input <text>]`，
+/// 或直接传入一个 `kit://` 链接（Windows/Linux 下系统以此方式转交深链接）
+pub fn parse_args(args: &[String], cwd: Option<&str>) -> Option<NavigatePayload> {
+  let mut slug = None;
+  let mut input = None;
+  let mut file = None;
+  let mut iter = args.iter().skip(1);
+
+  while let Some(arg) = iter.next() {
+    if arg.starts_with(&format!("{URL_SCHEME}://")) {
+      if let Some(mut payload) = Url::parse(arg).ok().as_ref().and_then(parse_url) {
+        payload.file = payload.file.map(|file| resolve_file(file, cwd));
+        return Some(payload);
+      }
+      continue;
+    }
+    let (flag, inline) = match arg.split_once('=') {
+      Some((flag, value)) => (flag, Some(value.to_string())),
+      None => (arg.as_str(), None),
+    };
+    let mut value = || inline.clone().or_else(|| iter.next().cloned());
+    match flag {
+      "--tool" | "-t" => slug = value(),
+      "--input" | "-i" => input = value(),
+      "--file" | "-f" => file = value(),
+      _ => {}
+    }
+  }
+
+  let slug = slug.filter(|slug| is_valid_slug(slug))?;
+  Some(NavigatePayload {
+    slug,
+    input,
+    file: file.map(|file| resolve_file(file, cwd)),
+  })
+}
+
+/// 导航到工具，并允许前端读取请求中携带的文件
+fn navigate<R: Runtime>(app: &AppHandle<R>, payload: NavigatePayload) {
+  if let Some(file) = &payload.file {
+    if let Ok(mut files) = app.state::<LaunchState>().files.lock() {
+      files.insert(file.clone());
+    }
+  }
+  tray::navigate(app, payload);
+}
+
+/// 处理第二个实例转交过来的参数：不再启动新进程，而是在现有窗口中导航
+pub fn handle_second_instance<R: Runtime>(app: &AppHandle<R>, args: Vec<String>, cwd: String) {
+  match parse_args(&args, Some(&cwd)) {
+    Some(payload) => navigate(app, payload),
+    None => tray::show_main_window(app),
+  }
+}
+
+/// 处理运行中收到的深链接
+pub fn handle_urls<R: Runtime>(app: &AppHandle<R>, urls: &[Url]) {
+  if let Some(payload) = urls.iter().find_map(parse_url) {
+    navigate(app, payload);
+  }
+}
+
+/// 记录冷启动时的导航请求，并同时尝试直接发给前端
+fn set_pending<R: Runtime>(app: &AppHandle<R>, payload: NavigatePayload) {
+  if let Ok(mut pending) = app.state::<LaunchState>().pending.lock() {
+    *pending = Some(payload.clone());
+  }
+  navigate(app, payload);
+}
+
+/// 注册深链接监听，并处理冷启动时的命令行参数或链接
+pub fn init<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+  // Linux 与 Windows 开发模式下需要在运行时注册 URL scheme；注册失败只影响深链接，不阻塞启动
+  #[cfg(any(target_os = "linux", all(debug_assertions, windows)))]
+  if let Err(error) = app.deep_link().register_all() {
+    log::warn!("Failed to register {URL_SCHEME}:// URL scheme: {error}");
+  }
+
+  let handle = app.clone();
+  app
+    .deep_link()
+    .on_open_url(move |event| handle_urls(&handle, &event.urls()));
+
+  let args: Vec<String> = std::env::args().collect();
+  let cwd = std::env::current_dir()
+    .ok()
+    .map(|dir| dir.to_string_lossy().into_owned());
+  let payload = parse_args(&args, cwd.as_deref()).or_else(|| {
+    app
+      .deep_link()
+      .get_current()
+      .ok()
+      .flatten()
+      .and_then(|urls| urls.iter().find_map(parse_url))
+  });
+  if let Some(payload) = payload {
+    set_pending(app, payload);
+  }
+  Ok(())
+}
+
+/// 取走冷启动时的导航请求，前端初始化完成后调用一次
+#[tauri::command]
+pub fn launch_take_pending(state: State<'_, LaunchState>) -> Result<Option<NavigatePayload>, String> {
+  Ok(state.pending.lock().map_err(|e| e.to_string())?.take())
+}
+
+/// 读取导航请求中携带的文件内容，供工具作为初始输入；非 UTF-8 字节按替换字符处理
+#[tauri::command]
+pub async fn launch_read_file(state: State<'_, LaunchState>, path: String) -> Result<String, String> {
+  if !state.files.lock().map_err(|e| e.to_string())?.contains(&path) {
+    return Err(format!("{path} was not passed to Kit on launch"));
+  }
+  tauri::async_runtime::spawn_blocking(move || {
+    let size = std::fs::metadata(&path)
+      .map_err(|e| format!("Failed to read {path}: {e}"))?
+      .len();
+    if size > MAX_FILE_SIZE {
+      return Err(format!("{path} is larger than {} MB", MAX_FILE_SIZE / 1024 / 1024));
+    }
+    let data = std::fs::read(&path).map_err(|e| format!("Failed to read {path}: {e}"))?;
+    Ok(String::from_utf8_lossy(&data).into_owned())
+  })
+  .await
+  .map_err(|e| e.to_string())?
+}
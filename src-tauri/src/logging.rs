@@ -0,0 +1,312 @@
+use std::collections::VecDeque;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tauri::{AppHandle, Manager, Runtime};
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+
+const LOG_FILE: &str = "kit.log";
+/// 单个日志文件上限，超过后轮转为 `kit.1.log`、`kit.2.log` …
+const MAX_FILE_SIZE: u64 = 5 * 1024 * 1024;
+const MAX_ROTATED_FILES: usize = 5;
+/// 诊断包中保留的最近前端错误与命令失败条数
+const MAX_RECENT: usize = 200;
+
+static LOGGER: OnceLock<FileLogger> = OnceLock::new();
+
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+  Trace,
+  Debug,
+  Info,
+  Warn,
+  Error,
+}
+
+impl From<LogLevel> for Level {
+  fn from(level: LogLevel) -> Self {
+    match level {
+      LogLevel::Trace => Level::Trace,
+      LogLevel::Debug => Level::Debug,
+      LogLevel::Info => Level::Info,
+      LogLevel::Warn => Level::Warn,
+      LogLevel::Error => Level::Error,
+    }
+  }
+}
+
+/// 最近的失败记录，写入诊断包
+#[derive(Serialize, Clone, Debug)]
+pub struct FailureRecord {
+  pub timestamp: String,
+  pub source: String,
+  pub message: String,
+  pub fields: Option<Value>,
+}
+
+struct LogFile {
+  dir: PathBuf,
+  file: Option<File>,
+  size: u64,
+}
+
+struct FileLogger {
+  level: LevelFilter,
+  file: Mutex<LogFile>,
+  webview_errors: Mutex<VecDeque<FailureRecord>>,
+  command_failures: Mutex<VecDeque<FailureRecord>>,
+}
+
+fn now() -> String {
+  chrono::Local::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, false)
+}
+
+fn rotated_path(dir: &Path, index: usize) -> PathBuf {
+  match index {
+    0 => dir.join(LOG_FILE),
+    index => dir.join(format!("kit.{index}.log")),
+  }
+}
+
+fn push_recent(queue: &Mutex<VecDeque<FailureRecord>>, record: FailureRecord) {
+  if let Ok(mut queue) = queue.lock() {
+    if queue.len() >= MAX_RECENT {
+      queue.pop_front();
+    }
+    queue.push_back(record);
+  }
+}
+
+impl LogFile {
+  fn open(&mut self) -> std::io::Result<&mut File> {
+    if self.file.is_none() {
+      let path = rotated_path(&self.dir, 0);
+      let file = OpenOptions::new().create(true).append(true).open(&path)?;
+      self.size = file.metadata().map(|meta| meta.len()).unwrap_or(0);
+      self.file = Some(file);
+    }
+    Ok(self.file.as_mut().expect("log file is open"))
+  }
+
+  fn rotate(&mut self) -> std::io::Result<()> {
+    self.file = None;
+    let _ = std::fs::remove_file(rotated_path(&self.dir, MAX_ROTATED_FILES));
+    for index in (0..MAX_ROTATED_FILES).rev() {
+      let from = rotated_path(&self.dir, index);
+      if from.exists() {
+        std::fs::rename(&from, rotated_path(&self.dir, index + 1))?;
+      }
+    }
+    self.size = 0;
+    Ok(())
+  }
+
+  fn write_line(&mut self, line: &str) -> std::io::Result<()> {
+    if self.size + line.len() as u64 > MAX_FILE_SIZE {
+      self.rotate()?;
+    }
+    let file = self.open()?;
+    file.write_all(line.as_bytes())?;
+    self.size += line.len() as u64;
+    Ok(())
+  }
+}
+
+impl FileLogger {
+  fn write(&self, level: Level, target: &str, message: &str, fields: Option<&Value>) {
+    let mut entry = json!({
+      "ts": now(),
+      "level": level.as_str().to_lowercase(),
+      "target": target,
+      "message": message,
+    });
+    if let Some(fields) = fields {
+      entry["fields"] = fields.clone();
+    }
+    if let Ok(mut file) = self.file.lock() {
+      let _ = file.write_line(&format!("{entry}\n"));
+    }
+    if cfg!(debug_assertions) {
+      eprintln!("[{level}] {target}: {message}");
+    }
+  }
+}
+
+impl Log for FileLogger {
+  fn enabled(&self, metadata: &Metadata) -> bool {
+    metadata.level() <= self.level
+  }
+
+  fn log(&self, record: &Record) {
+    if self.enabled(record.metadata()) {
+      self.write(record.level(), record.target(), &record.args().to_string(), None);
+    }
+  }
+
+  fn flush(&self) {
+    if let Ok(mut file) = self.file.lock() {
+      if let Some(file) = file.file.as_mut() {
+        let _ = file.flush();
+      }
+    }
+  }
+}
+
+/// 初始化文件日志，日志写入应用日志目录；之后可直接使用 `log::info!` 等宏
+pub fn init<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+  let dir = app.path().app_log_dir().map_err(|e| e.to_string())?;
+  std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+  let level = if cfg!(debug_assertions) {
+    LevelFilter::Debug
+  } else {
+    LevelFilter::Info
+  };
+  let logger = LOGGER.get_or_init(|| FileLogger {
+    level,
+    file: Mutex::new(LogFile {
+      dir,
+      file: None,
+      size: 0,
+    }),
+    webview_errors: Mutex::new(VecDeque::new()),
+    command_failures: Mutex::new(VecDeque::new()),
+  });
+  log::set_logger(logger).map_err(|e| e.to_string())?;
+  log::set_max_level(level);
+  log::info!(
+    "Kit {} starting on {} ({})",
+    app.package_info().version,
+    os_info::get(),
+    std::env::consts::ARCH
+  );
+  Ok(())
+}
+
+/// 记录一次命令或后台任务失败，同时写入日志
+pub fn record_failure(source: &str, message: &str) {
+  log::error!(target: "command", "{source} failed: {message}");
+  if let Some(logger) = LOGGER.get() {
+    push_recent(
+      &logger.command_failures,
+      FailureRecord {
+        timestamp: now(),
+        source: source.to_string(),
+        message: message.to_string(),
+        fields: None,
+      },
+    );
+  }
+}
+
+fn snapshot(queue: &Mutex<VecDeque<FailureRecord>>) -> Vec<FailureRecord> {
+  queue
+    .lock()
+    .map(|queue| queue.iter().cloned().collect())
+    .unwrap_or_default()
+}
+
+fn write_bundle<R: Runtime>(app: &AppHandle<R>, path: &Path) -> Result<(), String> {
+  let logger = LOGGER.get().ok_or("Logging is not initialized")?;
+  log::logger().flush();
+
+  let file = File::create(path).map_err(|e| format!("Failed to create {}: {e}", path.display()))?;
+  let mut zip = ZipWriter::new(file);
+  let options = SimpleFileOptions::default();
+  let mut add = |name: &str, content: &[u8]| -> Result<(), String> {
+    zip.start_file(name, options).map_err(|e| e.to_string())?;
+    zip.write_all(content).map_err(|e| e.to_string())
+  };
+
+  let system = json!({
+    "app_name": app.package_info().name.clone(),
+    "app_version": app.package_info().version.to_string(),
+    "tauri_version": tauri::VERSION,
+    "webview_version": tauri::webview_version().ok(),
+    "os": os_info::get().to_string(),
+    "os_family": std::env::consts::OS,
+    "arch": std::env::consts::ARCH,
+    "generated_at": now(),
+  });
+  add("system.json", &serde_json::to_vec_pretty(&system).map_err(|e| e.to_string())?)?;
+  add(
+    "webview-errors.json",
+    &serde_json::to_vec_pretty(&snapshot(&logger.webview_errors)).map_err(|e| e.to_string())?,
+  )?;
+  add(
+    "command-failures.json",
+    &serde_json::to_vec_pretty(&snapshot(&logger.command_failures)).map_err(|e| e.to_string())?,
+  )?;
+
+  let dir = logger.file.lock().map_err(|e| e.to_string())?.dir.clone();
+  for index in 0..=MAX_ROTATED_FILES {
+    let log_path = rotated_path(&dir, index);
+    if let Ok(content) = std::fs::read(&log_path) {
+      let name = log_path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+      add(&format!("logs/{name}"), &content)?;
+    }
+  }
+
+  zip.finish().map_err(|e| e.to_string())?;
+  Ok(())
+}
+
+/// 前端日志转发：写入同一份日志文件，错误级别同时记入诊断包；带 `command` 的错误视为命令失败
+#[tauri::command]
+pub fn log_write(
+  level: LogLevel,
+  message: String,
+  target: Option<String>,
+  fields: Option<Value>,
+  command: Option<String>,
+) {
+  let Some(logger) = LOGGER.get() else {
+    return;
+  };
+  let target = target.unwrap_or_else(|| "webview".to_string());
+  let level = Level::from(level);
+  if level <= logger.level {
+    logger.write(level, &target, &message, fields.as_ref());
+  }
+  if level == Level::Error {
+    let record = FailureRecord {
+      timestamp: now(),
+      source: command.clone().unwrap_or_else(|| target.clone()),
+      message,
+      fields,
+    };
+    let queue = if command.is_some() {
+      &logger.command_failures
+    } else {
+      &logger.webview_errors
+    };
+    push_recent(queue, record);
+  }
+}
+
+/// 生成诊断包（zip），包含日志、应用与系统版本、前端错误与最近的命令失败；返回文件路径
+#[tauri::command]
+pub async fn diagnostics_bundle(app_handle: AppHandle, path: Option<String>) -> Result<String, String> {
+  let path = match path {
+    Some(path) => PathBuf::from(path),
+    None => {
+      let dir = app_handle.path().app_cache_dir().map_err(|e| e.to_string())?;
+      std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+      dir.join(format!(
+        "kit-diagnostics-{}.zip",
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+      ))
+    }
+  };
+  let bundle_path = path.clone();
+  tauri::async_runtime::spawn_blocking(move || write_bundle(&app_handle, &bundle_path))
+    .await
+    .map_err(|e| e.to_string())??;
+  Ok(path.to_string_lossy().into_owned())
+}
@@ -1,28 +1,33 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-use serde::Serialize;
-use tauri::{AppHandle, Emitter, WebviewWindow};
+mod app_data;
+mod batch;
+mod cli;
+mod dns;
+mod gif_ops;
+mod hash;
+mod history;
+mod http;
+mod image_ops;
+mod launch;
+mod logging;
+mod metadata;
+mod ops;
+mod pdf;
+mod settings;
+mod shortcut;
+mod storage;
+mod task;
+mod text_pdf;
+mod tool_window;
+mod tray;
+mod updater;
+mod window_state;
+
+use std::sync::Arc;
+
+use tauri::{AppHandle, Manager, RunEvent, WebviewWindow, WindowEvent};
 use tauri_plugin_shell::ShellExt;
-use tauri_plugin_updater::UpdaterExt;
-
-/// 更新信息，复用现有 Electron 侧的字段语义
-#[derive(Serialize, Clone)]
-struct UpdateInfo {
-  version: String,
-  date: Option<String>,
-  body: Option<String>,
-}
-
-#[derive(Serialize, Clone)]
-#[serde(tag = "event", content = "data")]
-enum UpdateProgressEvent {
-  Started { content_length: Option<u64> },
-  Progress {
-    downloaded: u64,
-    content_length: Option<u64>,
-  },
-  Finished {},
-}
 
 /// 打开外部链接
 #[tauri::command]
@@ -41,7 +46,7 @@ fn relaunch(app_handle: AppHandle) {
   app_handle.restart();
 }
 
-/// 窗口控制相关命令
+/// 窗口控制相关命令，均作用于发起调用的窗口（主窗口或独立工具窗口）
 #[tauri::command]
 fn window_minimize(window: WebviewWindow) -> Result<(), String> {
   window.minimize().map_err(|e| e.to_string())
@@ -56,9 +61,10 @@ fn window_maximize_toggle(window: WebviewWindow) -> Result<(), String> {
   }
 }
 
+/// 关闭窗口；主窗口开启托盘模式时改为隐藏
 #[tauri::command]
 fn window_close(window: WebviewWindow) -> Result<(), String> {
-  window.close().map_err(|e| e.to_string())
+  tray::close_or_hide(&window).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -66,97 +72,57 @@ fn window_is_maximized(window: WebviewWindow) -> Result<bool, String> {
   Ok(window.is_maximized().unwrap_or(false))
 }
 
-/// 检查是否有可用更新
-#[tauri::command]
-async fn updater_check(app_handle: AppHandle) -> Result<Option<UpdateInfo>, String> {
-  let updater = app_handle.updater_builder().build().map_err(|e| e.to_string())?;
-  let update = updater.check().await.map_err(|e| e.to_string())?;
-
-  if let Some(info) = update {
-    Ok(Some(UpdateInfo {
-      version: info.version,
-      date: info.date.map(|date| date.to_string()),
-      body: info.body,
-    }))
-  } else {
-    Ok(None)
-  }
-}
-
-/// 下载并安装更新，同时通过事件推送进度
-#[tauri::command]
-async fn updater_download_and_install(app_handle: AppHandle) -> Result<(), String> {
-  let updater = app_handle.updater_builder().build().map_err(|e| e.to_string())?;
-  let update = updater
-    .check()
-    .await
-    .map_err(|e| e.to_string())?
-    .ok_or_else(|| "No update available".to_string())?;
-  let mut downloaded = 0_u64;
-  let mut emit_error: Option<String> = None;
-
-  app_handle
-    .emit(
-      "updater:progress",
-      UpdateProgressEvent::Started {
-        content_length: None,
-      },
-    )
-    .map_err(|e| e.to_string())?;
-
-  update
-    .download_and_install(
-      |chunk_length, content_length| {
-        if emit_error.is_some() {
-          return;
-        }
-        downloaded += chunk_length as u64;
-        if let Err(error) = app_handle.emit(
-          "updater:progress",
-          UpdateProgressEvent::Progress {
-            downloaded,
-            content_length,
-          },
-        ) {
-          emit_error = Some(error.to_string());
-        }
-      },
-      || {},
-    )
-    .await
-    .map_err(|e| e.to_string())?;
-
-  if let Some(error) = emit_error {
-    return Err(error);
+fn main() {
+  // `kit run <op>` 以无界面模式执行，不创建任何窗口
+  let args: Vec<String> = std::env::args().collect();
+  if let Some(code) = cli::try_run(&args) {
+    std::process::exit(code);
   }
 
-  app_handle
-    .emit("updater:progress", UpdateProgressEvent::Finished {})
-    .map_err(|e| e.to_string())?;
-
-  Ok(())
-}
-
-/// 执行更新安装（通常在下载完成后调用）
-#[tauri::command]
-async fn updater_install(app_handle: AppHandle) -> Result<(), String> {
-  let updater = app_handle.updater_builder().build().map_err(|e| e.to_string())?;
-  let update = updater
-    .check()
-    .await
-    .map_err(|e| e.to_string())?
-    .ok_or_else(|| "No update available".to_string())?;
-  update
-    .download_and_install(|_, _| {}, || {})
-    .await
-    .map_err(|e| e.to_string())?;
-  Ok(())
-}
-
-fn main() {
   tauri::Builder::default()
+    // 单实例插件需要最先注册，第二个进程的参数会转交给现有窗口
+    .plugin(tauri_plugin_single_instance::init(|app, args, cwd| {
+      launch::handle_second_instance(app, args, cwd)
+    }))
+    .plugin(tauri_plugin_deep_link::init())
     .plugin(tauri_plugin_shell::init())
     .plugin(tauri_plugin_updater::Builder::new().build())
+    .plugin(tauri_plugin_notification::init())
+    .plugin(
+      tauri_plugin_global_shortcut::Builder::new()
+        .with_handler(|app, shortcut, event| shortcut::handle(app, shortcut, event.state()))
+        .build(),
+    )
+    .manage(tray::TrayState::default())
+    .manage(shortcut::ShortcutRegistry::default())
+    .manage(launch::LaunchState::default())
+    .manage(window_state::WindowStateStore::default())
+    .manage(tool_window::ToolWindowState::default())
+    .manage(updater::UpdaterState::default())
+    .setup(|app| {
+      logging::init(app.handle())?;
+      app.manage(task::TaskRegistry::new(Arc::new(app.handle().clone())));
+      settings::init(app.handle())?;
+      history::init(app.handle())?;
+      window_state::init(app.handle())?;
+      tool_window::init(app.handle())?;
+      tray::init(app.handle())?;
+      shortcut::init(app.handle())?;
+      launch::init(app.handle())?;
+      updater::init(app.handle());
+      Ok(())
+    })
+    .on_window_event(|window, event| match event {
+      WindowEvent::Moved(_) | WindowEvent::Resized(_) => window_state::save(window, false),
+      WindowEvent::CloseRequested { api, .. } => {
+        window_state::save(window, true);
+        if window.label() == "main" && tray::close_to_tray(window.app_handle()) {
+          api.prevent_close();
+          let _ = window.hide();
+        }
+      }
+      _ => {}
+    })
     .invoke_handler(tauri::generate_handler![
       open_external,
       relaunch,
@@ -164,10 +130,75 @@ fn main() {
       window_maximize_toggle,
       window_close,
       window_is_maximized,
-      updater_check,
-      updater_download_and_install,
-      updater_install
+      updater::updater_check,
+      updater::updater_set_channel,
+      updater::updater_download,
+      updater::updater_download_and_install,
+      updater::updater_install,
+      updater::updater_staged,
+      updater::updater_set_install_on_quit,
+      updater::updater_install_from_file,
+      updater::updater_skip_version,
+      updater::updater_snooze,
+      updater::updater_set_schedule,
+      hash::hash_file,
+      hash::hash_cancel,
+      dns::dns_query,
+      http::http_request,
+      task::task_list,
+      task::task_status,
+      task::task_cancel,
+      pdf::pdf_merge,
+      pdf::pdf_split,
+      pdf::pdf_extract_pages,
+      pdf::pdf_rotate,
+      pdf::images_to_pdf,
+      text_pdf::text_to_pdf,
+      image_ops::image_process,
+      batch::batch_run,
+      metadata::metadata_read,
+      metadata::metadata_write,
+      gif_ops::gif_split,
+      gif_ops::gif_assemble,
+      gif_ops::gif_info,
+      tray::tray_set_tools,
+      tray::tray_set_close_to_tray,
+      shortcut::shortcut_register,
+      shortcut::shortcut_unregister,
+      shortcut::shortcut_list,
+      launch::launch_take_pending,
+      launch::launch_read_file,
+      ops::base64_transform,
+      ops::url_transform,
+      ops::uuid_generate,
+      ops::json_format_text,
+      ops::timestamp_convert,
+      window_state::window_state_get,
+      window_state::window_state_reset,
+      tool_window::window_open_tool,
+      tool_window::window_list,
+      settings::settings_get,
+      settings::settings_set,
+      settings::settings_watch,
+      settings::settings_import_legacy,
+      history::history_add,
+      history::history_query,
+      history::history_pin,
+      history::history_delete,
+      history::history_clear,
+      history::history_set_retention,
+      history::history_get_retention,
+      app_data::data_export,
+      app_data::data_import,
+      logging::log_write,
+      logging::diagnostics_bundle
     ])
-    .run(tauri::generate_context!())
-    .expect("error while running tauri application");
+    .build(tauri::generate_context!())
+    .expect("error while building tauri application")
+    .run(|app, event| {
+      if let RunEvent::Exit = event {
+        window_state::flush(app);
+        updater::install_on_exit(app);
+      }
+    });
 }
@@ -0,0 +1,2291 @@
+use std::io::{BufReader, Cursor};
+use std::ops::Range;
+use std::path::Path;
+
+use exif::{Context, Field, In, Rational, Tag, Value};
+use img_parts::jpeg::{markers, Jpeg, JpegSegment};
+use img_parts::png::{Png, PngChunk};
+use img_parts::riff::{RiffChunk, RiffContent};
+use img_parts::webp::{WebP, CHUNK_ALPH, CHUNK_EXIF, CHUNK_ICCP, CHUNK_VP8L, CHUNK_VP8X, CHUNK_XMP};
+use img_parts::{Bytes, ImageEXIF};
+use serde::{Deserialize, Serialize};
+
+const XMP_JPEG_PREFIX: &[u8] = b"http://ns.adobe.com/xap/1.0/\0";
+const XMP_PNG_KEYWORD: &[u8] = b"XML:com.adobe.xmp";
+const PHOTOSHOP_PREFIX: &[u8] = b"Photoshop 3.0\0";
+const IPTC_RESOURCE_ID: u16 = 0x0404;
+const TIFF_TAG_XMP: u16 = 700;
+const TIFF_TAG_IPTC: u16 = 33723;
+const TIFF_TAG_PHOTOSHOP: u16 = 34377;
+const TIFF_TAG_EXIF_IFD: u16 = 34665;
+const TIFF_TAG_GPS_IFD: u16 = 34853;
+const TIFF_TAG_INTEROP_IFD: u16 = 40965;
+/// 遍历 IFD 链的上限，防止循环引用
+const MAX_IFD_COUNT: usize = 32;
+const PNG_IPTC_KEYWORD: &[u8] = b"Raw profile type iptc";
+const HEIC_XMP_MIME: &[u8] = b"application/rdf+xml";
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum MetadataFormat {
+  Jpeg,
+  Png,
+  Webp,
+  Tiff,
+  Heic,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ExifEntry {
+  pub ifd: String,
+  pub tag: String,
+  pub value: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct GpsInfo {
+  pub latitude: f64,
+  pub longitude: f64,
+  pub altitude: Option<f64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct IptcEntry {
+  pub record: u8,
+  pub dataset: u8,
+  #[serde(default)]
+  pub name: String,
+  pub value: String,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct MetadataReport {
+  pub format: MetadataFormat,
+  pub exif: Vec<ExifEntry>,
+  pub gps: Option<GpsInfo>,
+  pub iptc: Vec<IptcEntry>,
+  pub xmp: Option<String>,
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum StripMode {
+  #[default]
+  Keep,
+  /// 仅移除 GPS 信息，其余 EXIF 保留
+  Gps,
+  /// 移除 EXIF、IPTC 与 XMP，保留 ICC 色彩配置
+  All,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct ExifEdit {
+  pub tag: String,
+  pub value: String,
+}
+
+/// 元数据修改请求；先执行 strip，再应用各项修改
+#[derive(Deserialize, Clone, Debug, Default)]
+pub struct MetadataWrite {
+  #[serde(default)]
+  pub strip: StripMode,
+  #[serde(default)]
+  pub exif: Vec<ExifEdit>,
+  pub gps: Option<GpsInfo>,
+  /// 替换后的 IPTC 数据集（record 2）；为空数组表示清除
+  pub iptc: Option<Vec<IptcEntry>>,
+  /// 完整的 XMP 包；空字符串表示清除
+  pub xmp: Option<String>,
+}
+
+fn detect_format(data: &[u8]) -> Result<MetadataFormat, String> {
+  if data.starts_with(&[0xFF, 0xD8]) {
+    Ok(MetadataFormat::Jpeg)
+  } else if data.starts_with(b"\x89PNG\r\n\x1a\n") {
+    Ok(MetadataFormat::Png)
+  } else if data.len() >= 12 && &data[0..4] == b"RIFF" && &data[8..12] == b"WEBP" {
+    Ok(MetadataFormat::Webp)
+  } else if data.starts_with(b"II*\0") || data.starts_with(b"MM\0*") {
+    Ok(MetadataFormat::Tiff)
+  } else if data.len() >= 12
+    && &data[4..8] == b"ftyp"
+    && matches!(&data[8..12], b"heic" | b"heix" | b"heim" | b"heis" | b"mif1" | b"msf1")
+  {
+    Ok(MetadataFormat::Heic)
+  } else {
+    Err("Unsupported image format".to_string())
+  }
+}
+
+fn iptc_dataset_name(dataset: u8) -> &'static str {
+  match dataset {
+    5 => "ObjectName",
+    15 => "Category",
+    25 => "Keywords",
+    40 => "SpecialInstructions",
+    55 => "DateCreated",
+    80 => "By-line",
+    85 => "By-lineTitle",
+    90 => "City",
+    95 => "Province-State",
+    101 => "Country",
+    105 => "Headline",
+    110 => "Credit",
+    115 => "Source",
+    116 => "CopyrightNotice",
+    120 => "Caption-Abstract",
+    122 => "Writer-Editor",
+    _ => "",
+  }
+}
+
+/// 解析 IPTC-IIM 数据集
+fn parse_iim(data: &[u8]) -> Vec<IptcEntry> {
+  let mut entries = Vec::new();
+  let mut pos = 0;
+  while pos + 5 <= data.len() && data[pos] == 0x1C {
+    let record = data[pos + 1];
+    let dataset = data[pos + 2];
+    let len = u16::from_be_bytes([data[pos + 3], data[pos + 4]]) as usize;
+    pos += 5;
+    // 扩展长度的数据集不常见，遇到时直接停止解析
+    if len & 0x8000 != 0 || pos + len > data.len() {
+      break;
+    }
+    if record == 2 && dataset != 0 {
+      entries.push(IptcEntry {
+        record,
+        dataset,
+        name: iptc_dataset_name(dataset).to_string(),
+        value: String::from_utf8_lossy(&data[pos..pos + len]).into_owned(),
+      });
+    }
+    pos += len;
+  }
+  entries
+}
+
+fn build_iim(entries: &[IptcEntry]) -> Vec<u8> {
+  // 2:00 记录版本号，随后声明 UTF-8 编码（1:90）
+  let mut data = vec![0x1C, 1, 90, 0, 3, 0x1B, 0x25, 0x47];
+  data.extend_from_slice(&[0x1C, 2, 0, 0, 2, 0, 4]);
+  for entry in entries {
+    let value = entry.value.as_bytes();
+    let len = value.len().min(0x7FFF);
+    data.extend_from_slice(&[0x1C, 2, entry.dataset]);
+    data.extend_from_slice(&(len as u16).to_be_bytes());
+    data.extend_from_slice(&value[..len]);
+  }
+  data
+}
+
+/// Photoshop 图像资源块（8BIM）
+struct Resource {
+  id: u16,
+  name: Vec<u8>,
+  data: Vec<u8>,
+}
+
+fn parse_resources(data: &[u8]) -> Vec<Resource> {
+  let mut resources = Vec::new();
+  let mut pos = 0;
+  while pos + 12 <= data.len() && &data[pos..pos + 4] == b"8BIM" {
+    let id = u16::from_be_bytes([data[pos + 4], data[pos + 5]]);
+    pos += 6;
+    let name_len = data[pos] as usize;
+    let name_total = (name_len + 2) & !1;
+    if pos + name_total + 4 > data.len() {
+      break;
+    }
+    let name = data[pos + 1..pos + 1 + name_len].to_vec();
+    pos += name_total;
+    let size = u32::from_be_bytes([data[pos], data[pos + 1], data[pos + 2], data[pos + 3]]) as usize;
+    pos += 4;
+    if pos + size > data.len() {
+      break;
+    }
+    resources.push(Resource {
+      id,
+      name,
+      data: data[pos..pos + size].to_vec(),
+    });
+    pos += (size + 1) & !1;
+  }
+  resources
+}
+
+fn build_resources(resources: &[Resource]) -> Vec<u8> {
+  let mut data = Vec::new();
+  for resource in resources {
+    data.extend_from_slice(b"8BIM");
+    data.extend_from_slice(&resource.id.to_be_bytes());
+    data.push(resource.name.len() as u8);
+    data.extend_from_slice(&resource.name);
+    if (resource.name.len() + 1) % 2 == 1 {
+      data.push(0);
+    }
+    data.extend_from_slice(&(resource.data.len() as u32).to_be_bytes());
+    data.extend_from_slice(&resource.data);
+    if resource.data.len() % 2 == 1 {
+      data.push(0);
+    }
+  }
+  data
+}
+
+fn rational_to_f64(values: &[Rational]) -> Option<f64> {
+  let parts: Vec<f64> = values.iter().map(|value| value.to_f64()).collect();
+  match parts.as_slice() {
+    [degrees, minutes, seconds, ..] => Some(degrees + minutes / 60.0 + seconds / 3600.0),
+    [degrees] => Some(*degrees),
+    _ => None,
+  }
+}
+
+fn gps_from_exif(exif: &exif::Exif) -> Option<GpsInfo> {
+  let coordinate = |value_tag: Tag, ref_tag: Tag, negative: &str| -> Option<f64> {
+    let value = match &exif.get_field(value_tag, In::PRIMARY)?.value {
+      Value::Rational(values) => rational_to_f64(values)?,
+      _ => return None,
+    };
+    let reference = exif
+      .get_field(ref_tag, In::PRIMARY)
+      .map(|field| field.display_value().to_string())
+      .unwrap_or_default();
+    Some(if reference.contains(negative) { -value } else { value })
+  };
+  let latitude = coordinate(Tag::GPSLatitude, Tag::GPSLatitudeRef, "S")?;
+  let longitude = coordinate(Tag::GPSLongitude, Tag::GPSLongitudeRef, "W")?;
+  let altitude = match exif.get_field(Tag::GPSAltitude, In::PRIMARY).map(|f| &f.value) {
+    Some(Value::Rational(values)) if !values.is_empty() => {
+      let below = matches!(
+        exif.get_field(Tag::GPSAltitudeRef, In::PRIMARY).map(|f| &f.value),
+        Some(Value::Byte(bytes)) if bytes.first() == Some(&1)
+      );
+      let altitude = values[0].to_f64();
+      Some(if below { -altitude } else { altitude })
+    }
+    _ => None,
+  };
+  Some(GpsInfo {
+    latitude,
+    longitude,
+    altitude,
+  })
+}
+
+fn field_bytes(exif: &exif::Exif, number: u16) -> Option<Vec<u8>> {
+  let field = exif.get_field(Tag(Context::Tiff, number), In::PRIMARY)?;
+  match &field.value {
+    Value::Byte(bytes) | Value::Undefined(bytes, _) => Some(bytes.clone()),
+    Value::Long(values) => Some(values.iter().flat_map(|v| v.to_be_bytes()).collect()),
+    _ => None,
+  }
+}
+
+fn jpeg_xmp(jpeg: &Jpeg) -> Option<String> {
+  jpeg
+    .segments()
+    .iter()
+    .find(|segment| segment.marker() == markers::APP1 && segment.contents().starts_with(XMP_JPEG_PREFIX))
+    .map(|segment| String::from_utf8_lossy(&segment.contents()[XMP_JPEG_PREFIX.len()..]).into_owned())
+}
+
+fn jpeg_iptc(jpeg: &Jpeg) -> Vec<IptcEntry> {
+  jpeg
+    .segments()
+    .iter()
+    .filter(|segment| segment.marker() == markers::APP13 && segment.contents().starts_with(PHOTOSHOP_PREFIX))
+    .flat_map(|segment| parse_resources(&segment.contents()[PHOTOSHOP_PREFIX.len()..]))
+    .filter(|resource| resource.id == IPTC_RESOURCE_ID)
+    .flat_map(|resource| parse_iim(&resource.data))
+    .collect()
+}
+
+/// 解析 iTXt 块，返回 (关键字, 文本)
+fn parse_itxt(contents: &[u8]) -> Option<(&[u8], String)> {
+  let keyword_end = contents.iter().position(|b| *b == 0)?;
+  let keyword = &contents[..keyword_end];
+  let rest = contents.get(keyword_end + 1..)?;
+  let (compressed, rest) = (rest.first()? == &1, rest.get(2..)?);
+  let language_end = rest.iter().position(|b| *b == 0)?;
+  let rest = rest.get(language_end + 1..)?;
+  let translated_end = rest.iter().position(|b| *b == 0)?;
+  let text = rest.get(translated_end + 1..)?;
+  if compressed {
+    use std::io::Read;
+    let mut decoded = String::new();
+    flate2::read::ZlibDecoder::new(text)
+      .read_to_string(&mut decoded)
+      .ok()?;
+    Some((keyword, decoded))
+  } else {
+    Some((keyword, String::from_utf8_lossy(text).into_owned()))
+  }
+}
+
+fn png_xmp(png: &Png) -> Option<String> {
+  png
+    .chunks()
+    .iter()
+    .filter(|chunk| &chunk.kind() == b"iTXt")
+    .filter_map(|chunk| parse_itxt(chunk.contents()))
+    .find(|(keyword, _)| *keyword == XMP_PNG_KEYWORD)
+    .map(|(_, text)| text)
+}
+
+fn webp_xmp(webp: &WebP) -> Option<String> {
+  webp
+    .chunk_by_id(*b"XMP ")
+    .and_then(|chunk| chunk.content().data())
+    .map(|data| String::from_utf8_lossy(data).into_owned())
+}
+
+/// 从 IPTC 载荷中取出数据集；兼容裸 IIM 与 Photoshop 资源块两种封装
+fn iptc_from_payload(data: &[u8]) -> Vec<IptcEntry> {
+  let data = data.strip_prefix(PHOTOSHOP_PREFIX).unwrap_or(data);
+  if data.starts_with(b"8BIM") {
+    parse_resources(data)
+      .into_iter()
+      .filter(|resource| resource.id == IPTC_RESOURCE_ID)
+      .flat_map(|resource| parse_iim(&resource.data))
+      .collect()
+  } else {
+    parse_iim(data)
+  }
+}
+
+/// 解析 ImageMagick 风格的 "Raw profile type" 文本：换行、名称、长度，随后是十六进制数据
+fn parse_raw_profile(text: &[u8]) -> Option<Vec<u8>> {
+  let text = std::str::from_utf8(text).ok()?;
+  let mut tokens = text.split_ascii_whitespace();
+  let _name = tokens.next()?;
+  let len: usize = tokens.next()?.parse().ok()?;
+  let digits: String = tokens.collect();
+  let data = hex::decode(digits).ok()?;
+  (data.len() >= len).then(|| data[..len].to_vec())
+}
+
+fn build_raw_profile(name: &str, data: &[u8]) -> Vec<u8> {
+  let mut text = format!("\n{name}\n{:8}\n", data.len());
+  for line in data.chunks(36) {
+    text.push_str(&hex::encode(line));
+    text.push('\n');
+  }
+  text.into_bytes()
+}
+
+/// 读取 tEXt/zTXt 块，返回 (关键字, 文本)
+fn parse_text_chunk(chunk: &PngChunk) -> Option<(&[u8], Vec<u8>)> {
+  let contents = chunk.contents();
+  let keyword_end = contents.iter().position(|b| *b == 0)?;
+  let keyword = &contents[..keyword_end];
+  match &chunk.kind() {
+    b"tEXt" => Some((keyword, contents.get(keyword_end + 1..)?.to_vec())),
+    b"zTXt" => {
+      use std::io::Read;
+      let mut decoded = Vec::new();
+      flate2::read::ZlibDecoder::new(contents.get(keyword_end + 2..)?)
+        .read_to_end(&mut decoded)
+        .ok()?;
+      Some((keyword, decoded))
+    }
+    _ => None,
+  }
+}
+
+fn png_iptc(png: &Png) -> Vec<IptcEntry> {
+  png
+    .chunks()
+    .iter()
+    .filter_map(parse_text_chunk)
+    .find(|(keyword, _)| *keyword == PNG_IPTC_KEYWORD)
+    .and_then(|(_, text)| parse_raw_profile(&text))
+    .map(|data| iptc_from_payload(&data))
+    .unwrap_or_default()
+}
+
+/// 拆分 ISOBMFF 盒子序列，返回 (类型, 盒子起始偏移, 内容范围)
+fn box_spans(data: &[u8]) -> Vec<([u8; 4], usize, Range<usize>)> {
+  let mut boxes = Vec::new();
+  let mut pos = 0;
+  while pos + 8 <= data.len() {
+    let kind = [data[pos + 4], data[pos + 5], data[pos + 6], data[pos + 7]];
+    let (header, size) = match u32::from_be_bytes([data[pos], data[pos + 1], data[pos + 2], data[pos + 3]]) {
+      0 => (8, data.len() - pos),
+      1 => match data.get(pos + 8..pos + 16) {
+        Some(large) => (16, u64::from_be_bytes(large.try_into().unwrap()) as usize),
+        None => break,
+      },
+      size => (8, size as usize),
+    };
+    if size < header || size > data.len() - pos {
+      break;
+    }
+    boxes.push((kind, pos, pos + header..pos + size));
+    pos += size;
+  }
+  boxes
+}
+
+/// 拆分 ISOBMFF 盒子序列，返回 (类型, 内容)
+fn parse_boxes(data: &[u8]) -> Vec<([u8; 4], &[u8])> {
+  box_spans(data)
+    .into_iter()
+    .map(|(kind, _, range)| (kind, &data[range]))
+    .collect()
+}
+
+fn find_box<'a>(data: &'a [u8], kind: &[u8; 4]) -> Option<&'a [u8]> {
+  parse_boxes(data)
+    .into_iter()
+    .find(|(found, _)| found == kind)
+    .map(|(_, contents)| contents)
+}
+
+/// 按字节数读取大端无符号整数；HEIC 的 iloc 字段宽度可变
+fn read_uint(data: &[u8], pos: &mut usize, size: usize) -> Option<u64> {
+  let bytes = data.get(*pos..*pos + size)?;
+  *pos += size;
+  Some(bytes.iter().fold(0, |value, byte| (value << 8) | u64::from(*byte)))
+}
+
+/// iinf 中的条目信息（infe 版本 2/3）
+struct ItemInfo {
+  id: u32,
+  item_type: [u8; 4],
+  content_type: Vec<u8>,
+}
+
+fn parse_infe(infe: &[u8]) -> Option<ItemInfo> {
+  let mut pos = 4;
+  let id = match infe.first()? {
+    2 => read_uint(infe, &mut pos, 2)?,
+    3 => read_uint(infe, &mut pos, 4)?,
+    _ => return None,
+  };
+  pos += 2;
+  let item_type: [u8; 4] = infe.get(pos..pos + 4)?.try_into().ok()?;
+  pos += 4;
+  let rest = infe.get(pos..)?;
+  let name_end = rest.iter().position(|b| *b == 0)?;
+  let rest = &rest[name_end + 1..];
+  let content_type = rest[..rest.iter().position(|b| *b == 0).unwrap_or(rest.len())].to_vec();
+  Some(ItemInfo {
+    id: id as u32,
+    item_type,
+    content_type,
+  })
+}
+
+/// iinf 内容中 infe 子盒子的起始位置：版本 0 的条目数为 16 位，其余为 32 位
+fn iinf_entries_start(iinf: &[u8]) -> Option<usize> {
+  Some(if iinf.first()? == &0 { 6 } else { 8 })
+}
+
+fn heic_items(meta: &[u8]) -> Vec<ItemInfo> {
+  let Some(entries) = find_box(meta, b"iinf").and_then(|iinf| iinf.get(iinf_entries_start(iinf)?..)) else {
+    return Vec::new();
+  };
+  parse_boxes(entries)
+    .into_iter()
+    .filter(|(kind, _)| kind == b"infe")
+    .filter_map(|(_, infe)| parse_infe(infe))
+    .collect()
+}
+
+/// 在 iinf 中查找 MIME 类型匹配的条目，返回其 item_ID
+fn heic_mime_item(meta: &[u8], mime: &[u8]) -> Option<u32> {
+  heic_items(meta)
+    .into_iter()
+    .find(|item| &item.item_type == b"mime" && item.content_type == mime)
+    .map(|item| item.id)
+}
+
+/// iloc 中的一个条目；extents 为 (索引, 偏移, 长度)
+#[derive(Clone, Debug)]
+struct IlocItem {
+  id: u32,
+  method: u8,
+  data_ref: u16,
+  base: u64,
+  extents: Vec<(u64, u64, u64)>,
+}
+
+/// iloc 盒子：记录每个条目的数据位置，字段宽度由头部声明
+#[derive(Clone, Debug)]
+struct Iloc {
+  version: u8,
+  offset_size: usize,
+  length_size: usize,
+  base_size: usize,
+  index_size: usize,
+  items: Vec<IlocItem>,
+}
+
+fn parse_iloc(iloc: &[u8]) -> Option<Iloc> {
+  let version = *iloc.first()?;
+  let sizes = iloc.get(4..6)?;
+  let (offset_size, length_size) = ((sizes[0] >> 4) as usize, (sizes[0] & 0x0F) as usize);
+  let (base_size, index_size) = ((sizes[1] >> 4) as usize, (sizes[1] & 0x0F) as usize);
+  let index_size = if version == 0 { 0 } else { index_size };
+  let id_size = if version < 2 { 2 } else { 4 };
+  let mut pos = 6;
+  let count = read_uint(iloc, &mut pos, id_size)?;
+  let mut items = Vec::new();
+  for _ in 0..count {
+    let id = read_uint(iloc, &mut pos, id_size)? as u32;
+    let method = if version == 0 { 0 } else { (read_uint(iloc, &mut pos, 2)? & 0x0F) as u8 };
+    let data_ref = read_uint(iloc, &mut pos, 2)? as u16;
+    let base = read_uint(iloc, &mut pos, base_size)?;
+    let extent_count = read_uint(iloc, &mut pos, 2)?;
+    let mut extents = Vec::new();
+    for _ in 0..extent_count {
+      let index = read_uint(iloc, &mut pos, index_size)?;
+      let offset = read_uint(iloc, &mut pos, offset_size)?;
+      let length = read_uint(iloc, &mut pos, length_size)?;
+      extents.push((index, offset, length));
+    }
+    items.push(IlocItem {
+      id,
+      method,
+      data_ref,
+      base,
+      extents,
+    });
+  }
+  Some(Iloc {
+    version,
+    offset_size,
+    length_size,
+    base_size,
+    index_size,
+    items,
+  })
+}
+
+/// 按指定宽度写入大端无符号整数
+fn write_uint(output: &mut Vec<u8>, value: u64, size: usize) {
+  output.extend_from_slice(&value.to_be_bytes()[8 - size..]);
+}
+
+fn make_box(kind: &[u8; 4], contents: &[u8]) -> Vec<u8> {
+  let mut output = ((contents.len() + 8) as u32).to_be_bytes().to_vec();
+  output.extend_from_slice(kind);
+  output.extend_from_slice(contents);
+  output
+}
+
+fn build_iloc(iloc: &Iloc) -> Vec<u8> {
+  let id_size = if iloc.version < 2 { 2 } else { 4 };
+  let mut contents = vec![iloc.version, 0, 0, 0];
+  contents.push(((iloc.offset_size << 4) | iloc.length_size) as u8);
+  contents.push(((iloc.base_size << 4) | iloc.index_size) as u8);
+  write_uint(&mut contents, iloc.items.len() as u64, id_size);
+  for item in &iloc.items {
+    write_uint(&mut contents, u64::from(item.id), id_size);
+    if iloc.version > 0 {
+      write_uint(&mut contents, u64::from(item.method), 2);
+    }
+    write_uint(&mut contents, u64::from(item.data_ref), 2);
+    write_uint(&mut contents, item.base, iloc.base_size);
+    write_uint(&mut contents, item.extents.len() as u64, 2);
+    for &(index, offset, length) in &item.extents {
+      write_uint(&mut contents, index, iloc.index_size);
+      write_uint(&mut contents, offset, iloc.offset_size);
+      write_uint(&mut contents, length, iloc.length_size);
+    }
+  }
+  make_box(b"iloc", &contents)
+}
+
+/// 按 iloc 拼接条目数据；仅支持文件偏移与 idat 两种构造方式
+fn heic_item_data(data: &[u8], meta: &[u8], item: u32) -> Option<Vec<u8>> {
+  let iloc = parse_iloc(find_box(meta, b"iloc")?)?;
+  let entry = iloc.items.iter().find(|entry| entry.id == item)?;
+  let source = match (entry.method, entry.data_ref) {
+    (0, 0) => data,
+    (1, _) => find_box(meta, b"idat")?,
+    _ => return None,
+  };
+  let mut bytes = Vec::new();
+  for &(_, offset, length) in &entry.extents {
+    let start = usize::try_from(entry.base.checked_add(offset)?).ok()?;
+    // 长度为 0 表示延伸到数据末尾
+    let end = if length == 0 { source.len() } else { start.checked_add(usize::try_from(length).ok()?)? };
+    bytes.extend_from_slice(source.get(start..end)?);
+  }
+  Some(bytes)
+}
+
+/// HEIC 的 Exif 条目以 4 字节的 TIFF 头偏移开头，之后通常是 "Exif\0\0" 与 TIFF 结构
+fn heic_exif_tiff(item: &[u8]) -> Option<&[u8]> {
+  let offset = u32::from_be_bytes(item.get(..4)?.try_into().ok()?) as usize;
+  item.get(4usize.checked_add(offset)?..)
+}
+
+fn heic_xmp(data: &[u8]) -> Option<String> {
+  let meta = find_box(data, b"meta")?.get(4..)?;
+  let item = heic_mime_item(meta, HEIC_XMP_MIME)?;
+  heic_item_data(data, meta, item).map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// IPTC Core 属性在 XMP 中的组织方式
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum XmpContainer {
+  Simple,
+  Alt,
+  Bag,
+  Seq,
+}
+
+/// WebP 与 HEIC 没有 IPTC 容器，IPTC 数据集按 IPTC Core 规范映射到 XMP 属性
+const IPTC_XMP_PROPERTIES: [(u8, &str, XmpContainer); 16] = [
+  (5, "dc:title", XmpContainer::Alt),
+  (15, "photoshop:Category", XmpContainer::Simple),
+  (25, "dc:subject", XmpContainer::Bag),
+  (40, "photoshop:Instructions", XmpContainer::Simple),
+  (55, "photoshop:DateCreated", XmpContainer::Simple),
+  (80, "dc:creator", XmpContainer::Seq),
+  (85, "photoshop:AuthorsPosition", XmpContainer::Simple),
+  (90, "photoshop:City", XmpContainer::Simple),
+  (95, "photoshop:State", XmpContainer::Simple),
+  (101, "photoshop:Country", XmpContainer::Simple),
+  (105, "photoshop:Headline", XmpContainer::Simple),
+  (110, "photoshop:Credit", XmpContainer::Simple),
+  (115, "photoshop:Source", XmpContainer::Simple),
+  (116, "dc:rights", XmpContainer::Alt),
+  (120, "dc:description", XmpContainer::Alt),
+  (122, "photoshop:CaptionWriter", XmpContainer::Simple),
+];
+
+fn xml_escape(text: &str) -> String {
+  text
+    .replace('&', "&amp;")
+    .replace('<', "&lt;")
+    .replace('>', "&gt;")
+    .replace('"', "&quot;")
+}
+
+fn xml_unescape(text: &str) -> String {
+  text
+    .replace("&lt;", "<")
+    .replace("&gt;", ">")
+    .replace("&quot;", "\"")
+    .replace("&apos;", "'")
+    .replace("&amp;", "&")
+}
+
+/// 查找元素 `<name ...>` 的起始位置，跳过名称仅前缀相同的元素
+fn find_element(xmp: &str, name: &str, from: usize) -> Option<usize> {
+  let open = format!("<{name}");
+  let mut pos = from;
+  while let Some(found) = xmp.get(pos..)?.find(&open) {
+    let start = pos + found;
+    let next = xmp[start + open.len()..].chars().next();
+    if matches!(next, Some('>' | '/') | Some(' ' | '\t' | '\r' | '\n')) {
+      return Some(start);
+    }
+    pos = start + open.len();
+  }
+  None
+}
+
+/// 元素的完整范围与内容范围；自闭合元素没有内容
+fn element_span(xmp: &str, name: &str, start: usize) -> Option<(Range<usize>, Range<usize>)> {
+  let tag_end = start + xmp[start..].find('>')? + 1;
+  if xmp[..tag_end].ends_with("/>") {
+    return Some((start..tag_end, tag_end..tag_end));
+  }
+  let close = format!("</{name}>");
+  let content_end = tag_end + xmp[tag_end..].find(&close)?;
+  Some((start..content_end + close.len(), tag_end..content_end))
+}
+
+/// 查找属性形式 ` name="value"` 的范围（含前导空白）与取值
+fn find_attribute(xmp: &str, name: &str) -> Option<(Range<usize>, String)> {
+  for quote in ['"', '\''] {
+    let pattern = format!("{name}={quote}");
+    let mut pos = 0;
+    while let Some(found) = xmp[pos..].find(&pattern) {
+      let start = pos + found;
+      let value_start = start + pattern.len();
+      pos = value_start;
+      if !xmp[..start].ends_with(char::is_whitespace) {
+        continue;
+      }
+      let value_end = value_start + xmp[value_start..].find(quote)?;
+      let lead = xmp[..start].len() - xmp[..start].trim_end().len();
+      return Some((start - lead..value_end + 1, xml_unescape(&xmp[value_start..value_end])));
+    }
+  }
+  None
+}
+
+/// 移除 XMP 中的某个属性，包括元素与属性两种写法
+fn remove_xmp_property(xmp: &str, name: &str) -> String {
+  let mut xmp = xmp.to_string();
+  while let Some((range, _)) = find_element(&xmp, name, 0).and_then(|start| element_span(&xmp, name, start)) {
+    xmp.replace_range(range, "");
+  }
+  while let Some((range, _)) = find_attribute(&xmp, name) {
+    xmp.replace_range(range, "");
+  }
+  xmp
+}
+
+/// 读取 XMP 中 IPTC Core 属性，转换为 IPTC 数据集
+fn iptc_from_xmp(xmp: &str) -> Vec<IptcEntry> {
+  let mut entries = Vec::new();
+  for (dataset, name, container) in IPTC_XMP_PROPERTIES {
+    let mut values = Vec::new();
+    if let Some((_, content)) = find_element(xmp, name, 0).and_then(|start| element_span(xmp, name, start)) {
+      let content = &xmp[content];
+      if container == XmpContainer::Simple {
+        values.push(xml_unescape(content.trim()));
+      } else {
+        let mut pos = 0;
+        while let Some((_, item)) = find_element(content, "rdf:li", pos).and_then(|start| element_span(content, "rdf:li", start)) {
+          values.push(xml_unescape(content[item.clone()].trim()));
+          pos = item.end;
+        }
+      }
+    } else if let Some((_, value)) = find_attribute(xmp, name) {
+      values.push(value);
+    }
+    if container == XmpContainer::Alt {
+      values.truncate(1);
+    }
+    for mut value in values {
+      // XMP 日期为 CCYY-MM-DD，IIM 为 CCYYMMDD
+      if dataset == 55 {
+        value = value.chars().take(10).filter(|c| *c != '-').collect();
+      }
+      entries.push(IptcEntry {
+        record: 2,
+        dataset,
+        name: iptc_dataset_name(dataset).to_string(),
+        value,
+      });
+    }
+  }
+  entries
+}
+
+/// 用 IPTC 数据集替换 XMP 中的 IPTC Core 属性；`xmp` 为空时生成新的 XMP 包
+fn merge_iptc_xmp(xmp: &str, entries: &[IptcEntry]) -> Result<String, String> {
+  if let Some(entry) = entries
+    .iter()
+    .find(|entry| !IPTC_XMP_PROPERTIES.iter().any(|(dataset, ..)| *dataset == entry.dataset))
+  {
+    return Err(format!("IPTC dataset 2:{} has no XMP equivalent", entry.dataset));
+  }
+  let mut xmp = xmp.to_string();
+  for (_, name, _) in IPTC_XMP_PROPERTIES {
+    xmp = remove_xmp_property(&xmp, name);
+  }
+  if entries.is_empty() {
+    return Ok(xmp);
+  }
+
+  let mut properties = String::new();
+  for (dataset, name, container) in IPTC_XMP_PROPERTIES {
+    let values: Vec<String> = entries
+      .iter()
+      .filter(|entry| entry.dataset == dataset)
+      .map(|entry| {
+        let value = &entry.value;
+        if dataset == 55 && value.len() == 8 && value.chars().all(|c| c.is_ascii_digit()) {
+          format!("{}-{}-{}", &value[..4], &value[4..6], &value[6..])
+        } else {
+          value.clone()
+        }
+      })
+      .map(|value| xml_escape(&value))
+      .collect();
+    let Some(first) = values.first() else {
+      continue;
+    };
+    let body = match container {
+      XmpContainer::Simple => first.clone(),
+      XmpContainer::Alt => format!("<rdf:Alt><rdf:li xml:lang=\"x-default\">{first}</rdf:li></rdf:Alt>"),
+      XmpContainer::Bag | XmpContainer::Seq => {
+        let kind = if container == XmpContainer::Bag { "Bag" } else { "Seq" };
+        let items: String = values.iter().map(|value| format!("<rdf:li>{value}</rdf:li>")).collect();
+        format!("<rdf:{kind}>{items}</rdf:{kind}>")
+      }
+    };
+    properties.push_str(&format!("<{name}>{body}</{name}>"));
+  }
+  let description = format!(
+    "<rdf:Description rdf:about=\"\" xmlns:dc=\"http://purl.org/dc/elements/1.1/\" xmlns:photoshop=\"http://ns.adobe.com/photoshop/1.0/\">{properties}</rdf:Description>"
+  );
+
+  if xmp.trim().is_empty() {
+    return Ok(format!(
+      "<?xpacket begin=\"\u{feff}\" id=\"W5M0MpCehiHzreSzNTczkc9d\"?><x:xmpmeta xmlns:x=\"adobe:ns:meta/\"><rdf:RDF xmlns:rdf=\"http://www.w3.org/1999/02/22-rdf-syntax-ns#\">{description}</rdf:RDF></x:xmpmeta><?xpacket end=\"w\"?>"
+    ));
+  }
+  let position = xmp.rfind("</rdf:RDF>").ok_or("XMP packet has no rdf:RDF element")?;
+  xmp.insert_str(position, &description);
+  Ok(xmp)
+}
+
+/// 计算改写后的 XMP（IPTC 并入其中）。外层 None 表示不改动，Some(None) 表示移除
+fn updated_xmp(existing: Option<String>, request: &MetadataWrite) -> Result<Option<Option<String>>, String> {
+  let strip_all = request.strip == StripMode::All;
+  if request.xmp.is_none() && request.iptc.is_none() {
+    return Ok(strip_all.then_some(None));
+  }
+  let base = match &request.xmp {
+    Some(xmp) => xmp.clone(),
+    None if strip_all => String::new(),
+    None => existing.unwrap_or_default(),
+  };
+  let xmp = match request.iptc.as_ref().filter(|_| !strip_all) {
+    Some(entries) => merge_iptc_xmp(&base, entries)?,
+    None => base,
+  };
+  Ok(Some((!xmp.is_empty()).then_some(xmp)))
+}
+
+/// 读取图片的 EXIF、GPS、IPTC 与 XMP 信息。WebP 与 HEIC 没有 IPTC 容器，`iptc` 取自 XMP 中的 IPTC Core 属性
+pub fn read(path: &str) -> Result<MetadataReport, String> {
+  let data = std::fs::read(path).map_err(|e| format!("Failed to read {path}: {e}"))?;
+  let format = detect_format(&data)?;
+
+  let exif = exif::Reader::new()
+    .read_from_container(&mut BufReader::new(Cursor::new(&data)))
+    .ok();
+  let mut entries = Vec::new();
+  if let Some(exif) = &exif {
+    for field in exif.fields() {
+      // XMP/IPTC 在 TIFF 中以标签形式存在，单独解析
+      if field.tag == Tag(Context::Tiff, TIFF_TAG_XMP) || field.tag == Tag(Context::Tiff, TIFF_TAG_IPTC) {
+        continue;
+      }
+      entries.push(ExifEntry {
+        ifd: format!("{:?}", field.tag.context()),
+        tag: field.tag.to_string(),
+        value: field.display_value().with_unit(exif).to_string(),
+      });
+    }
+  }
+  let gps = exif.as_ref().and_then(gps_from_exif);
+
+  let (iptc, xmp) = match format {
+    MetadataFormat::Jpeg => {
+      let jpeg = Jpeg::from_bytes(Bytes::from(data)).map_err(|e| e.to_string())?;
+      (jpeg_iptc(&jpeg), jpeg_xmp(&jpeg))
+    }
+    MetadataFormat::Png => {
+      let png = Png::from_bytes(Bytes::from(data)).map_err(|e| e.to_string())?;
+      (png_iptc(&png), png_xmp(&png))
+    }
+    MetadataFormat::Webp => {
+      let webp = WebP::from_bytes(Bytes::from(data)).map_err(|e| e.to_string())?;
+      let xmp = webp_xmp(&webp);
+      (xmp.as_deref().map(iptc_from_xmp).unwrap_or_default(), xmp)
+    }
+    MetadataFormat::Tiff => {
+      let exif = exif.as_ref();
+      (
+        exif
+          .and_then(|exif| field_bytes(exif, TIFF_TAG_IPTC))
+          .map(|bytes| parse_iim(&bytes))
+          .unwrap_or_default(),
+        exif
+          .and_then(|exif| field_bytes(exif, TIFF_TAG_XMP))
+          .map(|bytes| String::from_utf8_lossy(&bytes).into_owned()),
+      )
+    }
+    MetadataFormat::Heic => {
+      let xmp = heic_xmp(&data);
+      (xmp.as_deref().map(iptc_from_xmp).unwrap_or_default(), xmp)
+    }
+  };
+
+  Ok(MetadataReport {
+    format,
+    exif: entries,
+    gps,
+    iptc,
+    xmp,
+  })
+}
+
+fn ascii_tag(name: &str) -> Option<Tag> {
+  Some(match name {
+    "ImageDescription" => Tag::ImageDescription,
+    "Make" => Tag::Make,
+    "Model" => Tag::Model,
+    "Software" => Tag::Software,
+    "Artist" => Tag::Artist,
+    "Copyright" => Tag::Copyright,
+    "DateTime" => Tag::DateTime,
+    "DateTimeOriginal" => Tag::DateTimeOriginal,
+    "DateTimeDigitized" => Tag::DateTimeDigitized,
+    "LensMake" => Tag::LensMake,
+    "LensModel" => Tag::LensModel,
+    "BodySerialNumber" => Tag::BodySerialNumber,
+    "CameraOwnerName" => Tag::CameraOwnerName,
+    _ => return None,
+  })
+}
+
+fn to_dms(value: f64) -> Vec<Rational> {
+  // 先按万分之一秒取整再拆分，避免秒数四舍五入到 60
+  let total = (value.abs() * 36_000_000.0).round() as u64;
+  vec![
+    Rational::from(((total / 36_000_000) as u32, 1)),
+    Rational::from(((total / 600_000 % 60) as u32, 1)),
+    Rational::from(((total % 600_000) as u32, 10000)),
+  ]
+}
+
+fn gps_fields(gps: &GpsInfo) -> Vec<Field> {
+  let field = |tag, value| Field {
+    tag,
+    ifd_num: In::PRIMARY,
+    value,
+  };
+  let mut fields = vec![
+    field(Tag::GPSVersionID, Value::Byte(vec![2, 3, 0, 0])),
+    field(
+      Tag::GPSLatitudeRef,
+      Value::Ascii(vec![if gps.latitude < 0.0 { b"S".to_vec() } else { b"N".to_vec() }]),
+    ),
+    field(Tag::GPSLatitude, Value::Rational(to_dms(gps.latitude))),
+    field(
+      Tag::GPSLongitudeRef,
+      Value::Ascii(vec![if gps.longitude < 0.0 { b"W".to_vec() } else { b"E".to_vec() }]),
+    ),
+    field(Tag::GPSLongitude, Value::Rational(to_dms(gps.longitude))),
+  ];
+  if let Some(altitude) = gps.altitude {
+    fields.push(field(
+      Tag::GPSAltitudeRef,
+      Value::Byte(vec![u8::from(altitude < 0.0)]),
+    ));
+    fields.push(field(
+      Tag::GPSAltitude,
+      Value::Rational(vec![Rational::from(((altitude.abs() * 100.0).round() as u32, 100))]),
+    ));
+  }
+  fields
+}
+
+/// 清除全部元数据时从 TIFF 各 IFD 移除的描述性标签；图像结构与 ICC 标签保留
+const TIFF_DESCRIPTIVE_TAGS: [u16; 12] = [
+  270,
+  271,
+  272,
+  305,
+  306,
+  315,
+  33432,
+  TIFF_TAG_XMP,
+  TIFF_TAG_IPTC,
+  TIFF_TAG_PHOTOSHOP,
+  TIFF_TAG_EXIF_IFD,
+  TIFF_TAG_GPS_IFD,
+];
+
+/// TIFF 字段类型的单个值字节数；未知类型返回 None
+fn tiff_type_size(typ: u16) -> Option<usize> {
+  Some(match typ {
+    1 | 2 | 6 | 7 => 1,
+    3 | 8 => 2,
+    4 | 9 | 11 | 13 => 4,
+    5 | 10 | 12 | 16..=18 => 8,
+    _ => return None,
+  })
+}
+
+/// 原始 IFD 条目：值（或值的偏移）保留原字节，未改动的条目原样写回
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct IfdEntry {
+  tag: u16,
+  typ: u16,
+  count: u32,
+  value: [u8; 4],
+}
+
+/// 就地编辑 TIFF 结构：改动过的 IFD 追加到末尾并更新指向它的偏移，其余字节保持原位，
+/// 因此图像数据、厂商注释以及无法解析类型的字段都原样保留，原有偏移依然有效
+struct TiffEditor {
+  data: Vec<u8>,
+  little_endian: bool,
+}
+
+impl TiffEditor {
+  fn parse(data: Vec<u8>) -> Result<Self, String> {
+    let little_endian = match data.get(0..4) {
+      Some(b"II*\0") => true,
+      Some(b"MM\0*") => false,
+      _ => return Err("Invalid TIFF header".to_string()),
+    };
+    let editor = Self { data, little_endian };
+    editor.read_u32(4).ok_or("Invalid TIFF header")?;
+    Ok(editor)
+  }
+
+  /// 只含一个空主 IFD 的最小 TIFF 结构，原图没有 EXIF 时以此为基础
+  fn empty() -> Self {
+    Self {
+      data: b"II*\0\x08\0\0\0\0\0\0\0\0\0".to_vec(),
+      little_endian: true,
+    }
+  }
+
+  fn read_u16(&self, pos: usize) -> Option<u16> {
+    let bytes = self.data.get(pos..pos.checked_add(2)?)?.try_into().ok()?;
+    Some(if self.little_endian {
+      u16::from_le_bytes(bytes)
+    } else {
+      u16::from_be_bytes(bytes)
+    })
+  }
+
+  fn read_u32(&self, pos: usize) -> Option<u32> {
+    let bytes = self.data.get(pos..pos.checked_add(4)?)?.try_into().ok()?;
+    Some(if self.little_endian {
+      u32::from_le_bytes(bytes)
+    } else {
+      u32::from_be_bytes(bytes)
+    })
+  }
+
+  fn u16_bytes(&self, value: u16) -> [u8; 2] {
+    if self.little_endian {
+      value.to_le_bytes()
+    } else {
+      value.to_be_bytes()
+    }
+  }
+
+  fn u32_bytes(&self, value: u32) -> [u8; 4] {
+    if self.little_endian {
+      value.to_le_bytes()
+    } else {
+      value.to_be_bytes()
+    }
+  }
+
+  /// 条目值按 LONG 解读，用于子 IFD 指针与值偏移
+  fn pointer(&self, entry: &IfdEntry) -> u32 {
+    if self.little_endian {
+      u32::from_le_bytes(entry.value)
+    } else {
+      u32::from_be_bytes(entry.value)
+    }
+  }
+
+  fn first_ifd(&self) -> u32 {
+    self.read_u32(4).unwrap_or(0)
+  }
+
+  fn read_ifd(&self, offset: u32) -> Result<(Vec<IfdEntry>, u32), String> {
+    let offset = offset as usize;
+    let count = self.read_u16(offset).ok_or("Truncated IFD")? as usize;
+    let mut entries = Vec::with_capacity(count);
+    for index in 0..count {
+      let pos = offset + 2 + index * 12;
+      let (Some(tag), Some(typ), Some(count), Some(value)) = (
+        self.read_u16(pos),
+        self.read_u16(pos + 2),
+        self.read_u32(pos + 4),
+        self.data.get(pos + 8..pos + 12),
+      ) else {
+        return Err("Truncated IFD".to_string());
+      };
+      entries.push(IfdEntry {
+        tag,
+        typ,
+        count,
+        value: value.try_into().map_err(|_| "Truncated IFD")?,
+      });
+    }
+    let next = self.read_u32(offset + 2 + count * 12).ok_or("Truncated IFD")?;
+    Ok((entries, next))
+  }
+
+  /// 主 IFD 及其后续 IFD 的偏移
+  fn ifd_chain(&self) -> Result<Vec<u32>, String> {
+    let mut chain = Vec::new();
+    let mut offset = self.first_ifd();
+    while offset != 0 && chain.len() < MAX_IFD_COUNT && !chain.contains(&offset) {
+      chain.push(offset);
+      offset = self.read_ifd(offset)?.1;
+    }
+    Ok(chain)
+  }
+
+  /// 值超过 4 字节时存放在条目之外，返回其字节范围
+  fn value_range(&self, entry: &IfdEntry) -> Option<Range<usize>> {
+    let len = tiff_type_size(entry.typ)?.checked_mul(entry.count as usize)?;
+    if len <= 4 {
+      return None;
+    }
+    let start = self.pointer(entry) as usize;
+    let end = start.checked_add(len)?.min(self.data.len());
+    (start < end).then_some(start..end)
+  }
+
+  /// 把不再被引用的字节清零，避免被移除的元数据残留在文件中
+  fn scrub(&mut self, range: Range<usize>) {
+    let end = range.end.min(self.data.len());
+    if let Some(bytes) = self.data.get_mut(range.start.min(end)..end) {
+      bytes.fill(0);
+    }
+  }
+
+  fn scrub_table(&mut self, offset: u32, count: usize) {
+    let start = offset as usize;
+    self.scrub(start..start + 6 + count * 12);
+  }
+
+  /// 清除条目的值；子 IFD 指针连同整个子 IFD 一并清除
+  fn scrub_entry(&mut self, entry: &IfdEntry, depth: usize) {
+    if matches!(entry.tag, TIFF_TAG_EXIF_IFD | TIFF_TAG_GPS_IFD | TIFF_TAG_INTEROP_IFD) && depth < 2 {
+      let offset = self.pointer(entry);
+      if let Ok((entries, _)) = self.read_ifd(offset) {
+        for child in &entries {
+          self.scrub_entry(child, depth + 1);
+        }
+        self.scrub_table(offset, entries.len());
+      }
+    }
+    if let Some(range) = self.value_range(entry) {
+      self.scrub(range);
+    }
+  }
+
+  /// 移除满足条件的条目并清除其数据，返回是否有条目被移除
+  fn remove_entries(&mut self, entries: &mut Vec<IfdEntry>, remove: impl Fn(&IfdEntry) -> bool) -> bool {
+    let (removed, kept): (Vec<IfdEntry>, Vec<IfdEntry>) = entries.iter().partition(|entry| remove(entry));
+    for entry in &removed {
+      self.scrub_entry(entry, 0);
+    }
+    *entries = kept;
+    !removed.is_empty()
+  }
+
+  /// 在末尾按字对齐追加数据，返回其偏移
+  fn append(&mut self, bytes: &[u8]) -> Result<u32, String> {
+    if self.data.len() % 2 == 1 {
+      self.data.push(0);
+    }
+    let offset = u32::try_from(self.data.len()).map_err(|_| "TIFF data is too large".to_string())?;
+    self.data.extend_from_slice(bytes);
+    Ok(offset)
+  }
+
+  fn encode_value(&self, value: &Value) -> Result<(u16, u32, Vec<u8>), String> {
+    let mut bytes = Vec::new();
+    let (typ, count) = match value {
+      Value::Byte(values) => {
+        bytes.extend_from_slice(values);
+        (1, values.len())
+      }
+      Value::Ascii(values) => {
+        for value in values {
+          bytes.extend_from_slice(value);
+          bytes.push(0);
+        }
+        (2, bytes.len())
+      }
+      Value::Short(values) => {
+        for value in values {
+          bytes.extend(self.u16_bytes(*value));
+        }
+        (3, values.len())
+      }
+      Value::Long(values) => {
+        for value in values {
+          bytes.extend(self.u32_bytes(*value));
+        }
+        (4, values.len())
+      }
+      Value::Rational(values) => {
+        for value in values {
+          bytes.extend(self.u32_bytes(value.num));
+          bytes.extend(self.u32_bytes(value.denom));
+        }
+        (5, values.len())
+      }
+      Value::Undefined(values, _) => {
+        bytes.extend_from_slice(values);
+        (7, values.len())
+      }
+      _ => return Err("Unsupported EXIF value type".to_string()),
+    };
+    let count = u32::try_from(count).map_err(|_| "EXIF value is too large".to_string())?;
+    Ok((typ, count, bytes))
+  }
+
+  /// 写入字段：同标签的原有条目被替换，值超过 4 字节时追加到末尾
+  fn put_entry(&mut self, entries: &mut Vec<IfdEntry>, tag: u16, value: &Value) -> Result<(), String> {
+    self.remove_entries(entries, |entry| entry.tag == tag);
+    let (typ, count, bytes) = self.encode_value(value)?;
+    let mut inline = [0; 4];
+    if bytes.len() <= 4 {
+      inline[..bytes.len()].copy_from_slice(&bytes);
+    } else {
+      let offset = self.append(&bytes)?;
+      inline = self.u32_bytes(offset);
+    }
+    entries.push(IfdEntry {
+      tag,
+      typ,
+      count,
+      value: inline,
+    });
+    Ok(())
+  }
+
+  /// 追加新的 IFD（条目按标签升序），返回其偏移
+  fn append_ifd(&mut self, entries: &mut [IfdEntry], next: u32) -> Result<u32, String> {
+    entries.sort_by_key(|entry| entry.tag);
+    let count = u16::try_from(entries.len()).map_err(|_| "Too many IFD entries".to_string())?;
+    let mut table = Vec::with_capacity(6 + entries.len() * 12);
+    table.extend(self.u16_bytes(count));
+    for entry in entries.iter() {
+      table.extend(self.u16_bytes(entry.tag));
+      table.extend(self.u16_bytes(entry.typ));
+      table.extend(self.u32_bytes(entry.count));
+      table.extend(entry.value);
+    }
+    table.extend(self.u32_bytes(next));
+    self.append(&table)
+  }
+
+  /// 改写 `pointer_tag` 指向的子 IFD：写入 `fields`，子 IFD 不存在时新建
+  fn put_sub_ifd(&mut self, entries: &mut Vec<IfdEntry>, pointer_tag: u16, fields: &[&Field]) -> Result<(), String> {
+    let existing = entries.iter().find(|entry| entry.tag == pointer_tag).copied();
+    let (mut children, old) = match existing {
+      Some(entry) => {
+        let offset = self.pointer(&entry);
+        let (children, _) = self.read_ifd(offset)?;
+        let count = children.len();
+        (children, Some((offset, count)))
+      }
+      None => (Vec::new(), None),
+    };
+    for field in fields {
+      self.put_entry(&mut children, field.tag.number(), &field.value)?;
+    }
+    let offset = self.append_ifd(&mut children, 0)?;
+    if let Some((old_offset, count)) = old {
+      self.scrub_table(old_offset, count);
+    }
+    entries.retain(|entry| entry.tag != pointer_tag);
+    entries.push(IfdEntry {
+      tag: pointer_tag,
+      typ: 4,
+      count: 1,
+      value: self.u32_bytes(offset),
+    });
+    Ok(())
+  }
+}
+
+/// 把请求中的 EXIF 文本修改转换为字段
+fn exif_edits(request: &MetadataWrite) -> Result<Vec<Field>, String> {
+  request
+    .exif
+    .iter()
+    .map(|edit| {
+      let tag = ascii_tag(&edit.tag).ok_or_else(|| format!("EXIF tag {} is not writable", edit.tag))?;
+      Ok(Field {
+        tag,
+        ifd_num: In::PRIMARY,
+        value: Value::Ascii(vec![edit.value.as_bytes().to_vec()]),
+      })
+    })
+    .collect()
+}
+
+/// 按请求改写 TIFF 结构中的元数据。`tiff_file` 为真时还处理主 IFD 中的 XMP/IPTC 标签
+fn edit_tiff(tiff: &mut TiffEditor, request: &MetadataWrite, tiff_file: bool) -> Result<(), String> {
+  let edits = exif_edits(request)?;
+  let strip_all = request.strip == StripMode::All;
+  let chain = tiff.ifd_chain()?;
+  // 自后向前处理：某个 IFD 被重写后，前一个 IFD 的 next 指针也要随之更新
+  let mut rewritten_next: Option<u32> = None;
+  for (index, &offset) in chain.iter().enumerate().rev() {
+    let (mut entries, next) = tiff.read_ifd(offset)?;
+    let original_count = entries.len();
+    let mut changed = rewritten_next.is_some();
+    if strip_all {
+      changed |= tiff.remove_entries(&mut entries, |entry| TIFF_DESCRIPTIVE_TAGS.contains(&entry.tag));
+    }
+
+    if index == 0 {
+      if request.strip == StripMode::Gps || request.gps.is_some() {
+        changed |= tiff.remove_entries(&mut entries, |entry| entry.tag == TIFF_TAG_GPS_IFD);
+      }
+      if let Some(gps) = &request.gps {
+        let fields = gps_fields(gps);
+        tiff.put_sub_ifd(&mut entries, TIFF_TAG_GPS_IFD, &fields.iter().collect::<Vec<_>>())?;
+        changed = true;
+      }
+      let exif_fields: Vec<&Field> = edits.iter().filter(|field| field.tag.context() == Context::Exif).collect();
+      if !exif_fields.is_empty() {
+        tiff.put_sub_ifd(&mut entries, TIFF_TAG_EXIF_IFD, &exif_fields)?;
+        changed = true;
+      }
+      for field in edits.iter().filter(|field| field.tag.context() == Context::Tiff) {
+        tiff.put_entry(&mut entries, field.tag.number(), &field.value)?;
+        changed = true;
+      }
+      if tiff_file {
+        if request.xmp.is_some() {
+          changed |= tiff.remove_entries(&mut entries, |entry| entry.tag == TIFF_TAG_XMP);
+        }
+        if let Some(xmp) = request.xmp.as_deref().filter(|xmp| !xmp.is_empty()) {
+          tiff.put_entry(&mut entries, TIFF_TAG_XMP, &Value::Byte(xmp.as_bytes().to_vec()))?;
+          changed = true;
+        }
+        if request.iptc.is_some() {
+          changed |= tiff.remove_entries(&mut entries, |entry| entry.tag == TIFF_TAG_IPTC);
+        }
+        if let Some(iptc) = request.iptc.as_ref().filter(|iptc| !iptc.is_empty() && !strip_all) {
+          tiff.put_entry(&mut entries, TIFF_TAG_IPTC, &Value::Undefined(build_iim(iptc), 0))?;
+          changed = true;
+        }
+      }
+    }
+
+    rewritten_next = if changed {
+      let new_offset = tiff.append_ifd(&mut entries, rewritten_next.unwrap_or(next))?;
+      tiff.scrub_table(offset, original_count);
+      Some(new_offset)
+    } else {
+      None
+    };
+  }
+  if let Some(first) = rewritten_next {
+    let bytes = tiff.u32_bytes(first);
+    tiff.data[4..8].copy_from_slice(&bytes);
+  }
+  Ok(())
+}
+
+/// 按请求改写 EXIF（TIFF 结构）。返回 None 表示不保留 EXIF
+fn rebuild_exif(existing: Option<Bytes>, request: &MetadataWrite) -> Result<Option<Vec<u8>>, String> {
+  let mut tiff = match (request.strip, existing) {
+    (StripMode::All, _) | (_, None) => TiffEditor::empty(),
+    (_, Some(bytes)) => TiffEditor::parse(bytes.to_vec())?,
+  };
+  edit_tiff(&mut tiff, request, false)?;
+  // 主 IFD 为空时缩略图也没有意义
+  if tiff.read_ifd(tiff.first_ifd())?.0.is_empty() {
+    return Ok(None);
+  }
+  Ok(Some(tiff.data))
+}
+
+fn write_jpeg(data: Vec<u8>, request: &MetadataWrite) -> Result<Vec<u8>, String> {
+  let mut jpeg = Jpeg::from_bytes(Bytes::from(data)).map_err(|e| e.to_string())?;
+  let exif = rebuild_exif(jpeg.exif(), request)?;
+  // APP1 段长度上限 65535，扣除长度字段与 "Exif\0\0" 前缀
+  if exif.as_ref().is_some_and(|exif| exif.len() > 65527) {
+    return Err("EXIF data is too large for a JPEG APP1 segment".to_string());
+  }
+  jpeg.set_exif(exif.map(Bytes::from));
+
+  let strip_all = request.strip == StripMode::All;
+  let segments = jpeg.segments_mut();
+  let is_xmp = |segment: &JpegSegment| {
+    segment.marker() == markers::APP1 && segment.contents().starts_with(XMP_JPEG_PREFIX)
+  };
+  let is_irb = |segment: &JpegSegment| {
+    segment.marker() == markers::APP13 && segment.contents().starts_with(PHOTOSHOP_PREFIX)
+  };
+
+  if strip_all || request.xmp.is_some() {
+    segments.retain(|segment| !is_xmp(segment));
+  }
+  if let Some(xmp) = request.xmp.as_deref().filter(|xmp| !xmp.is_empty()) {
+    let mut contents = XMP_JPEG_PREFIX.to_vec();
+    contents.extend_from_slice(xmp.as_bytes());
+    let position = segments
+      .iter()
+      .position(|segment| segment.marker() != markers::APP0 && segment.marker() != markers::APP1)
+      .unwrap_or(0);
+    segments.insert(
+      position,
+      JpegSegment::new_with_contents(markers::APP1, Bytes::from(contents)),
+    );
+  }
+
+  if strip_all || request.iptc.is_some() {
+    // 只替换 IPTC 资源，其余 Photoshop 资源保留
+    let mut resources: Vec<Resource> = segments
+      .iter()
+      .filter(|segment| is_irb(segment))
+      .flat_map(|segment| parse_resources(&segment.contents()[PHOTOSHOP_PREFIX.len()..]))
+      .filter(|resource| resource.id != IPTC_RESOURCE_ID)
+      .collect();
+    let position = segments.iter().position(is_irb);
+    segments.retain(|segment| !is_irb(segment));
+    if let Some(entries) = request.iptc.as_ref().filter(|entries| !entries.is_empty() && !strip_all) {
+      resources.push(Resource {
+        id: IPTC_RESOURCE_ID,
+        name: Vec::new(),
+        data: build_iim(entries),
+      });
+    }
+    if !resources.is_empty() {
+      let mut contents = PHOTOSHOP_PREFIX.to_vec();
+      contents.extend(build_resources(&resources));
+      let position = position.unwrap_or_else(|| {
+        segments
+          .iter()
+          .position(|segment| !matches!(segment.marker(), markers::APP0 | markers::APP1))
+          .unwrap_or(0)
+      });
+      segments.insert(
+        position.min(segments.len()),
+        JpegSegment::new_with_contents(markers::APP13, Bytes::from(contents)),
+      );
+    }
+  }
+
+  let mut output = Vec::new();
+  jpeg
+    .encoder()
+    .write_to(&mut output)
+    .map_err(|e| e.to_string())?;
+  Ok(output)
+}
+
+fn build_itxt(keyword: &[u8], text: &str) -> Vec<u8> {
+  let mut contents = keyword.to_vec();
+  // 关键字结束符、不压缩、压缩方法、空语言标签、空翻译关键字
+  contents.extend_from_slice(&[0, 0, 0, 0, 0]);
+  contents.extend_from_slice(text.as_bytes());
+  contents
+}
+
+fn write_png(data: Vec<u8>, request: &MetadataWrite) -> Result<Vec<u8>, String> {
+  let mut png = Png::from_bytes(Bytes::from(data)).map_err(|e| e.to_string())?;
+  let exif = rebuild_exif(png.exif(), request)?;
+  png.set_exif(exif.map(Bytes::from));
+
+  let strip_all = request.strip == StripMode::All;
+  let chunks = png.chunks_mut();
+  if strip_all || request.xmp.is_some() {
+    chunks.retain(|chunk| {
+      !(&chunk.kind() == b"iTXt"
+        && parse_itxt(chunk.contents()).is_some_and(|(keyword, _)| keyword == XMP_PNG_KEYWORD))
+    });
+  }
+  if strip_all {
+    // ImageMagick 等工具会把 IPTC/EXIF 放在 "Raw profile type" 文本块中
+    chunks.retain(|chunk| {
+      !(matches!(&chunk.kind(), b"tEXt" | b"zTXt") && chunk.contents().starts_with(b"Raw profile type"))
+    });
+  }
+  if request.iptc.is_some() {
+    chunks.retain(|chunk| parse_text_chunk(chunk).is_none_or(|(keyword, _)| keyword != PNG_IPTC_KEYWORD));
+  }
+  let position = chunks
+    .iter()
+    .position(|chunk| &chunk.kind() == b"IDAT")
+    .unwrap_or(chunks.len().saturating_sub(1));
+  if let Some(xmp) = request.xmp.as_deref().filter(|xmp| !xmp.is_empty()) {
+    chunks.insert(
+      position,
+      PngChunk::new(*b"iTXt", Bytes::from(build_itxt(XMP_PNG_KEYWORD, xmp))),
+    );
+  }
+  if let Some(entries) = request.iptc.as_ref().filter(|entries| !entries.is_empty()) {
+    // 与 ExifTool/ImageMagick 一致：IPTC 以 Photoshop 资源块封装后写入 tEXt
+    let resource = Resource {
+      id: IPTC_RESOURCE_ID,
+      name: Vec::new(),
+      data: build_iim(entries),
+    };
+    let mut contents = PNG_IPTC_KEYWORD.to_vec();
+    contents.push(0);
+    contents.extend(build_raw_profile("iptc", &build_resources(&[resource])));
+    chunks.insert(position, PngChunk::new(*b"tEXt", Bytes::from(contents)));
+  }
+
+  let mut output = Vec::new();
+  png
+    .encoder()
+    .write_to(&mut output)
+    .map_err(|e| e.to_string())?;
+  Ok(output)
+}
+
+/// 按实际存在的块重建 VP8X 头；`original` 为改写前的 VP8X 内容，其中的 alpha/动画标志原样保留
+fn sync_vp8x(webp: &mut WebP, original: Option<Bytes>) {
+  const ICC: u8 = 0x20;
+  const ALPHA: u8 = 0x10;
+  const EXIF: u8 = 0x08;
+  const XMP: u8 = 0x04;
+  webp.remove_chunks_by_id(CHUNK_VP8X);
+  let has = |id| webp.has_chunk(id);
+  let mut flags = original.as_ref().and_then(|data| data.first().copied()).unwrap_or(0) & !(ICC | EXIF | XMP);
+  if has(CHUNK_ICCP) {
+    flags |= ICC;
+  }
+  if has(CHUNK_EXIF) {
+    flags |= EXIF;
+  }
+  if has(CHUNK_XMP) {
+    flags |= XMP;
+  }
+  if has(CHUNK_ALPH) {
+    flags |= ALPHA;
+  }
+  let mut content = match original {
+    Some(data) if data.len() >= 10 => data.to_vec(),
+    _ => {
+      if flags == 0 {
+        return;
+      }
+      let Some((width, height)) = webp.dimensions() else {
+        return;
+      };
+      // 无损格式的 alpha 标志位于 VP8L 头第 28 位
+      let lossless_alpha = webp
+        .chunk_by_id(CHUNK_VP8L)
+        .and_then(|chunk| chunk.content().data()?.get(1..5).map(|bits| bits[3] & 0x10 != 0))
+        .unwrap_or(false);
+      if lossless_alpha {
+        flags |= ALPHA;
+      }
+      let mut content = vec![0; 4];
+      content.extend_from_slice(&(width - 1).to_le_bytes()[..3]);
+      content.extend_from_slice(&(height - 1).to_le_bytes()[..3]);
+      content
+    }
+  };
+  content[0] = flags;
+  webp
+    .chunks_mut()
+    .insert(0, RiffChunk::new(CHUNK_VP8X, RiffContent::Data(Bytes::from(content))));
+}
+
+/// WebP 没有 IPTC 容器，IPTC 字段写入 XMP 中对应的 IPTC Core 属性
+fn write_webp(data: Vec<u8>, request: &MetadataWrite) -> Result<Vec<u8>, String> {
+  let mut webp = WebP::from_bytes(Bytes::from(data)).map_err(|e| e.to_string())?;
+  let original = webp
+    .chunk_by_id(CHUNK_VP8X)
+    .and_then(|chunk| chunk.content().data().cloned());
+  // 规范中 EXIF 块直接存放 TIFF 结构；img-parts 会额外加上 "Exif\0\0" 前缀，这里自行读写
+  let existing = webp
+    .chunk_by_id(CHUNK_EXIF)
+    .and_then(|chunk| chunk.content().data())
+    .map(|data| data.slice(if data.starts_with(b"Exif\0\0") { 6 } else { 0 }..));
+  let exif = rebuild_exif(existing, request)?;
+  let xmp = updated_xmp(webp_xmp(&webp), request)?;
+  webp.remove_chunks_by_id(CHUNK_EXIF);
+  if let Some(exif) = exif {
+    webp
+      .chunks_mut()
+      .push(RiffChunk::new(CHUNK_EXIF, RiffContent::Data(Bytes::from(exif))));
+  }
+  if let Some(xmp) = xmp {
+    webp.remove_chunks_by_id(CHUNK_XMP);
+    if let Some(xmp) = xmp {
+      webp
+        .chunks_mut()
+        .push(RiffChunk::new(CHUNK_XMP, RiffContent::Data(Bytes::from(xmp.into_bytes()))));
+    }
+  }
+  sync_vp8x(&mut webp, original);
+
+  let mut output = Vec::new();
+  webp
+    .encoder()
+    .write_to(&mut output)
+    .map_err(|e| e.to_string())?;
+  Ok(output)
+}
+
+/// TIFF 的元数据与图像结构同在 IFD 中：只重写改动过的 IFD，条带、分块与其余字段原位保留
+fn write_tiff(data: Vec<u8>, request: &MetadataWrite) -> Result<Vec<u8>, String> {
+  let mut tiff = TiffEditor::parse(data)?;
+  edit_tiff(&mut tiff, request, true)?;
+  Ok(tiff.data)
+}
+
+type ItemReference = ([u8; 4], u32, Vec<u32>);
+
+/// 解析 iref：版本 0 的条目 ID 为 16 位，版本 1 为 32 位
+fn parse_iref(iref: &[u8]) -> Option<Vec<ItemReference>> {
+  let id_size = if *iref.first()? == 0 { 2 } else { 4 };
+  let mut references = Vec::new();
+  for (kind, contents) in parse_boxes(iref.get(4..)?) {
+    let mut pos = 0;
+    let from = read_uint(contents, &mut pos, id_size)? as u32;
+    let count = read_uint(contents, &mut pos, 2)?;
+    let to = (0..count)
+      .map(|_| read_uint(contents, &mut pos, id_size).map(|id| id as u32))
+      .collect::<Option<Vec<_>>>()?;
+    references.push((kind, from, to));
+  }
+  Some(references)
+}
+
+fn build_iref(references: &[ItemReference]) -> Vec<u8> {
+  let wide = references
+    .iter()
+    .any(|(_, from, to)| *from > 0xFFFF || to.iter().any(|id| *id > 0xFFFF));
+  let id_size = if wide { 4 } else { 2 };
+  let mut contents = vec![u8::from(wide), 0, 0, 0];
+  for (kind, from, to) in references {
+    let mut reference = Vec::new();
+    write_uint(&mut reference, u64::from(*from), id_size);
+    write_uint(&mut reference, to.len() as u64, 2);
+    for id in to {
+      write_uint(&mut reference, u64::from(*id), id_size);
+    }
+    contents.extend(make_box(kind, &reference));
+  }
+  make_box(b"iref", &contents)
+}
+
+fn build_infe(id: u32, item_type: &[u8; 4], content_type: &[u8]) -> Vec<u8> {
+  let wide = id > 0xFFFF;
+  let mut contents = vec![if wide { 3 } else { 2 }, 0, 0, 0];
+  write_uint(&mut contents, u64::from(id), if wide { 4 } else { 2 });
+  // item_protection_index 与空的 item_name
+  contents.extend_from_slice(&[0, 0]);
+  contents.extend_from_slice(item_type);
+  contents.push(0);
+  if !content_type.is_empty() {
+    contents.extend_from_slice(content_type);
+    contents.push(0);
+  }
+  make_box(b"infe", &contents)
+}
+
+/// HEIC 的元数据是独立条目，数据位置由 iloc 描述。改写时移除旧的 Exif/XMP 条目并清零其数据，
+/// 新数据放在文件末尾追加的 mdat 中；meta 长度变化后，位于 meta 之后的条目偏移随之修正，图像数据原样保留
+fn write_heic(mut data: Vec<u8>, request: &MetadataWrite) -> Result<Vec<u8>, String> {
+  let top = box_spans(&data);
+  if top.iter().any(|(kind, ..)| kind == b"moov") {
+    return Err("HEIC image sequences are not supported".to_string());
+  }
+  let (_, meta_start, meta_range) = top
+    .iter()
+    .find(|(kind, ..)| kind == b"meta")
+    .cloned()
+    .ok_or("HEIC file has no meta box")?;
+  let (meta_end, children_start) = (meta_range.end, meta_range.start + 4);
+  let meta = data.get(children_start..meta_end).ok_or("Invalid meta box")?;
+  let children: Vec<([u8; 4], Range<usize>)> = box_spans(meta)
+    .into_iter()
+    .map(|(kind, start, range)| (kind, children_start + start..children_start + range.end))
+    .collect();
+  let child = |kind: &[u8; 4]| {
+    box_spans(meta)
+      .into_iter()
+      .find(|(found, ..)| found == kind)
+      .map(|(_, _, range)| children_start + range.start..children_start + range.end)
+  };
+
+  let items = heic_items(meta);
+  let mut iloc = find_box(meta, b"iloc")
+    .and_then(parse_iloc)
+    .ok_or("HEIC file has no item locations")?;
+  let iinf = data[child(b"iinf").ok_or("HEIC file has no item information")?].to_vec();
+  let mut references = find_box(meta, b"iref").and_then(parse_iref).unwrap_or_default();
+  let idat = child(b"idat");
+  let primary = find_box(meta, b"pitm").and_then(|pitm| {
+    let mut pos = 4;
+    read_uint(pitm, &mut pos, if *pitm.first()? == 0 { 2 } else { 4 }).map(|id| id as u32)
+  });
+  let exif_items: Vec<u32> = items
+    .iter()
+    .filter(|item| &item.item_type == b"Exif")
+    .map(|item| item.id)
+    .collect();
+  let xmp_items: Vec<u32> = items
+    .iter()
+    .filter(|item| &item.item_type == b"mime" && item.content_type == HEIC_XMP_MIME)
+    .map(|item| item.id)
+    .collect();
+
+  let mut removed = Vec::new();
+  let mut added: Vec<(&[u8; 4], &[u8], Vec<u8>)> = Vec::new();
+  if request.strip != StripMode::Keep || !request.exif.is_empty() || request.gps.is_some() {
+    let existing = exif_items
+      .first()
+      .and_then(|id| heic_item_data(&data, meta, *id))
+      .and_then(|item| heic_exif_tiff(&item).map(Bytes::copy_from_slice));
+    removed.extend(&exif_items);
+    if let Some(tiff) = rebuild_exif(existing, request)? {
+      let mut payload = vec![0, 0, 0, 6];
+      payload.extend_from_slice(b"Exif\0\0");
+      payload.extend(tiff);
+      added.push((b"Exif", b"", payload));
+    }
+  }
+  if let Some(xmp) = updated_xmp(heic_xmp(&data), request)? {
+    removed.extend(&xmp_items);
+    if let Some(xmp) = xmp {
+      added.push((b"mime", HEIC_XMP_MIME, xmp.into_bytes()));
+    }
+  }
+  if removed.is_empty() && added.is_empty() {
+    return Ok(data);
+  }
+
+  // 清零被移除条目的数据，避免旧的 GPS 等信息残留在 mdat 或 idat 中
+  let file_len = data.len() as u64;
+  for item in iloc.items.iter().filter(|item| removed.contains(&item.id)) {
+    let source = match (item.method, item.data_ref) {
+      (0, 0) => 0..data.len(),
+      (1, _) => match &idat {
+        Some(range) => range.clone(),
+        None => continue,
+      },
+      _ => continue,
+    };
+    for &(_, offset, length) in &item.extents {
+      let start = (source.start as u64).saturating_add(item.base).saturating_add(offset);
+      let end = if length == 0 { source.end as u64 } else { start.saturating_add(length) };
+      let (start, end) = (start.min(source.end as u64) as usize, end.min(source.end as u64) as usize);
+      data[start..end.max(start)].fill(0);
+    }
+  }
+  iloc.items.retain(|item| !removed.contains(&item.id));
+  for item in iloc.items.iter_mut().filter(|item| item.method == 0 && item.data_ref == 0) {
+    for extent in item.extents.iter_mut() {
+      let position = item.base.saturating_add(extent.1);
+      if position >= meta_start as u64 && position < meta_end as u64 {
+        return Err("HEIC item data stored inside the meta box is not supported".to_string());
+      }
+      // 长度为 0 表示延伸到文件末尾；追加 mdat 后需要改为显式长度
+      if extent.2 == 0 {
+        extent.2 = file_len.saturating_sub(position);
+      }
+    }
+  }
+
+  let next_id = items
+    .iter()
+    .map(|item| item.id)
+    .chain(iloc.items.iter().map(|item| item.id))
+    .chain(removed.iter().copied())
+    .max()
+    .unwrap_or(0)
+    + 1;
+  let new_ids: Vec<u32> = (next_id..).take(added.len()).collect();
+  references.retain_mut(|(_, from, to)| {
+    to.retain(|id| !removed.contains(id));
+    !removed.contains(from) && !to.is_empty()
+  });
+  if let Some(primary) = primary {
+    for id in &new_ids {
+      references.push((*b"cdsc", *id, vec![primary]));
+    }
+  }
+
+  let iinf_start = iinf_entries_start(&iinf).ok_or("Invalid iinf box")?;
+  let entries = iinf.get(iinf_start..).ok_or("Invalid iinf box")?;
+  let mut infe_boxes: Vec<Vec<u8>> = box_spans(entries)
+    .into_iter()
+    .filter(|(kind, _, range)| {
+      !(kind == b"infe" && parse_infe(&entries[range.clone()]).is_some_and(|info| removed.contains(&info.id)))
+    })
+    .map(|(_, start, range)| entries[start..range.end].to_vec())
+    .collect();
+  for ((item_type, content_type, _), id) in added.iter().zip(&new_ids) {
+    infe_boxes.push(build_infe(*id, item_type, content_type));
+  }
+  let iinf_version = if infe_boxes.len() > 0xFFFF { 1 } else { iinf[0] };
+  let mut iinf_contents = vec![iinf_version];
+  iinf_contents.extend_from_slice(&iinf[1..4]);
+  write_uint(&mut iinf_contents, infe_boxes.len() as u64, if iinf_version == 0 { 2 } else { 4 });
+  iinf_contents.extend(infe_boxes.concat());
+  let iinf_box = make_box(b"iinf", &iinf_contents);
+
+  // 字段宽度在两次构建之间保持不变，meta 的长度因此与偏移取值无关
+  let payload_len: usize = added.iter().map(|(_, _, payload)| payload.len()).sum();
+  let large = file_len + payload_len as u64 + 0x10000 > u64::from(u32::MAX);
+  let width = |size: usize| if large { 8 } else { size.max(4) };
+  iloc.offset_size = width(iloc.offset_size);
+  iloc.length_size = width(iloc.length_size);
+  if iloc.base_size > 0 {
+    iloc.base_size = width(iloc.base_size);
+  }
+  if iloc.version < 2 && new_ids.iter().any(|id| *id > 0xFFFF) {
+    iloc.version = 2;
+  }
+
+  let build_meta = |delta: i64| -> Result<Vec<u8>, String> {
+    let shift = |value: u64| {
+      u64::try_from(value as i64 + delta).map_err(|_| "Invalid HEIC item offset".to_string())
+    };
+    let mut iloc = iloc.clone();
+    for item in iloc.items.iter_mut().filter(|item| item.method == 0 && item.data_ref == 0) {
+      if item.base >= meta_end as u64 {
+        item.base = shift(item.base)?;
+        continue;
+      }
+      for extent in item.extents.iter_mut() {
+        if item.base.saturating_add(extent.1) >= meta_end as u64 {
+          extent.1 = shift(extent.1)?;
+        }
+      }
+    }
+    let mut offset = shift(file_len)? + 8;
+    for ((_, _, payload), id) in added.iter().zip(&new_ids) {
+      iloc.items.push(IlocItem {
+        id: *id,
+        method: 0,
+        data_ref: 0,
+        base: 0,
+        extents: vec![(0, offset, payload.len() as u64)],
+      });
+      offset += payload.len() as u64;
+    }
+
+    let mut contents = data[meta_range.start..children_start].to_vec();
+    for (kind, range) in &children {
+      match kind {
+        b"iinf" => {
+          contents.extend_from_slice(&iinf_box);
+          if !references.is_empty() && !children.iter().any(|(kind, _)| kind == b"iref") {
+            contents.extend(build_iref(&references));
+          }
+        }
+        b"iloc" => contents.extend(build_iloc(&iloc)),
+        b"iref" if references.is_empty() => {}
+        b"iref" => contents.extend(build_iref(&references)),
+        _ => contents.extend_from_slice(&data[range.clone()]),
+      }
+    }
+    Ok(make_box(b"meta", &contents))
+  };
+  let delta = build_meta(0)?.len() as i64 - (meta_end - meta_start) as i64;
+  let meta = build_meta(delta)?;
+
+  // 大小为 0 的盒子延伸到文件末尾，追加 mdat 前改为显式大小
+  for (_, start, range) in &top {
+    if *start > meta_start && data[*start..*start + 4] == [0, 0, 0, 0] {
+      let size = u32::try_from(range.end - start).map_err(|_| "HEIC file is too large".to_string())?;
+      data[*start..*start + 4].copy_from_slice(&size.to_be_bytes());
+    }
+  }
+  let mut output = Vec::with_capacity(data.len() + meta.len() + payload_len + 8);
+  output.extend_from_slice(&data[..meta_start]);
+  output.extend(meta);
+  output.extend_from_slice(&data[meta_end..]);
+  if !added.is_empty() {
+    let payload: Vec<u8> = added.into_iter().flat_map(|(_, _, payload)| payload).collect();
+    output.extend(make_box(b"mdat", &payload));
+  }
+  Ok(output)
+}
+
+/// 无损改写元数据：只替换元数据段，像素数据原样保留。`output` 为空时覆盖原文件
+///
+/// JPEG、PNG、TIFF 支持全部字段；WebP 与 HEIC 没有 IPTC 容器，IPTC 写入 XMP 中对应的 IPTC Core 属性
+pub fn write(path: &str, output: Option<&str>, request: &MetadataWrite) -> Result<(), String> {
+  let data = std::fs::read(path).map_err(|e| format!("Failed to read {path}: {e}"))?;
+  let rewritten = match detect_format(&data)? {
+    MetadataFormat::Jpeg => write_jpeg(data, request)?,
+    MetadataFormat::Png => write_png(data, request)?,
+    MetadataFormat::Webp => write_webp(data, request)?,
+    MetadataFormat::Tiff => write_tiff(data, request)?,
+    MetadataFormat::Heic => write_heic(data, request)?,
+  };
+
+  let target = output.unwrap_or(path);
+  if let Some(parent) = Path::new(target).parent() {
+    std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+  }
+  // 先写临时文件再改名，避免覆盖原图时中途失败
+  let temp = format!("{target}.kit-tmp");
+  std::fs::write(&temp, rewritten).map_err(|e| format!("Failed to write {target}: {e}"))?;
+  std::fs::rename(&temp, target).map_err(|e| {
+    let _ = std::fs::remove_file(&temp);
+    format!("Failed to write {target}: {e}")
+  })
+}
+
+/// 读取图片元数据，供 exif-viewer 使用
+#[tauri::command]
+pub async fn metadata_read(path: String) -> Result<MetadataReport, String> {
+  tauri::async_runtime::spawn_blocking(move || read(&path))
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 写入或清除图片元数据
+#[tauri::command]
+pub async fn metadata_write(
+  path: String,
+  output: Option<String>,
+  request: MetadataWrite,
+) -> Result<(), String> {
+  tauri::async_runtime::spawn_blocking(move || write(&path, output.as_deref(), &request))
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use image::{ImageFormat, Rgb, RgbImage};
+
+  fn sample(format: ImageFormat) -> Vec<u8> {
+    let image = RgbImage::from_fn(16, 8, |x, y| Rgb([x as u8 * 16, y as u8 * 32, 128]));
+    let mut output = Cursor::new(Vec::new());
+    image.write_to(&mut output, format).unwrap();
+    output.into_inner()
+  }
+
+  fn pixels(path: &Path) -> Vec<u8> {
+    image::open(path).unwrap().to_rgb8().into_raw()
+  }
+
+  fn iptc(dataset: u8, value: &str) -> IptcEntry {
+    IptcEntry {
+      record: 2,
+      dataset,
+      name: String::new(),
+      value: value.to_string(),
+    }
+  }
+
+  fn exif_edit(tag: &str, value: &str) -> ExifEdit {
+    ExifEdit {
+      tag: tag.to_string(),
+      value: value.to_string(),
+    }
+  }
+
+  fn exif_value(report: &MetadataReport, tag: &str) -> Option<String> {
+    report
+      .exif
+      .iter()
+      .find(|entry| entry.tag == tag)
+      .map(|entry| entry.value.clone())
+  }
+
+  fn keywords(report: &MetadataReport) -> Vec<&str> {
+    report
+      .iptc
+      .iter()
+      .filter(|entry| entry.dataset == 25)
+      .map(|entry| entry.value.as_str())
+      .collect()
+  }
+
+  fn strip(mode: StripMode) -> MetadataWrite {
+    MetadataWrite {
+      strip: mode,
+      ..Default::default()
+    }
+  }
+
+  fn edit_request() -> MetadataWrite {
+    MetadataWrite {
+      exif: vec![
+        exif_edit("Artist", "Ada Lovelace"),
+        exif_edit("DateTimeOriginal", "2024:01:02 03:04:05"),
+      ],
+      gps: Some(GpsInfo {
+        latitude: 35.6762,
+        longitude: -139.6503,
+        altitude: Some(40.0),
+      }),
+      iptc: Some(vec![iptc(25, "travel"), iptc(25, "tokyo"), iptc(120, "Fish & <Chips>")]),
+      ..Default::default()
+    }
+  }
+
+  #[test]
+  fn round_trips_and_strips_every_container() {
+    let dir = tempfile::tempdir().unwrap();
+    for (format, name) in [
+      (ImageFormat::Jpeg, "a.jpg"),
+      (ImageFormat::Png, "a.png"),
+      (ImageFormat::WebP, "a.webp"),
+      (ImageFormat::Tiff, "a.tif"),
+    ] {
+      let path = dir.path().join(name);
+      std::fs::write(&path, sample(format)).unwrap();
+      let original = pixels(&path);
+      let path = path.to_str().unwrap();
+
+      write(path, None, &edit_request()).unwrap();
+      let report = read(path).unwrap();
+      assert!(exif_value(&report, "Artist").unwrap().contains("Ada Lovelace"), "{name}");
+      assert!(exif_value(&report, "DateTimeOriginal").unwrap().contains("2024-01-02"), "{name}");
+      let gps = report.gps.unwrap();
+      assert!((gps.latitude - 35.6762).abs() < 1e-6, "{name}: {gps:?}");
+      assert!((gps.longitude + 139.6503).abs() < 1e-6, "{name}: {gps:?}");
+      assert_eq!(gps.altitude, Some(40.0), "{name}");
+      assert_eq!(keywords(&report), ["travel", "tokyo"], "{name}");
+      assert!(report.iptc.iter().any(|entry| entry.value == "Fish & <Chips>"), "{name}");
+      assert_eq!(pixels(Path::new(path)), original, "{name}");
+
+      write(path, None, &strip(StripMode::Gps)).unwrap();
+      let report = read(path).unwrap();
+      assert!(report.gps.is_none(), "{name}");
+      assert!(report.exif.iter().all(|entry| !entry.tag.starts_with("GPS")), "{name}");
+      assert!(exif_value(&report, "Artist").is_some(), "{name}");
+      assert_eq!(keywords(&report), ["travel", "tokyo"], "{name}");
+
+      write(path, None, &strip(StripMode::All)).unwrap();
+      let report = read(path).unwrap();
+      assert!(exif_value(&report, "Artist").is_none(), "{name}");
+      assert!(exif_value(&report, "DateTimeOriginal").is_none(), "{name}");
+      assert!(report.iptc.is_empty() && report.xmp.is_none(), "{name}");
+      assert_eq!(pixels(Path::new(path)), original, "{name}");
+    }
+  }
+
+  #[test]
+  fn webp_iptc_is_stored_as_xmp() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("a.webp");
+    std::fs::write(&path, sample(ImageFormat::WebP)).unwrap();
+    let path = path.to_str().unwrap();
+    let existing = "<x:xmpmeta xmlns:x=\"adobe:ns:meta/\"><rdf:RDF xmlns:rdf=\"http://www.w3.org/1999/02/22-rdf-syntax-ns#\"><rdf:Description rdf:about=\"\" xmlns:xmp=\"http://ns.adobe.com/xap/1.0/\" xmp:Rating=\"4\" xmlns:photoshop=\"http://ns.adobe.com/photoshop/1.0/\" photoshop:City=\"Osaka\"/></rdf:RDF></x:xmpmeta>";
+
+    let request = MetadataWrite {
+      xmp: Some(existing.to_string()),
+      iptc: Some(vec![iptc(25, "a<b"), iptc(5, "Title"), iptc(55, "20240102")]),
+      ..Default::default()
+    };
+    write(path, None, &request).unwrap();
+    let report = read(path).unwrap();
+    let xmp = report.xmp.as_deref().unwrap();
+    assert!(xmp.contains("xmp:Rating=\"4\""));
+    assert!(xmp.contains("<rdf:li>a&lt;b</rdf:li>"));
+    assert!(xmp.contains("<photoshop:DateCreated>2024-01-02</photoshop:DateCreated>"));
+    assert!(!xmp.contains("Osaka"));
+    let datasets: Vec<(u8, &str)> = report
+      .iptc
+      .iter()
+      .map(|entry| (entry.dataset, entry.value.as_str()))
+      .collect();
+    assert_eq!(datasets, [(5, "Title"), (25, "a<b"), (55, "20240102")]);
+
+    let request = MetadataWrite {
+      iptc: Some(Vec::new()),
+      ..Default::default()
+    };
+    write(path, None, &request).unwrap();
+    let report = read(path).unwrap();
+    assert!(report.iptc.is_empty());
+    assert!(report.xmp.unwrap().contains("xmp:Rating=\"4\""));
+
+    let request = MetadataWrite {
+      iptc: Some(vec![iptc(103, "ref")]),
+      ..Default::default()
+    };
+    assert!(write(path, None, &request).is_err());
+  }
+
+  /// 在 TIFF 主 IFD 中加入一个 LONG8 类型（kamadak-exif 无法解析）的私有标签
+  fn tiff_with_private_tag(private: &[u8; 16]) -> Vec<u8> {
+    let mut tiff = TiffEditor::parse(sample(ImageFormat::Tiff)).unwrap();
+    let request = MetadataWrite {
+      exif: vec![exif_edit("Artist", "Original Artist Name")],
+      ..Default::default()
+    };
+    edit_tiff(&mut tiff, &request, true).unwrap();
+    let first = tiff.first_ifd();
+    let (mut entries, next) = tiff.read_ifd(first).unwrap();
+    let offset = tiff.append(private).unwrap();
+    entries.push(IfdEntry {
+      tag: 50000,
+      typ: 16,
+      count: 2,
+      value: tiff.u32_bytes(offset),
+    });
+    let first = tiff.append_ifd(&mut entries, next).unwrap();
+    let bytes = tiff.u32_bytes(first);
+    tiff.data[4..8].copy_from_slice(&bytes);
+    tiff.data
+  }
+
+  fn private_tag(data: &[u8]) -> Option<Vec<u8>> {
+    let tiff = TiffEditor::parse(data.to_vec()).unwrap();
+    let (entries, _) = tiff.read_ifd(tiff.first_ifd()).unwrap();
+    let entry = entries.iter().find(|entry| entry.tag == 50000)?;
+    Some(tiff.data[tiff.value_range(entry)?].to_vec())
+  }
+
+  #[test]
+  fn tiff_rewrite_keeps_unknown_fields() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("a.tif");
+    let private = *b"private-payload!";
+    std::fs::write(&path, tiff_with_private_tag(&private)).unwrap();
+    let original = pixels(&path);
+    let path = path.to_str().unwrap();
+
+    write(path, None, &edit_request()).unwrap();
+    let data = std::fs::read(path).unwrap();
+    assert_eq!(private_tag(&data).unwrap(), private);
+    // 被替换的旧值已清零，不会残留在文件中
+    assert!(!data.windows(20).any(|window| window == b"Original Artist Name"));
+    assert!(exif_value(&read(path).unwrap(), "Artist").unwrap().contains("Ada Lovelace"));
+
+    write(path, None, &strip(StripMode::All)).unwrap();
+    let data = std::fs::read(path).unwrap();
+    assert_eq!(private_tag(&data).unwrap(), private);
+    assert!(!data.windows(12).any(|window| window == b"Ada Lovelace"));
+    assert_eq!(pixels(Path::new(path)), original);
+  }
+
+  #[test]
+  fn jpeg_rewrite_keeps_thumbnail() {
+    let thumbnail = sample(ImageFormat::Jpeg);
+    let mut writer = exif::experimental::Writer::new();
+    let make = Field {
+      tag: Tag::Make,
+      ifd_num: In::PRIMARY,
+      value: Value::Ascii(vec![b"Camera".to_vec()]),
+    };
+    let compression = Field {
+      tag: Tag::Compression,
+      ifd_num: In::THUMBNAIL,
+      value: Value::Short(vec![6]),
+    };
+    writer.push_field(&make);
+    writer.push_field(&compression);
+    writer.set_jpeg(&thumbnail, In::THUMBNAIL);
+    let mut tiff = Cursor::new(Vec::new());
+    writer.write(&mut tiff, false).unwrap();
+    let mut jpeg = Jpeg::from_bytes(Bytes::from(sample(ImageFormat::Jpeg))).unwrap();
+    jpeg.set_exif(Some(Bytes::from(tiff.into_inner())));
+    let mut data = Vec::new();
+    jpeg.encoder().write_to(&mut data).unwrap();
+
+    let data = write_jpeg(data, &edit_request()).unwrap();
+    let jpeg = Jpeg::from_bytes(Bytes::from(data)).unwrap();
+    let exif = exif::Reader::new().read_raw(jpeg.exif().unwrap().to_vec()).unwrap();
+    let field = |tag| exif.get_field(tag, In::THUMBNAIL).unwrap().value.get_uint(0).unwrap() as usize;
+    let (offset, len) = (field(Tag::JPEGInterchangeFormat), field(Tag::JPEGInterchangeFormatLength));
+    assert_eq!(&exif.buf()[offset..offset + len], &thumbnail[..]);
+    assert!(exif.get_field(Tag::Make, In::PRIMARY).is_some());
+    assert!(exif.get_field(Tag::Artist, In::PRIMARY).is_some());
+  }
+
+  const HEIC_IMAGE: &[u8] = b"hvc1-coded-image-data";
+
+  /// 最小的 HEIC 结构：主图像条目与 Exif 条目，数据都放在 meta 之后的 mdat 中
+  fn sample_heic(exif: &[u8]) -> Vec<u8> {
+    let mut ftyp = b"heic\0\0\0\0".to_vec();
+    ftyp.extend_from_slice(b"mif1heic");
+    let ftyp = make_box(b"ftyp", &ftyp);
+    let mut payload = vec![0, 0, 0, 6];
+    payload.extend_from_slice(b"Exif\0\0");
+    payload.extend_from_slice(exif);
+
+    let build = |mdat_start: u64| {
+      let mut hdlr = vec![0; 8];
+      hdlr.extend_from_slice(b"pict");
+      hdlr.extend_from_slice(&[0; 13]);
+      let iloc = Iloc {
+        version: 1,
+        offset_size: 4,
+        length_size: 4,
+        base_size: 0,
+        index_size: 0,
+        items: vec![
+          IlocItem {
+            id: 1,
+            method: 0,
+            data_ref: 0,
+            base: 0,
+            extents: vec![(0, mdat_start + 8, HEIC_IMAGE.len() as u64)],
+          },
+          IlocItem {
+            id: 2,
+            method: 0,
+            data_ref: 0,
+            base: 0,
+            extents: vec![(0, mdat_start + 8 + HEIC_IMAGE.len() as u64, payload.len() as u64)],
+          },
+        ],
+      };
+      let mut iinf = vec![0, 0, 0, 0, 0, 2];
+      iinf.extend(build_infe(1, b"hvc1", b""));
+      iinf.extend(build_infe(2, b"Exif", b""));
+      let mut contents = vec![0; 4];
+      contents.extend(make_box(b"hdlr", &hdlr));
+      contents.extend(make_box(b"pitm", &[0, 0, 0, 0, 0, 1]));
+      contents.extend(build_iloc(&iloc));
+      contents.extend(make_box(b"iinf", &iinf));
+      contents.extend(build_iref(&[(*b"cdsc", 2, vec![1])]));
+      make_box(b"meta", &contents)
+    };
+    let meta_len = build(0).len();
+    let mut data = ftyp.clone();
+    data.extend(build((ftyp.len() + meta_len) as u64));
+    let mut mdat = HEIC_IMAGE.to_vec();
+    mdat.extend(payload);
+    data.extend(make_box(b"mdat", &mdat));
+    data
+  }
+
+  fn heic_image_data(data: &[u8]) -> Option<Vec<u8>> {
+    let meta = find_box(data, b"meta")?.get(4..)?;
+    heic_item_data(data, meta, 1)
+  }
+
+  #[test]
+  fn heic_rewrite_relocates_items() {
+    let exif = rebuild_exif(
+      None,
+      &MetadataWrite {
+        exif: vec![exif_edit("Make", "OldCamera")],
+        gps: Some(GpsInfo {
+          latitude: 1.5,
+          longitude: 2.5,
+          altitude: None,
+        }),
+        ..Default::default()
+      },
+    )
+    .unwrap()
+    .unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("a.heic");
+    std::fs::write(&path, sample_heic(&exif)).unwrap();
+    let path = path.to_str().unwrap();
+    let report = read(path).unwrap();
+    assert!(exif_value(&report, "Make").unwrap().contains("OldCamera"));
+    assert_eq!(report.gps.unwrap().latitude, 1.5);
+
+    write(path, None, &edit_request()).unwrap();
+    let data = std::fs::read(path).unwrap();
+    assert_eq!(heic_image_data(&data).unwrap(), HEIC_IMAGE);
+    let report = read(path).unwrap();
+    assert!(exif_value(&report, "Make").unwrap().contains("OldCamera"));
+    assert!(exif_value(&report, "Artist").unwrap().contains("Ada Lovelace"));
+    assert!((report.gps.unwrap().latitude - 35.6762).abs() < 1e-6);
+    assert_eq!(keywords(&report), ["travel", "tokyo"]);
+    let meta = find_box(&data, b"meta").unwrap().get(4..).unwrap();
+    assert_eq!(heic_items(meta).len(), 3);
+    assert_eq!(parse_iref(find_box(meta, b"iref").unwrap()).unwrap().len(), 2);
+
+    write(path, None, &strip(StripMode::All)).unwrap();
+    let data = std::fs::read(path).unwrap();
+    assert_eq!(heic_image_data(&data).unwrap(), HEIC_IMAGE);
+    let report = read(path).unwrap();
+    assert!(report.exif.is_empty() && report.gps.is_none() && report.xmp.is_none());
+    let meta = find_box(&data, b"meta").unwrap().get(4..).unwrap();
+    assert_eq!(heic_items(meta).len(), 1);
+    assert!(find_box(meta, b"iref").is_none());
+    assert!(!data.windows(9).any(|window| window == b"OldCamera"));
+    assert!(!data.windows(5).any(|window| window == b"tokyo"));
+  }
+}
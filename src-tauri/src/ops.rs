@@ -0,0 +1,365 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use base64::alphabet;
+use base64::engine::general_purpose::{GeneralPurpose, GeneralPurposeConfig, STANDARD, URL_SAFE_NO_PAD};
+use base64::engine::DecodePaddingMode;
+use base64::Engine;
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use serde::de::{self, MapAccess, SeqAccess, Visitor};
+use serde::ser::SerializeMap;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// 与 JS `encodeURIComponent` 一致：保留字母数字与 `-_.!~*'()`
+const URI_COMPONENT: &AsciiSet = &NON_ALPHANUMERIC
+  .remove(b'-')
+  .remove(b'_')
+  .remove(b'.')
+  .remove(b'!')
+  .remove(b'~')
+  .remove(b'*')
+  .remove(b'\'')
+  .remove(b'(')
+  .remove(b')');
+
+/// 解码时对 `=` 填充不作要求，带或不带填充的输入都能解码
+const DECODE_CONFIG: GeneralPurposeConfig =
+  GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent);
+const STANDARD_DECODER: GeneralPurpose = GeneralPurpose::new(&alphabet::STANDARD, DECODE_CONFIG);
+const URL_SAFE_DECODER: GeneralPurpose = GeneralPurpose::new(&alphabet::URL_SAFE, DECODE_CONFIG);
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum CodecMode {
+  Encode,
+  Decode,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct TimestampInfo {
+  pub unix: i64,
+  pub unix_ms: i64,
+  pub iso: String,
+  pub local: String,
+}
+
+/// Base64 解码结果：UTF-8 文本直接返回字符串，其他二进制数据返回 `{ "hex": "..." }`
+#[derive(Serialize, Clone, PartialEq, Eq, Debug)]
+#[serde(untagged)]
+pub enum CodecOutput {
+  Text(String),
+  Binary { hex: String },
+}
+
+pub fn base64(input: &[u8], mode: CodecMode, url_safe: bool) -> Result<CodecOutput, String> {
+  match mode {
+    CodecMode::Encode => Ok(CodecOutput::Text(if url_safe {
+      URL_SAFE_NO_PAD.encode(input)
+    } else {
+      STANDARD.encode(input)
+    })),
+    CodecMode::Decode => {
+      let text: String = String::from_utf8_lossy(input)
+        .chars()
+        .filter(|ch| !ch.is_whitespace())
+        .collect();
+      let bytes = if url_safe || text.contains(['-', '_']) {
+        URL_SAFE_DECODER.decode(&text)
+      } else {
+        STANDARD_DECODER.decode(&text)
+      }
+      .map_err(|e| format!("Invalid base64 input: {e}"))?;
+      Ok(match String::from_utf8(bytes) {
+        Ok(text) => CodecOutput::Text(text),
+        Err(error) => CodecOutput::Binary {
+          hex: hex::encode(error.into_bytes()),
+        },
+      })
+    }
+  }
+}
+
+pub fn url(input: &str, mode: CodecMode) -> Result<String, String> {
+  match mode {
+    CodecMode::Encode => Ok(utf8_percent_encode(input, URI_COMPONENT).to_string()),
+    CodecMode::Decode => percent_decode_str(&input.replace('+', " "))
+      .decode_utf8()
+      .map(|text| text.into_owned())
+      .map_err(|e| format!("Invalid URL-encoded input: {e}")),
+  }
+}
+
+pub fn uuid(version: u8, count: usize) -> Result<Vec<String>, String> {
+  if count == 0 || count > 10_000 {
+    return Err("Count must be between 1 and 10000".to_string());
+  }
+  (0..count)
+    .map(|_| match version {
+      4 => Ok(uuid::Uuid::new_v4().to_string()),
+      7 => Ok(uuid::Uuid::now_v7().to_string()),
+      other => Err(format!("Unsupported UUID version: {other}")),
+    })
+    .collect()
+}
+
+/// 保持键顺序的 JSON 值，仅供格式化使用；重复的键与 JS 一致保留首次出现的位置和最后一次的值
+enum OrderedJson {
+  Scalar(serde_json::Value),
+  Array(Vec<OrderedJson>),
+  Object(Vec<(String, OrderedJson)>),
+}
+
+struct OrderedJsonVisitor;
+
+impl<'de> Visitor<'de> for OrderedJsonVisitor {
+  type Value = OrderedJson;
+
+  fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+    formatter.write_str("a JSON value")
+  }
+
+  fn visit_bool<E: de::Error>(self, value: bool) -> Result<OrderedJson, E> {
+    Ok(OrderedJson::Scalar(value.into()))
+  }
+
+  fn visit_i64<E: de::Error>(self, value: i64) -> Result<OrderedJson, E> {
+    Ok(OrderedJson::Scalar(value.into()))
+  }
+
+  fn visit_u64<E: de::Error>(self, value: u64) -> Result<OrderedJson, E> {
+    Ok(OrderedJson::Scalar(value.into()))
+  }
+
+  fn visit_f64<E: de::Error>(self, value: f64) -> Result<OrderedJson, E> {
+    Ok(OrderedJson::Scalar(value.into()))
+  }
+
+  fn visit_str<E: de::Error>(self, value: &str) -> Result<OrderedJson, E> {
+    Ok(OrderedJson::Scalar(value.into()))
+  }
+
+  fn visit_string<E: de::Error>(self, value: String) -> Result<OrderedJson, E> {
+    Ok(OrderedJson::Scalar(value.into()))
+  }
+
+  fn visit_unit<E: de::Error>(self) -> Result<OrderedJson, E> {
+    Ok(OrderedJson::Scalar(serde_json::Value::Null))
+  }
+
+  fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<OrderedJson, A::Error> {
+    let mut items = Vec::new();
+    while let Some(item) = seq.next_element()? {
+      items.push(item);
+    }
+    Ok(OrderedJson::Array(items))
+  }
+
+  fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<OrderedJson, A::Error> {
+    let mut entries: Vec<(String, OrderedJson)> = Vec::new();
+    let mut positions: HashMap<String, usize> = HashMap::new();
+    while let Some((key, value)) = map.next_entry::<String, OrderedJson>()? {
+      match positions.get(&key) {
+        Some(&index) => entries[index].1 = value,
+        None => {
+          positions.insert(key.clone(), entries.len());
+          entries.push((key, value));
+        }
+      }
+    }
+    Ok(OrderedJson::Object(entries))
+  }
+}
+
+impl<'de> Deserialize<'de> for OrderedJson {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    deserializer.deserialize_any(OrderedJsonVisitor)
+  }
+}
+
+impl Serialize for OrderedJson {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    match self {
+      OrderedJson::Scalar(value) => value.serialize(serializer),
+      OrderedJson::Array(items) => serializer.collect_seq(items),
+      OrderedJson::Object(entries) => {
+        let mut map = serializer.serialize_map(Some(entries.len()))?;
+        for (key, value) in entries {
+          map.serialize_entry(key, value)?;
+        }
+        map.end()
+      }
+    }
+  }
+}
+
+/// 格式化或压缩 JSON，保持原有键顺序
+pub fn json_format(input: &str, indent: Option<usize>, minify: bool) -> Result<String, String> {
+  let value: OrderedJson = serde_json::from_str(input).map_err(|e| format!("Invalid JSON: {e}"))?;
+  if minify {
+    return serde_json::to_string(&value).map_err(|e| e.to_string());
+  }
+  let indent = " ".repeat(indent.unwrap_or(2).min(16));
+  let mut output = Vec::new();
+  let formatter = serde_json::ser::PrettyFormatter::with_indent(indent.as_bytes());
+  let mut serializer = serde_json::Serializer::with_formatter(&mut output, formatter);
+  value.serialize(&mut serializer).map_err(|e| e.to_string())?;
+  String::from_utf8(output).map_err(|e| e.to_string())
+}
+
+/// 时间戳转换：支持秒/毫秒（按数量级自动判断）、RFC 3339 与常见日期格式，缺省为当前时间
+pub fn timestamp(input: Option<&str>) -> Result<TimestampInfo, String> {
+  let time: DateTime<Utc> = match input.map(str::trim).filter(|value| !value.is_empty()) {
+    None => Utc::now(),
+    Some(value) => {
+      if let Ok(number) = value.parse::<i64>() {
+        let millis = if number.abs() >= 100_000_000_000 {
+          number
+        } else {
+          number * 1000
+        };
+        Utc
+          .timestamp_millis_opt(millis)
+          .single()
+          .ok_or_else(|| format!("Timestamp out of range: {value}"))?
+      } else if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        time.with_timezone(&Utc)
+      } else if let Ok(time) = NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S") {
+        Local
+          .from_local_datetime(&time)
+          .single()
+          .ok_or_else(|| format!("Ambiguous local time: {value}"))?
+          .with_timezone(&Utc)
+      } else if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        Local
+          .from_local_datetime(&date.and_hms_opt(0, 0, 0).unwrap_or_default())
+          .single()
+          .ok_or_else(|| format!("Ambiguous local time: {value}"))?
+          .with_timezone(&Utc)
+      } else {
+        return Err(format!("Unrecognized time value: {value}"));
+      }
+    }
+  };
+
+  Ok(TimestampInfo {
+    unix: time.timestamp(),
+    unix_ms: time.timestamp_millis(),
+    iso: time.to_rfc3339(),
+    local: time.with_timezone(&Local).to_rfc3339(),
+  })
+}
+
+/// Base64 编解码
+#[tauri::command]
+pub fn base64_transform(input: String, mode: CodecMode, url_safe: Option<bool>) -> Result<CodecOutput, String> {
+  base64(input.as_bytes(), mode, url_safe.unwrap_or(false))
+}
+
+/// URL 编解码
+#[tauri::command]
+pub fn url_transform(input: String, mode: CodecMode) -> Result<String, String> {
+  url(&input, mode)
+}
+
+/// 生成 UUID（v4 或 v7）
+#[tauri::command]
+pub fn uuid_generate(version: Option<u8>, count: Option<usize>) -> Result<Vec<String>, String> {
+  uuid(version.unwrap_or(4), count.unwrap_or(1))
+}
+
+/// JSON 格式化 / 压缩
+#[tauri::command]
+pub fn json_format_text(input: String, indent: Option<usize>, minify: Option<bool>) -> Result<String, String> {
+  json_format(&input, indent, minify.unwrap_or(false))
+}
+
+/// 时间戳转换
+#[tauri::command]
+pub fn timestamp_convert(input: Option<String>) -> Result<TimestampInfo, String> {
+  timestamp(input.as_deref())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn decode(input: &str, url_safe: bool) -> CodecOutput {
+    base64(input.as_bytes(), CodecMode::Decode, url_safe).unwrap()
+  }
+
+  fn text(value: &str) -> CodecOutput {
+    CodecOutput::Text(value.to_string())
+  }
+
+  #[test]
+  fn formats_json_in_source_order() {
+    let input = r#"{"b":1,"a":{"z":[1,2.5,{}],"y":null},"b":3}"#;
+    // 重复的键保留首次出现的位置与最后一次的值
+    assert_eq!(json_format(input, None, true).unwrap(), r#"{"b":3,"a":{"z":[1,2.5,{}],"y":null}}"#);
+    assert_eq!(
+      json_format(r#"{"z":1,"a":[true]}"#, Some(4), false).unwrap(),
+      "{\n    \"z\": 1,\n    \"a\": [\n        true\n    ]\n}"
+    );
+    assert!(json_format("{", None, false).is_err());
+  }
+
+  #[test]
+  fn decodes_base64_variants() {
+    assert_eq!(base64(b"hi?", CodecMode::Encode, false).unwrap(), text("aGk/"));
+    assert_eq!(base64(b"hi?", CodecMode::Encode, true).unwrap(), text("aGk_"));
+    assert_eq!(decode("aGk=", false), text("hi"));
+    // 标准字母表缺少填充时同样可以解码
+    assert_eq!(decode("aGk", false), text("hi"));
+    assert_eq!(decode("aGk/", false), text("hi?"));
+    // 出现 `-`/`_` 时自动按 URL 安全字母表解码，填充可有可无
+    assert_eq!(decode("aGk_", false), text("hi?"));
+    assert_eq!(decode("aGk_\n", true), text("hi?"));
+    assert_eq!(decode("_w==", false), CodecOutput::Binary { hex: "ff".to_string() });
+    assert_eq!(decode("/w", false), CodecOutput::Binary { hex: "ff".to_string() });
+    assert!(base64(b"a", CodecMode::Decode, false).is_err());
+    assert_eq!(
+      serde_json::to_value(CodecOutput::Binary { hex: "ff".to_string() }).unwrap(),
+      serde_json::json!({ "hex": "ff" })
+    );
+  }
+
+  #[test]
+  fn encodes_urls_like_encode_uri_component() {
+    assert_eq!(url("a b&c/d?e=ü!", CodecMode::Encode).unwrap(), "a%20b%26c%2Fd%3Fe%3D%C3%BC!");
+    assert_eq!(url("a+b%20c%C3%BC", CodecMode::Decode).unwrap(), "a b cü");
+    assert!(url("%FF", CodecMode::Decode).is_err());
+  }
+
+  #[test]
+  fn converts_timestamps() {
+    let seconds = timestamp(Some("1700000000")).unwrap();
+    assert_eq!(seconds.unix_ms, 1_700_000_000_000);
+    assert_eq!(seconds.iso, "2023-11-14T22:13:20+00:00");
+    let millis = timestamp(Some("1700000000123")).unwrap();
+    assert_eq!((millis.unix, millis.unix_ms), (1_700_000_000, 1_700_000_000_123));
+    let rfc = timestamp(Some("2023-11-14T22:13:20Z")).unwrap();
+    assert_eq!(rfc.unix, 1_700_000_000);
+
+    // 不带时区的日期按本地时间解释
+    let expected = Local
+      .with_ymd_and_hms(2024, 1, 2, 3, 4, 5)
+      .single()
+      .unwrap()
+      .timestamp();
+    assert_eq!(timestamp(Some("2024-01-02 03:04:05")).unwrap().unix, expected);
+    let midnight = Local.with_ymd_and_hms(2024, 1, 2, 0, 0, 0).single().unwrap().timestamp();
+    assert_eq!(timestamp(Some(" 2024-01-02 ")).unwrap().unix, midnight);
+    assert!(timestamp(Some("yesterday")).is_err());
+    assert!(timestamp(None).unwrap().unix > 1_700_000_000);
+  }
+
+  #[test]
+  fn generates_uuids() {
+    let ids = uuid(7, 3).unwrap();
+    assert_eq!(ids.len(), 3);
+    assert!(ids.iter().all(|id| id.as_bytes()[14] == b'7'));
+    assert!(uuid(5, 1).is_err());
+    assert!(uuid(4, 0).is_err());
+  }
+}
@@ -0,0 +1,890 @@
+use std::collections::{BTreeMap, HashSet};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+use image::{ColorType, ImageDecoder, ImageFormat, ImageReader};
+use lopdf::content::{Content, Operation};
+use lopdf::{dictionary, Bookmark, Dictionary, Document, Object, ObjectId, Stream, StringFormat};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::task::{TaskHandle, TaskRegistry};
+
+const A4_PT: (f32, f32) = (595.28, 841.89);
+const LETTER_PT: (f32, f32) = (612.0, 792.0);
+/// 图片按 96 DPI 换算为 PDF 点（1/72 英寸）
+const PX_TO_PT: f32 = 72.0 / 96.0;
+/// 读取书签树与解析命名目标时的最大嵌套深度，防止恶意文件造成无限递归
+const MAX_OUTLINE_DEPTH: usize = 32;
+
+#[derive(Serialize, Clone, Debug)]
+pub struct PdfOutput {
+  pub path: String,
+  pub pages: u32,
+}
+
+/// 页码区间，1 起始且包含两端；`end` 为空表示直到最后一页
+#[derive(Deserialize, Clone, Copy, Debug)]
+pub struct PageRange {
+  pub start: u32,
+  pub end: Option<u32>,
+}
+
+#[derive(Deserialize, Clone, Copy, Debug, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum PdfPageSize {
+  /// 页面尺寸与图片一致
+  #[default]
+  Fit,
+  A4,
+  Letter,
+}
+
+#[derive(Deserialize, Clone, Debug, Default)]
+pub struct ImagesToPdfOptions {
+  #[serde(default)]
+  pub page_size: PdfPageSize,
+  #[serde(default)]
+  pub landscape: bool,
+  #[serde(default)]
+  pub margin: f32,
+}
+
+struct SourcePages {
+  document: Document,
+  pages: Vec<u32>,
+  label: String,
+}
+
+fn file_label(path: &str) -> String {
+  Path::new(path)
+    .file_stem()
+    .map(|stem| stem.to_string_lossy().into_owned())
+    .unwrap_or_else(|| path.to_string())
+}
+
+fn load(path: &str) -> Result<Document, String> {
+  let document = Document::load(path).map_err(|e| format!("Failed to load {path}: {e}"))?;
+  if document.is_encrypted() {
+    return Err(format!("{path} is encrypted"));
+  }
+  Ok(document)
+}
+
+fn save(mut document: Document, path: &str) -> Result<PdfOutput, String> {
+  if let Some(parent) = Path::new(path).parent() {
+    std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+  }
+  document.compress();
+  document
+    .save(path)
+    .map_err(|e| format!("Failed to write {path}: {e}"))?;
+  Ok(PdfOutput {
+    path: path.to_string(),
+    pages: document.get_pages().len() as u32,
+  })
+}
+
+fn expand_ranges(ranges: &[PageRange], page_count: u32) -> Result<Vec<u32>, String> {
+  let mut pages = Vec::new();
+  for range in ranges {
+    let end = range.end.unwrap_or(page_count);
+    if range.start == 0 || range.start > end || end > page_count {
+      return Err(format!(
+        "Invalid page range {}-{end} for a document with {page_count} pages",
+        range.start
+      ));
+    }
+    pages.extend(range.start..=end);
+  }
+  Ok(pages)
+}
+
+/// 复制页面字典，并把从父级 Pages 节点继承的属性落到页面上，便于重建页树
+fn inherited_page_dict(document: &Document, page_id: ObjectId) -> Result<Dictionary, String> {
+  let mut dict = document
+    .get_object(page_id)
+    .and_then(Object::as_dict)
+    .map_err(|e| e.to_string())?
+    .clone();
+  let keys: [&[u8]; 4] = [b"Resources", b"MediaBox", b"CropBox", b"Rotate"];
+  let mut parent = dict.get(b"Parent").and_then(Object::as_reference).ok();
+  let mut depth = 0;
+
+  while let Some(parent_id) = parent {
+    depth += 1;
+    if depth > 64 {
+      break;
+    }
+    let Ok(parent_dict) = document.get_object(parent_id).and_then(Object::as_dict) else {
+      break;
+    };
+    for key in keys {
+      if !dict.has(key) {
+        if let Ok(value) = parent_dict.get(key) {
+          dict.set(key.to_vec(), value.clone());
+        }
+      }
+    }
+    parent = parent_dict.get(b"Parent").and_then(Object::as_reference).ok();
+  }
+  Ok(dict)
+}
+
+/// 源文档中的书签节点，`page` 为目标页对象 id
+struct OutlineNode {
+  title: String,
+  page: Option<ObjectId>,
+  children: Vec<OutlineNode>,
+}
+
+/// PDF 文本字符串：带 BOM 的 UTF-16BE / UTF-8，否则按 PDFDocEncoding（近似 Latin-1）
+fn decode_text(bytes: &[u8]) -> String {
+  if let Some(rest) = bytes.strip_prefix(&[0xFE, 0xFF]) {
+    let units: Vec<u16> = rest
+      .chunks_exact(2)
+      .map(|unit| u16::from_be_bytes([unit[0], unit[1]]))
+      .collect();
+    String::from_utf16_lossy(&units)
+  } else if let Some(rest) = bytes.strip_prefix(&[0xEF, 0xBB, 0xBF]) {
+    String::from_utf8_lossy(rest).into_owned()
+  } else {
+    bytes.iter().map(|&byte| byte as char).collect()
+  }
+}
+
+/// 非 ASCII 文本写为带 BOM 的 UTF-16BE
+fn encode_text(text: &str) -> Object {
+  if text.is_ascii() {
+    return Object::string_literal(text);
+  }
+  let mut bytes = vec![0xFE, 0xFF];
+  for unit in text.encode_utf16() {
+    bytes.extend_from_slice(&unit.to_be_bytes());
+  }
+  Object::String(bytes, StringFormat::Hexadecimal)
+}
+
+fn resolve<'a>(document: &'a Document, object: &'a Object) -> Option<&'a Object> {
+  document.dereference(object).ok().map(|(_, object)| object)
+}
+
+/// 在名称树（`/Names /Dests`）中查找命名目标
+fn lookup_name_tree(document: &Document, node: &Dictionary, key: &[u8], depth: usize) -> Option<Object> {
+  if depth > MAX_OUTLINE_DEPTH {
+    return None;
+  }
+  if let Ok(names) = node.get(b"Names").and_then(Object::as_array) {
+    for pair in names.chunks_exact(2) {
+      if resolve(document, &pair[0]).and_then(|name| name.as_str().ok()) == Some(key) {
+        return Some(pair[1].clone());
+      }
+    }
+  }
+  let kids = node.get(b"Kids").and_then(Object::as_array).ok()?;
+  kids.iter().find_map(|kid| {
+    let kid = resolve(document, kid)?.as_dict().ok()?;
+    lookup_name_tree(document, kid, key, depth + 1)
+  })
+}
+
+/// 解析目标所在页：显式目标数组、带 `D` 的字典，或 Catalog 中 `/Dests` 与名称树里的命名目标
+fn destination_page(document: &Document, dest: &Object, depth: usize) -> Option<ObjectId> {
+  if depth > MAX_OUTLINE_DEPTH {
+    return None;
+  }
+  match resolve(document, dest)? {
+    Object::Array(items) => items.first()?.as_reference().ok(),
+    Object::Dictionary(dict) => destination_page(document, dict.get(b"D").ok()?, depth + 1),
+    Object::Name(name) | Object::String(name, _) => {
+      let catalog = document.catalog().ok()?;
+      let target = catalog
+        .get(b"Dests")
+        .ok()
+        .and_then(|dests| resolve(document, dests)?.as_dict().ok())
+        .and_then(|dests| dests.get(name).ok().cloned())
+        .or_else(|| {
+          let names = resolve(document, catalog.get(b"Names").ok()?)?.as_dict().ok()?;
+          let tree = resolve(document, names.get(b"Dests").ok()?)?.as_dict().ok()?;
+          lookup_name_tree(document, tree, name, 0)
+        })?;
+      destination_page(document, &target, depth + 1)
+    }
+    _ => None,
+  }
+}
+
+fn outline_target(document: &Document, item: &Dictionary) -> Option<ObjectId> {
+  if let Ok(dest) = item.get(b"Dest") {
+    return destination_page(document, dest, 0);
+  }
+  let action = resolve(document, item.get(b"A").ok()?)?.as_dict().ok()?;
+  if action.get(b"S").and_then(Object::as_name).ok()? != b"GoTo" {
+    return None;
+  }
+  destination_page(document, action.get(b"D").ok()?, 0)
+}
+
+fn read_outline_items(
+  document: &Document,
+  parent: &Dictionary,
+  visited: &mut HashSet<ObjectId>,
+  depth: usize,
+) -> Vec<OutlineNode> {
+  let mut nodes = Vec::new();
+  if depth > MAX_OUTLINE_DEPTH {
+    return nodes;
+  }
+  let mut next = parent.get(b"First").and_then(Object::as_reference).ok();
+  while let Some(id) = next {
+    if !visited.insert(id) {
+      break;
+    }
+    let Ok(item) = document.get_dictionary(id) else {
+      break;
+    };
+    nodes.push(OutlineNode {
+      title: item
+        .get(b"Title")
+        .ok()
+        .and_then(|title| resolve(document, title)?.as_str().ok())
+        .map(decode_text)
+        .unwrap_or_default(),
+      page: outline_target(document, item),
+      children: read_outline_items(document, item, visited, depth + 1),
+    });
+    next = item.get(b"Next").and_then(Object::as_reference).ok();
+  }
+  nodes
+}
+
+/// 读取文档原有的书签树
+fn read_outline(document: &Document) -> Vec<OutlineNode> {
+  let root = document
+    .catalog()
+    .ok()
+    .and_then(|catalog| catalog.get(b"Outlines").ok())
+    .and_then(|outlines| resolve(document, outlines)?.as_dict().ok());
+  match root {
+    Some(root) => read_outline_items(document, root, &mut HashSet::new(), 0),
+    None => Vec::new(),
+  }
+}
+
+/// 把源书签写入输出文档；目标页未被保留的书签省略，其子书签上移一级
+fn add_outline(output: &mut Document, nodes: &[OutlineNode], pages: &HashSet<ObjectId>, parent: Option<u32>) {
+  for node in nodes {
+    match node.page.filter(|page| pages.contains(page)) {
+      Some(page) => {
+        let id = output.add_bookmark(Bookmark::new(node.title.clone(), [0.0, 0.0, 0.0], 0, page), parent);
+        add_outline(output, &node.children, pages, Some(id));
+      }
+      None => add_outline(output, &node.children, pages, parent),
+    }
+  }
+}
+
+/// 按给定顺序把多个文档的指定页面组装成新文档，保留目标页仍在的原有书签；
+/// `file_bookmarks` 为真时为每个来源生成一个书签，原有书签嵌套在其下。同一页可被重复选择
+fn assemble(
+  sources: Vec<SourcePages>,
+  file_bookmarks: bool,
+  task: &mut TaskHandle,
+) -> Result<Document, String> {
+  let mut output = Document::with_version("1.5");
+  let mut max_id = 1;
+  let mut catalog: Option<(ObjectId, Dictionary)> = None;
+  let mut pages_id: Option<ObjectId> = None;
+  let mut page_dicts: Vec<(ObjectId, Dictionary)> = Vec::new();
+  let total = sources.len() as u64;
+
+  for (index, source) in sources.into_iter().enumerate() {
+    if task.is_cancelled() {
+      return Err("Cancelled".to_string());
+    }
+    task.progress_ratio("assembling", index as u64, total);
+
+    let mut document = source.document;
+    document.renumber_objects_with(max_id);
+    max_id = document.max_id + 1;
+
+    let page_ids = document.get_pages();
+    let outline = read_outline(&document);
+    let mut kept = HashSet::new();
+    let mut first_page = None;
+    for number in &source.pages {
+      let source_id = *page_ids
+        .get(number)
+        .ok_or_else(|| format!("Page {number} does not exist in {}", source.label))?;
+      // 重复选择的页面复制为新的页面对象，书签指向第一次出现的位置
+      let id = if kept.insert(source_id) {
+        source_id
+      } else {
+        max_id += 1;
+        (max_id - 1, 0)
+      };
+      first_page.get_or_insert(id);
+      page_dicts.push((id, inherited_page_dict(&document, source_id)?));
+    }
+    let parent = match first_page {
+      Some(page) if file_bookmarks => Some(output.add_bookmark(
+        Bookmark::new(source.label.clone(), [0.0, 0.0, 0.0], 0, page),
+        None,
+      )),
+      _ => None,
+    };
+    add_outline(&mut output, &outline, &kept, parent);
+
+    for (id, object) in document.objects {
+      let type_name = object.type_name().map(|name| name.as_bytes().to_vec()).unwrap_or_default();
+      match type_name.as_slice() {
+        b"Catalog" => {
+          if catalog.is_none() {
+            catalog = Some((id, object.as_dict().map_err(|e| e.to_string())?.clone()));
+          }
+        }
+        b"Pages" => {
+          pages_id.get_or_insert(id);
+        }
+        // 页面由上面的 page_dicts 重新写入，书签已读出，由 build_outline 重建
+        b"Page" | b"Outlines" => {}
+        _ => {
+          output.objects.insert(id, object);
+        }
+      }
+    }
+  }
+
+  let (catalog_id, mut catalog_dict) = catalog.ok_or("Source document has no catalog")?;
+  let pages_id = pages_id.ok_or("Source document has no page tree")?;
+  let mut kids = Vec::with_capacity(page_dicts.len());
+  for (id, mut dict) in page_dicts {
+    dict.set("Parent", pages_id);
+    kids.push(Object::Reference(id));
+    output.objects.insert(id, Object::Dictionary(dict));
+  }
+  let count = kids.len() as i64;
+  output.objects.insert(
+    pages_id,
+    Object::Dictionary(dictionary! {
+      "Type" => "Pages",
+      "Kids" => kids,
+      "Count" => count,
+    }),
+  );
+
+  catalog_dict.set("Pages", pages_id);
+  catalog_dict.remove(b"Outlines");
+  catalog_dict.remove(b"PageLabels");
+  output.max_id = max_id;
+  let first_outline_id = max_id + 1;
+  if let Some(outline_id) = output.build_outline() {
+    // build_outline 按 UTF-8 字节写入标题，改为 PDF 文本字符串编码
+    for id in first_outline_id..=output.max_id {
+      let Ok(dict) = output.get_dictionary_mut((id, 0)) else {
+        continue;
+      };
+      let title = match dict.get(b"Title") {
+        Ok(Object::String(bytes, _)) => Some(String::from_utf8_lossy(bytes).into_owned()),
+        _ => None,
+      };
+      if let Some(title) = title {
+        dict.set("Title", encode_text(&title));
+      }
+    }
+    catalog_dict.set("Outlines", outline_id);
+  }
+  output.objects.insert(catalog_id, Object::Dictionary(catalog_dict));
+  output.trailer.set("Root", catalog_id);
+
+  output.prune_objects();
+  output.renumber_objects();
+  Ok(output)
+}
+
+/// 计算拆分分组：优先按区间，其次按固定页数，否则每页一个文件
+fn split_groups(
+  ranges: Option<Vec<PageRange>>,
+  every: Option<u32>,
+  page_count: u32,
+) -> Result<Vec<Vec<u32>>, String> {
+  Ok(match (ranges, every) {
+    (Some(ranges), _) => ranges
+      .iter()
+      .map(|range| expand_ranges(std::slice::from_ref(range), page_count))
+      .collect::<Result<_, _>>()?,
+    (None, Some(size)) if size > 0 => (1..=page_count)
+      .collect::<Vec<_>>()
+      .chunks(size as usize)
+      .map(|chunk| chunk.to_vec())
+      .collect(),
+    _ => (1..=page_count).map(|page| vec![page]).collect(),
+  })
+}
+
+/// 在原有旋转角度上叠加 `angle`，重复的页码只处理一次
+fn rotate_pages(
+  document: &mut Document,
+  angle: i64,
+  pages: Option<Vec<u32>>,
+  task: &mut TaskHandle,
+) -> Result<(), String> {
+  let page_ids: BTreeMap<u32, ObjectId> = document.get_pages();
+  let mut seen = HashSet::new();
+  let selected: Vec<u32> = pages
+    .unwrap_or_else(|| page_ids.keys().copied().collect())
+    .into_iter()
+    .filter(|number| seen.insert(*number))
+    .collect();
+
+  for (index, number) in selected.iter().enumerate() {
+    task.progress_ratio("rotating", index as u64, selected.len() as u64);
+    let id = *page_ids
+      .get(number)
+      .ok_or_else(|| format!("Page {number} does not exist"))?;
+    let current = inherited_page_dict(document, id)?
+      .get(b"Rotate")
+      .and_then(Object::as_i64)
+      .unwrap_or(0);
+    let dict = document
+      .get_object_mut(id)
+      .and_then(Object::as_dict_mut)
+      .map_err(|e| e.to_string())?;
+    dict.set("Rotate", (current + angle).rem_euclid(360));
+  }
+  Ok(())
+}
+
+fn run_task<T: Send + 'static>(
+  tasks: &TaskRegistry,
+  task_id: Option<String>,
+  kind: &str,
+  job: impl FnOnce(&mut TaskHandle) -> Result<T, String> + Send + 'static,
+) -> Result<tauri::async_runtime::JoinHandle<Result<T, String>>, String> {
+  let mut task = tasks.start(task_id, kind)?;
+  Ok(tauri::async_runtime::spawn_blocking(move || {
+    let result = job(&mut task);
+    task.settle(&result);
+    result
+  }))
+}
+
+/// 按顺序合并多个 PDF，每个来源文件生成一个书签
+#[tauri::command]
+pub async fn pdf_merge(
+  tasks: State<'_, TaskRegistry>,
+  task_id: Option<String>,
+  inputs: Vec<String>,
+  output: String,
+) -> Result<PdfOutput, String> {
+  if inputs.is_empty() {
+    return Err("No input files".to_string());
+  }
+  run_task(&tasks, task_id, "pdf_merge", move |task| {
+    let mut sources = Vec::with_capacity(inputs.len());
+    for (index, path) in inputs.iter().enumerate() {
+      task.progress_ratio("loading", index as u64, inputs.len() as u64);
+      let document = load(path)?;
+      let pages = document.get_pages().keys().copied().collect();
+      sources.push(SourcePages {
+        document,
+        pages,
+        label: file_label(path),
+      });
+    }
+    let document = assemble(sources, true, task)?;
+    task.progress("saving", None);
+    save(document, &output)
+  })?
+  .await
+  .map_err(|e| e.to_string())?
+}
+
+/// 按区间拆分 PDF，每个区间输出一个文件到 `output_dir`
+#[tauri::command]
+pub async fn pdf_split(
+  tasks: State<'_, TaskRegistry>,
+  task_id: Option<String>,
+  input: String,
+  output_dir: String,
+  ranges: Option<Vec<PageRange>>,
+  every: Option<u32>,
+) -> Result<Vec<PdfOutput>, String> {
+  run_task(&tasks, task_id, "pdf_split", move |task| {
+    task.progress("loading", None);
+    let document = load(&input)?;
+    let groups = split_groups(ranges, every, document.get_pages().len() as u32)?;
+
+    let label = file_label(&input);
+    let mut outputs = Vec::with_capacity(groups.len());
+    for (index, pages) in groups.into_iter().enumerate() {
+      if task.is_cancelled() {
+        return Err("Cancelled".to_string());
+      }
+      let source = SourcePages {
+        document: document.clone(),
+        pages,
+        label: label.clone(),
+      };
+      let part = assemble(vec![source], false, task)?;
+      let path: PathBuf = Path::new(&output_dir).join(format!("{label}-{}.pdf", index + 1));
+      task.progress("saving", None);
+      outputs.push(save(part, &path.to_string_lossy())?);
+    }
+    Ok(outputs)
+  })?
+  .await
+  .map_err(|e| e.to_string())?
+}
+
+/// 按给定顺序提取页面，可用于重新排序；同一页可重复出现
+#[tauri::command]
+pub async fn pdf_extract_pages(
+  tasks: State<'_, TaskRegistry>,
+  task_id: Option<String>,
+  input: String,
+  output: String,
+  pages: Vec<u32>,
+) -> Result<PdfOutput, String> {
+  if pages.is_empty() {
+    return Err("No pages selected".to_string());
+  }
+  run_task(&tasks, task_id, "pdf_extract_pages", move |task| {
+    task.progress("loading", None);
+    let document = load(&input)?;
+    let source = SourcePages {
+      document,
+      pages,
+      label: file_label(&input),
+    };
+    let document = assemble(vec![source], false, task)?;
+    task.progress("saving", None);
+    save(document, &output)
+  })?
+  .await
+  .map_err(|e| e.to_string())?
+}
+
+/// 旋转页面（90 的倍数），`pages` 为空时旋转全部页面，重复的页码只旋转一次；原地修改页面属性以保留书签
+#[tauri::command]
+pub async fn pdf_rotate(
+  tasks: State<'_, TaskRegistry>,
+  task_id: Option<String>,
+  input: String,
+  output: String,
+  angle: i64,
+  pages: Option<Vec<u32>>,
+) -> Result<PdfOutput, String> {
+  if angle % 90 != 0 {
+    return Err("Rotation angle must be a multiple of 90".to_string());
+  }
+  run_task(&tasks, task_id, "pdf_rotate", move |task| {
+    task.progress("loading", None);
+    let mut document = load(&input)?;
+    rotate_pages(&mut document, angle, pages, task)?;
+    task.progress("saving", None);
+    save(document, &output)
+  })?
+  .await
+  .map_err(|e| e.to_string())?
+}
+
+fn zlib(data: &[u8]) -> Result<Vec<u8>, String> {
+  let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+  encoder.write_all(data).map_err(|e| e.to_string())?;
+  encoder.finish().map_err(|e| e.to_string())
+}
+
+/// 生成图片 XObject：RGB/灰度 JPEG 直接嵌入，其余格式解码后以 Flate 压缩
+fn image_xobject(path: &str) -> Result<(Stream, u32, u32), String> {
+  let reader = ImageReader::open(path)
+    .and_then(|reader| reader.with_guessed_format())
+    .map_err(|e| format!("Failed to open {path}: {e}"))?;
+
+  if reader.format() == Some(ImageFormat::Jpeg) {
+    let decoder = ImageReader::open(path)
+      .and_then(|reader| reader.with_guessed_format())
+      .map_err(|e| e.to_string())?
+      .into_decoder()
+      .map_err(|e| e.to_string())?;
+    let (width, height) = decoder.dimensions();
+    let color_space = match decoder.color_type() {
+      ColorType::L8 => Some("DeviceGray"),
+      ColorType::Rgb8 => Some("DeviceRGB"),
+      _ => None,
+    };
+    if let Some(color_space) = color_space {
+      let data = std::fs::read(path).map_err(|e| e.to_string())?;
+      let dict = dictionary! {
+        "Type" => "XObject",
+        "Subtype" => "Image",
+        "Width" => width as i64,
+        "Height" => height as i64,
+        "ColorSpace" => color_space,
+        "BitsPerComponent" => 8,
+        "Filter" => "DCTDecode",
+      };
+      return Ok((Stream::new(dict, data), width, height));
+    }
+  }
+
+  let image = reader
+    .decode()
+    .map_err(|e| format!("Failed to decode {path}: {e}"))?
+    .to_rgb8();
+  let (width, height) = image.dimensions();
+  let dict = dictionary! {
+    "Type" => "XObject",
+    "Subtype" => "Image",
+    "Width" => width as i64,
+    "Height" => height as i64,
+    "ColorSpace" => "DeviceRGB",
+    "BitsPerComponent" => 8,
+    "Filter" => "FlateDecode",
+  };
+  Ok((Stream::new(dict, zlib(image.as_raw())?), width, height))
+}
+
+/// 把多张图片按顺序生成 PDF，每张图片一页
+#[tauri::command]
+pub async fn images_to_pdf(
+  tasks: State<'_, TaskRegistry>,
+  task_id: Option<String>,
+  inputs: Vec<String>,
+  output: String,
+  options: Option<ImagesToPdfOptions>,
+) -> Result<PdfOutput, String> {
+  if inputs.is_empty() {
+    return Err("No input files".to_string());
+  }
+  let options = options.unwrap_or_default();
+  run_task(&tasks, task_id, "images_to_pdf", move |task| {
+    let mut document = Document::with_version("1.5");
+    let pages_id = document.new_object_id();
+    let mut kids = Vec::with_capacity(inputs.len());
+
+    for (index, path) in inputs.iter().enumerate() {
+      if task.is_cancelled() {
+        return Err("Cancelled".to_string());
+      }
+      task.progress_ratio("embedding", index as u64, inputs.len() as u64);
+
+      let (stream, width, height) = image_xobject(path)?;
+      let image_width = width as f32 * PX_TO_PT;
+      let image_height = height as f32 * PX_TO_PT;
+      let margin = options.margin.max(0.0);
+      let (page_width, page_height) = match options.page_size {
+        PdfPageSize::Fit => (image_width + margin * 2.0, image_height + margin * 2.0),
+        PdfPageSize::A4 => A4_PT,
+        PdfPageSize::Letter => LETTER_PT,
+      };
+      let (page_width, page_height) = match options.page_size {
+        PdfPageSize::Fit => (page_width, page_height),
+        _ if options.landscape => (page_height, page_width),
+        _ => (page_width, page_height),
+      };
+      // 在可用区域内等比缩放并居中
+      let available_width = (page_width - margin * 2.0).max(1.0);
+      let available_height = (page_height - margin * 2.0).max(1.0);
+      let scale = (available_width / image_width)
+        .min(available_height / image_height)
+        .min(1.0);
+      let draw_width = image_width * scale;
+      let draw_height = image_height * scale;
+      let x = (page_width - draw_width) / 2.0;
+      let y = (page_height - draw_height) / 2.0;
+
+      let image_id = document.add_object(stream);
+      let content = Content {
+        operations: vec![
+          Operation::new("q", vec![]),
+          Operation::new(
+            "cm",
+            vec![
+              draw_width.into(),
+              0.into(),
+              0.into(),
+              draw_height.into(),
+              x.into(),
+              y.into(),
+            ],
+          ),
+          Operation::new("Do", vec!["Im0".into()]),
+          Operation::new("Q", vec![]),
+        ],
+      };
+      let content_id = document.add_object(Stream::new(
+        dictionary! {},
+        content.encode().map_err(|e| e.to_string())?,
+      ));
+      let page_id = document.add_object(dictionary! {
+        "Type" => "Page",
+        "Parent" => pages_id,
+        "MediaBox" => vec![0.into(), 0.into(), page_width.into(), page_height.into()],
+        "Contents" => content_id,
+        "Resources" => dictionary! {
+          "XObject" => dictionary! { "Im0" => image_id },
+        },
+      });
+      kids.push(Object::Reference(page_id));
+    }
+
+    let count = kids.len() as i64;
+    document.objects.insert(
+      pages_id,
+      Object::Dictionary(dictionary! {
+        "Type" => "Pages",
+        "Kids" => kids,
+        "Count" => count,
+      }),
+    );
+    let catalog_id = document.add_object(dictionary! {
+      "Type" => "Catalog",
+      "Pages" => pages_id,
+    });
+    document.trailer.set("Root", catalog_id);
+
+    task.progress("saving", None);
+    save(document, &output)
+  })?
+  .await
+  .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::task::{TaskEmitter, TaskInfo};
+  use std::sync::Arc;
+
+  struct Silent;
+
+  impl TaskEmitter for Silent {
+    fn emit_progress(&self, _: &TaskInfo) {}
+  }
+
+  fn task() -> TaskHandle {
+    TaskRegistry::new(Arc::new(Silent)).start(None, "test").unwrap()
+  }
+
+  /// 生成 `count` 页的文档，页树根节点带有可继承的 `Rotate`
+  fn document(count: i64, inherited_rotate: i64) -> Document {
+    let mut document = Document::with_version("1.5");
+    let pages_id = document.new_object_id();
+    let kids: Vec<Object> = (0..count)
+      .map(|index| {
+        Object::Reference(document.add_object(dictionary! {
+          "Type" => "Page",
+          "Parent" => pages_id,
+          "MediaBox" => vec![0.into(), 0.into(), (100 + index).into(), 100.into()],
+        }))
+      })
+      .collect();
+    document.objects.insert(
+      pages_id,
+      Object::Dictionary(dictionary! {
+        "Type" => "Pages",
+        "Kids" => kids,
+        "Count" => count,
+        "Rotate" => inherited_rotate,
+      }),
+    );
+    let catalog_id = document.add_object(dictionary! {
+      "Type" => "Catalog",
+      "Pages" => pages_id,
+    });
+    document.trailer.set("Root", catalog_id);
+    document
+  }
+
+  fn rotations(document: &Document) -> Vec<i64> {
+    document
+      .get_pages()
+      .values()
+      .map(|id| {
+        document
+          .get_dictionary(*id)
+          .unwrap()
+          .get(b"Rotate")
+          .and_then(Object::as_i64)
+          .unwrap_or(0)
+      })
+      .collect()
+  }
+
+  fn widths(document: &Document) -> Vec<i64> {
+    document
+      .get_pages()
+      .values()
+      .map(|id| {
+        let dict = inherited_page_dict(document, *id).unwrap();
+        dict.get(b"MediaBox").unwrap().as_array().unwrap()[2].as_i64().unwrap()
+      })
+      .collect()
+  }
+
+  fn range(start: u32, end: Option<u32>) -> PageRange {
+    PageRange { start, end }
+  }
+
+  #[test]
+  fn expands_page_ranges() {
+    assert_eq!(
+      expand_ranges(&[range(2, Some(3)), range(5, None)], 6).unwrap(),
+      vec![2, 3, 5, 6]
+    );
+    assert_eq!(expand_ranges(&[range(4, Some(4))], 4).unwrap(), vec![4]);
+    for invalid in [range(0, Some(2)), range(3, Some(2)), range(1, Some(7)), range(7, None)] {
+      assert!(expand_ranges(&[invalid], 6).is_err());
+    }
+  }
+
+  #[test]
+  fn split_groups_prefer_ranges_then_chunks() {
+    assert_eq!(
+      split_groups(Some(vec![range(1, Some(2)), range(4, None)]), Some(1), 5).unwrap(),
+      vec![vec![1, 2], vec![4, 5]]
+    );
+    assert_eq!(
+      split_groups(None, Some(2), 5).unwrap(),
+      vec![vec![1, 2], vec![3, 4], vec![5]]
+    );
+    assert_eq!(
+      split_groups(None, Some(0), 3).unwrap(),
+      vec![vec![1], vec![2], vec![3]]
+    );
+    assert!(split_groups(Some(vec![range(2, Some(9))]), None, 5).is_err());
+  }
+
+  #[test]
+  fn rotate_applies_each_page_once() {
+    let mut doc = document(3, 90);
+    rotate_pages(&mut doc, 90, Some(vec![1, 1, 3, 1]), &mut task()).unwrap();
+    // 继承的 90° 叠加一次，第 2 页不写入自身属性
+    assert_eq!(rotations(&doc), vec![180, 0, 180]);
+
+    rotate_pages(&mut doc, -90, None, &mut task()).unwrap();
+    assert_eq!(rotations(&doc), vec![90, 0, 90]);
+    assert!(rotate_pages(&mut doc, 90, Some(vec![4]), &mut task()).is_err());
+  }
+
+  #[test]
+  fn assemble_keeps_order_and_duplicates() {
+    let source = SourcePages {
+      document: document(3, 0),
+      pages: vec![3, 1, 3],
+      label: "source".to_string(),
+    };
+    let output = assemble(vec![source], false, &mut task()).unwrap();
+    assert_eq!(widths(&output), vec![102, 100, 102]);
+
+    let missing = SourcePages {
+      document: document(2, 0),
+      pages: vec![3],
+      label: "source".to_string(),
+    };
+    assert!(assemble(vec![missing], false, &mut task()).is_err());
+  }
+}
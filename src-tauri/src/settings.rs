@@ -0,0 +1,460 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use tauri::{AppHandle, Emitter, Manager, Runtime, State, WebviewWindow};
+
+use crate::storage;
+use crate::updater::UpdaterSettings;
+
+const SETTINGS_FILE: &str = "settings.json";
+/// 当前设置结构版本，结构变化时递增并在 `MIGRATIONS` 末尾追加迁移函数
+pub const SETTINGS_VERSION: u32 = 1;
+
+/// `MIGRATIONS[n]` 把版本 n 的数据迁移到版本 n + 1
+const MIGRATIONS: &[fn(&mut Value)] = &[migrate_v0];
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Theme {
+  Light,
+  Dark,
+  #[default]
+  System,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+  #[default]
+  Zh,
+  En,
+}
+
+/// 用户偏好，对应前端的 `UserPreferences`
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(default)]
+pub struct Preferences {
+  pub theme: Theme,
+  pub language: Language,
+  pub auto_save: bool,
+  pub history_limit: u32,
+  pub show_tips: bool,
+  pub compact_mode: bool,
+  pub notifications: bool,
+  /// 关闭主窗口时隐藏到托盘而不是退出
+  pub close_to_tray: bool,
+}
+
+impl Default for Preferences {
+  fn default() -> Self {
+    Self {
+      theme: Theme::System,
+      language: Language::Zh,
+      auto_save: true,
+      history_limit: 100,
+      show_tips: true,
+      compact_mode: false,
+      notifications: true,
+      close_to_tray: false,
+    }
+  }
+}
+
+/// 单个工具的配置，对应前端的 `ToolConfig`
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+#[serde(default)]
+pub struct ToolConfig {
+  pub settings: Map<String, Value>,
+  pub last_modified: i64,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(default)]
+pub struct Settings {
+  pub version: u32,
+  pub preferences: Preferences,
+  pub tool_configs: BTreeMap<String, ToolConfig>,
+  /// 收藏的工具 slug，按收藏顺序
+  pub favorites: Vec<String>,
+  /// 最近使用的工具 slug，最近的在前
+  pub recent_tools: Vec<String>,
+  pub custom_categories: Vec<Value>,
+  pub updater: UpdaterSettings,
+}
+
+impl Default for Settings {
+  fn default() -> Self {
+    Self {
+      version: SETTINGS_VERSION,
+      preferences: Preferences::default(),
+      tool_configs: BTreeMap::new(),
+      favorites: Vec::new(),
+      recent_tools: Vec::new(),
+      custom_categories: Vec::new(),
+      updater: UpdaterSettings::default(),
+    }
+  }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct SettingsChange {
+  /// 变化的键路径（如 `preferences.theme`），整体替换时为空
+  pub key: Option<String>,
+  pub value: Value,
+  /// 发起修改的窗口 label，由 Rust 内部修改时为空
+  pub source: Option<String>,
+}
+
+pub struct SettingsStore {
+  path: PathBuf,
+  settings: Mutex<Settings>,
+  /// 各窗口订阅的键路径前缀，空列表表示订阅全部
+  watchers: Mutex<HashMap<String, Vec<String>>>,
+  /// 启动时没有设置文件，允许前端导入一次 localStorage 中的旧数据
+  legacy_import: AtomicBool,
+}
+
+impl SettingsStore {
+  pub(crate) fn new(path: PathBuf, settings: Settings, legacy_import: bool) -> Self {
+    Self {
+      path,
+      settings: Mutex::new(settings),
+      watchers: Mutex::new(HashMap::new()),
+      legacy_import: AtomicBool::new(legacy_import),
+    }
+  }
+
+  pub fn get(&self) -> Settings {
+    self
+      .settings
+      .lock()
+      .map(|settings| settings.clone())
+      .unwrap_or_default()
+  }
+}
+
+/// 版本 0 是前端 localStorage 中的旧格式：驼峰命名的偏好、以对象保存的收藏工具
+fn migrate_v0(value: &mut Value) {
+  let Some(root) = value.as_object_mut() else {
+    return;
+  };
+  let rename = |object: &mut Map<String, Value>, from: &str, to: &str| {
+    if let Some(item) = object.remove(from) {
+      object.insert(to.to_string(), item);
+    }
+  };
+
+  if let Some(preferences) = root.get_mut("preferences").and_then(Value::as_object_mut) {
+    rename(preferences, "autoSave", "auto_save");
+    rename(preferences, "historyLimit", "history_limit");
+    rename(preferences, "showTips", "show_tips");
+    rename(preferences, "compactMode", "compact_mode");
+    preferences.remove("lastModified");
+  }
+  rename(root, "recentTools", "recent_tools");
+  rename(root, "customCategories", "custom_categories");
+
+  // 旧版工具配置是 `{ toolSlug, settings, lastModified }` 数组
+  if let Some(Value::Array(configs)) = root.remove("configs") {
+    let configs: Map<String, Value> = configs
+      .into_iter()
+      .filter_map(|config| {
+        let slug = config.get("toolSlug")?.as_str()?.to_string();
+        Some((
+          slug,
+          serde_json::json!({
+            "settings": config.get("settings").cloned().unwrap_or_default(),
+            "last_modified": config.get("lastModified").cloned().unwrap_or_default(),
+          }),
+        ))
+      })
+      .collect();
+    root.insert("tool_configs".to_string(), Value::Object(configs));
+  }
+
+  // 收藏与最近使用从完整的工具对象改为 slug
+  for key in ["favorites", "recent_tools"] {
+    if let Some(Value::Array(items)) = root.get_mut(key) {
+      let slugs = items
+        .iter()
+        .filter_map(|item| match item {
+          Value::String(slug) => Some(Value::String(slug.clone())),
+          other => other.get("slug").cloned(),
+        })
+        .collect();
+      *items = slugs;
+    }
+  }
+}
+
+/// 按版本号依次执行迁移；返回是否发生了迁移
+pub(crate) fn migrate(value: &mut Value) -> Result<bool, String> {
+  let version = value.get("version").and_then(Value::as_u64).unwrap_or(0) as u32;
+  if version > SETTINGS_VERSION {
+    return Err(format!(
+      "Settings file version {version} is newer than supported version {SETTINGS_VERSION}"
+    ));
+  }
+  for migration in &MIGRATIONS[version as usize..] {
+    migration(value);
+  }
+  if let Some(root) = value.as_object_mut() {
+    root.insert("version".to_string(), Value::from(SETTINGS_VERSION));
+  }
+  Ok(version < SETTINGS_VERSION)
+}
+
+fn load(path: &std::path::Path) -> Result<Settings, String> {
+  let Some(mut value) = storage::read_json::<Value>(path)? else {
+    return Ok(Settings::default());
+  };
+  if migrate(&mut value)? {
+    // 迁移前保留一份旧文件，便于回退
+    let _ = std::fs::copy(path, path.with_extension("json.bak"));
+    let settings: Settings =
+      serde_json::from_value(value).map_err(|e| format!("Invalid settings after migration: {e}"))?;
+    storage::write_json(path, &settings)?;
+    return Ok(settings);
+  }
+  serde_json::from_value(value).map_err(|e| format!("Invalid settings file: {e}"))
+}
+
+/// 读取（必要时迁移）设置文件并注册到应用状态
+pub fn init<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+  let path = storage::config_file(app, SETTINGS_FILE)?;
+  let legacy_import = !path.exists();
+  let settings = match load(&path) {
+    Ok(settings) => settings,
+    Err(error) if path.exists() => {
+      // 无法解析或版本过新时不覆盖原文件，改名保留后使用默认值
+      let _ = std::fs::rename(&path, path.with_extension("json.invalid"));
+      log::warn!("Discarding unreadable settings file: {error}");
+      Settings::default()
+    }
+    Err(error) => return Err(error),
+  };
+  app.manage(SettingsStore::new(path, settings, legacy_import));
+  Ok(())
+}
+
+fn split_key(key: &str) -> Result<Vec<&str>, String> {
+  let parts: Vec<&str> = key.split('.').collect();
+  if parts.iter().any(|part| part.is_empty()) {
+    return Err(format!("Invalid settings key: {key}"));
+  }
+  if parts[0] == "version" {
+    return Err("Settings version is read-only".to_string());
+  }
+  Ok(parts)
+}
+
+fn get_path<'a>(value: &'a Value, parts: &[&str]) -> Option<&'a Value> {
+  parts.iter().try_fold(value, |current, part| current.get(part))
+}
+
+fn set_path(value: &mut Value, parts: &[&str], new_value: Value) -> Result<(), String> {
+  let (last, parents) = parts.split_last().ok_or("Empty settings key")?;
+  let mut current = value;
+  for part in parents {
+    let object = current
+      .as_object_mut()
+      .ok_or_else(|| format!("Settings key segment '{part}' is not an object"))?;
+    current = object
+      .entry(part.to_string())
+      .or_insert_with(|| Value::Object(Map::new()));
+  }
+  current
+    .as_object_mut()
+    .ok_or_else(|| format!("Settings key segment '{last}' is not an object"))?
+    .insert(last.to_string(), new_value);
+  Ok(())
+}
+
+fn matches_watch(prefixes: &[String], key: Option<&str>) -> bool {
+  let Some(key) = key else {
+    return true;
+  };
+  prefixes.is_empty()
+    || prefixes.iter().any(|prefix| {
+      key == prefix
+        || key.starts_with(&format!("{prefix}."))
+        || prefix.starts_with(&format!("{key}."))
+    })
+}
+
+/// 广播设置变化：调用过 `settings_watch` 的窗口按订阅的键路径过滤，其余窗口收到全部变化
+fn notify<R: Runtime>(app: &AppHandle<R>, store: &SettingsStore, change: SettingsChange) {
+  let Ok(mut watchers) = store.watchers.lock() else {
+    return;
+  };
+  let windows = app.webview_windows();
+  // 已关闭的窗口顺带清理掉
+  watchers.retain(|label, _| windows.contains_key(label));
+  for label in windows.keys() {
+    let matched = watchers
+      .get(label)
+      .is_none_or(|prefixes| matches_watch(prefixes, change.key.as_deref()));
+    if matched {
+      let _ = app.emit_to(label.as_str(), "settings:changed", change.clone());
+    }
+  }
+}
+
+/// 在已持有的锁内把修改写盘并替换内存中的设置；没有变化时返回 `None`
+fn commit(
+  store: &SettingsStore,
+  settings: &mut Settings,
+  key: Option<&str>,
+  value: &Value,
+) -> Result<Option<Settings>, String> {
+  let mut next = match key {
+    None => value.clone(),
+    Some(key) => {
+      let parts = split_key(key)?;
+      let mut next = serde_json::to_value(&*settings).map_err(|e| e.to_string())?;
+      set_path(&mut next, &parts, value.clone())?;
+      next
+    }
+  };
+  if let Some(root) = next.as_object_mut() {
+    root.insert("version".to_string(), Value::from(SETTINGS_VERSION));
+  }
+  let updated: Settings = serde_json::from_value(next).map_err(|e| format!("Invalid settings value: {e}"))?;
+  if updated == *settings {
+    return Ok(None);
+  }
+  storage::write_json(&store.path, &updated)?;
+  *settings = updated.clone();
+  Ok(Some(updated))
+}
+
+/// 修改设置：`key` 为空时整体替换，否则按点分路径写入；校验通过后原子写盘并通知订阅的窗口
+pub fn set<R: Runtime>(
+  app: &AppHandle<R>,
+  key: Option<&str>,
+  value: Value,
+  source: Option<String>,
+) -> Result<Settings, String> {
+  let store = app.state::<SettingsStore>();
+  let updated = {
+    let mut settings = store.settings.lock().map_err(|e| e.to_string())?;
+    match commit(&store, &mut settings, key, &value)? {
+      Some(updated) => updated,
+      None => return Ok(settings.clone()),
+    }
+  };
+
+  notify(
+    app,
+    &store,
+    SettingsChange {
+      key: key.map(str::to_string),
+      value,
+      source,
+    },
+  );
+  Ok(updated)
+}
+
+/// 在 Rust 侧以类型化方式修改设置；读取、修改与写盘在同一把锁内完成，并发修改不会互相覆盖
+pub fn update<R: Runtime>(app: &AppHandle<R>, key: &str, modify: impl FnOnce(&mut Settings)) -> Result<Settings, String> {
+  let parts = split_key(key)?;
+  let store = app.state::<SettingsStore>();
+  let (updated, value) = {
+    let mut settings = store.settings.lock().map_err(|e| e.to_string())?;
+    let mut next = settings.clone();
+    modify(&mut next);
+    let value = serde_json::to_value(&next).map_err(|e| e.to_string())?;
+    let value = get_path(&value, &parts).cloned().unwrap_or_default();
+    match commit(&store, &mut settings, Some(key), &value)? {
+      Some(updated) => (updated, value),
+      None => return Ok(settings.clone()),
+    }
+  };
+
+  notify(
+    app,
+    &store,
+    SettingsChange {
+      key: Some(key.to_string()),
+      value,
+      source: None,
+    },
+  );
+  Ok(updated)
+}
+
+/// 读取设置，`key` 为点分路径（如 `preferences.theme`），为空时返回全部
+#[tauri::command]
+pub fn settings_get(store: State<'_, SettingsStore>, key: Option<String>) -> Result<Value, String> {
+  let value = serde_json::to_value(store.get()).map_err(|e| e.to_string())?;
+  match key {
+    None => Ok(value),
+    Some(key) => {
+      let parts: Vec<&str> = key.split('.').collect();
+      Ok(get_path(&value, &parts).cloned().unwrap_or(Value::Null))
+    }
+  }
+}
+
+/// 写入设置并广播 `settings:changed`
+#[tauri::command]
+pub fn settings_set(
+  app_handle: AppHandle,
+  window: WebviewWindow,
+  key: Option<String>,
+  value: Value,
+) -> Result<Settings, String> {
+  set(&app_handle, key.as_deref(), value, Some(window.label().to_string()))
+}
+
+/// 订阅设置变化：调用方窗口此后只收到匹配键路径的 `settings:changed` 事件（未订阅的窗口收到全部变化），
+/// 重复调用会替换之前的订阅；返回当前设置作为初始值
+#[tauri::command]
+pub fn settings_watch(
+  window: WebviewWindow,
+  store: State<'_, SettingsStore>,
+  keys: Option<Vec<String>>,
+) -> Result<Settings, String> {
+  store
+    .watchers
+    .lock()
+    .map_err(|e| e.to_string())?
+    .insert(window.label().to_string(), keys.unwrap_or_default());
+  Ok(store.get())
+}
+
+/// 首次启动时导入前端 localStorage 中的旧版数据（版本 0），经 `migrate_v0` 转换后整体写入；
+/// 已有设置文件或已导入过时不做任何事，返回是否发生了导入
+#[tauri::command]
+pub fn settings_import_legacy(
+  app_handle: AppHandle,
+  window: WebviewWindow,
+  store: State<'_, SettingsStore>,
+  legacy: Value,
+) -> Result<bool, String> {
+  if !store.legacy_import.swap(false, Ordering::SeqCst) {
+    return Ok(false);
+  }
+  let mut value = legacy;
+  let Some(root) = value.as_object_mut() else {
+    return Err("Legacy settings must be an object".to_string());
+  };
+  root.insert("version".to_string(), Value::from(0));
+  migrate(&mut value)?;
+  // 旧数据里缺失或无法识别的字段回落到当前值
+  let mut merged = serde_json::to_value(store.get()).map_err(|e| e.to_string())?;
+  if let (Some(merged), Some(legacy)) = (merged.as_object_mut(), value.as_object()) {
+    for (key, item) in legacy {
+      if !item.is_null() {
+        merged.insert(key.clone(), item.clone());
+      }
+    }
+  }
+  set(&app_handle, None, merged, Some(window.label().to_string()))?;
+  Ok(true)
+}
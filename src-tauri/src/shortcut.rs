@@ -0,0 +1,253 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, Runtime, State};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+
+use crate::storage;
+use crate::tray::{self, NavigatePayload};
+
+const SHORTCUTS_FILE: &str = "shortcuts.json";
+
+/// 快捷键触发的动作
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ShortcutAction {
+  ToggleWindow,
+  OpenTool { slug: String },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ShortcutBinding {
+  pub accelerator: String,
+  pub action: ShortcutAction,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ShortcutStatus {
+  pub accelerator: String,
+  pub action: ShortcutAction,
+  pub registered: bool,
+  pub error: Option<String>,
+}
+
+/// 快捷键相关的结构化错误，前端可按 `kind` 分支处理
+#[derive(Serialize, Clone, Debug)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ShortcutError {
+  InvalidAccelerator {
+    accelerator: String,
+    message: String,
+  },
+  Conflict {
+    accelerator: String,
+    existing: ShortcutAction,
+  },
+  RegistrationFailed {
+    accelerator: String,
+    message: String,
+  },
+  NotFound {
+    accelerator: String,
+  },
+  Storage {
+    message: String,
+  },
+}
+
+/// 已保存的快捷键及其注册结果
+#[derive(Default)]
+pub struct ShortcutRegistry {
+  bindings: Mutex<Vec<ShortcutBinding>>,
+  failures: Mutex<HashMap<u32, String>>,
+}
+
+fn parse(accelerator: &str) -> Result<Shortcut, ShortcutError> {
+  Shortcut::from_str(accelerator).map_err(|e| ShortcutError::InvalidAccelerator {
+    accelerator: accelerator.to_string(),
+    message: e.to_string(),
+  })
+}
+
+fn save<R: Runtime>(app: &AppHandle<R>, bindings: &[ShortcutBinding]) -> Result<(), ShortcutError> {
+  let storage_error = |message: String| ShortcutError::Storage { message };
+  let path = storage::config_file(app, SHORTCUTS_FILE).map_err(storage_error)?;
+  storage::write_json(&path, &bindings).map_err(storage_error)
+}
+
+/// 全局快捷键按下时的回调
+pub fn handle<R: Runtime>(app: &AppHandle<R>, shortcut: &Shortcut, state: ShortcutState) {
+  if !matches!(state, ShortcutState::Pressed) {
+    return;
+  }
+  let registry = app.state::<ShortcutRegistry>();
+  let action = registry.bindings.lock().ok().and_then(|bindings| {
+    bindings
+      .iter()
+      .find(|binding| parse(&binding.accelerator).is_ok_and(|parsed| parsed.id() == shortcut.id()))
+      .map(|binding| binding.action.clone())
+  });
+  match action {
+    Some(ShortcutAction::ToggleWindow) => tray::toggle_main_window(app),
+    Some(ShortcutAction::OpenTool { slug }) => tray::navigate(
+      app,
+      NavigatePayload {
+        slug,
+        ..Default::default()
+      },
+    ),
+    None => {}
+  }
+}
+
+/// 启动时注册已保存的快捷键；失败项记录下来供 `shortcut_list` 展示
+pub fn init<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+  let path = storage::config_file(app, SHORTCUTS_FILE)?;
+  let bindings: Vec<ShortcutBinding> = match storage::read_json(&path) {
+    Ok(bindings) => bindings.unwrap_or_default(),
+    Err(error) => {
+      // 文件损坏时改名保留，不阻塞应用启动
+      let _ = std::fs::rename(&path, path.with_extension("json.invalid"));
+      log::warn!("Discarding unreadable shortcuts file: {error}");
+      Vec::new()
+    }
+  };
+  let registry = app.state::<ShortcutRegistry>();
+  let mut failures = registry.failures.lock().map_err(|e| e.to_string())?;
+
+  // 无法解析的快捷键保留在列表中，由 `shortcut_list` 报告解析错误
+  for binding in &bindings {
+    if let Ok(shortcut) = Shortcut::from_str(&binding.accelerator) {
+      if let Err(error) = app.global_shortcut().register(shortcut) {
+        failures.insert(shortcut.id(), error.to_string());
+      }
+    }
+  }
+  *registry.bindings.lock().map_err(|e| e.to_string())? = bindings;
+  Ok(())
+}
+
+/// 注册并保存一个快捷键
+#[tauri::command]
+pub fn shortcut_register(
+  app_handle: AppHandle,
+  registry: State<'_, ShortcutRegistry>,
+  accelerator: String,
+  action: ShortcutAction,
+) -> Result<ShortcutStatus, ShortcutError> {
+  let shortcut = parse(&accelerator)?;
+  let mut bindings = registry.bindings.lock().map_err(|e| ShortcutError::Storage {
+    message: e.to_string(),
+  })?;
+  if let Some(existing) = bindings
+    .iter()
+    .find(|binding| parse(&binding.accelerator).is_ok_and(|parsed| parsed.id() == shortcut.id()))
+  {
+    return Err(ShortcutError::Conflict {
+      accelerator,
+      existing: existing.action.clone(),
+    });
+  }
+
+  app_handle
+    .global_shortcut()
+    .register(shortcut)
+    .map_err(|e| ShortcutError::RegistrationFailed {
+      accelerator: accelerator.clone(),
+      message: e.to_string(),
+    })?;
+
+  bindings.push(ShortcutBinding {
+    accelerator: accelerator.clone(),
+    action: action.clone(),
+  });
+  if let Err(error) = save(&app_handle, &bindings) {
+    bindings.pop();
+    let _ = app_handle.global_shortcut().unregister(shortcut);
+    return Err(error);
+  }
+
+  Ok(ShortcutStatus {
+    accelerator,
+    action,
+    registered: true,
+    error: None,
+  })
+}
+
+/// 注销并删除一个快捷键
+#[tauri::command]
+pub fn shortcut_unregister(
+  app_handle: AppHandle,
+  registry: State<'_, ShortcutRegistry>,
+  accelerator: String,
+) -> Result<(), ShortcutError> {
+  let shortcut = parse(&accelerator)?;
+  let mut bindings = registry.bindings.lock().map_err(|e| ShortcutError::Storage {
+    message: e.to_string(),
+  })?;
+  let index = bindings
+    .iter()
+    .position(|binding| parse(&binding.accelerator).is_ok_and(|parsed| parsed.id() == shortcut.id()))
+    .ok_or_else(|| ShortcutError::NotFound {
+      accelerator: accelerator.clone(),
+    })?;
+
+  if app_handle.global_shortcut().is_registered(shortcut) {
+    app_handle
+      .global_shortcut()
+      .unregister(shortcut)
+      .map_err(|e| ShortcutError::RegistrationFailed {
+        accelerator: accelerator.clone(),
+        message: e.to_string(),
+      })?;
+  }
+  let removed = bindings.remove(index);
+  if let Ok(mut failures) = registry.failures.lock() {
+    failures.remove(&shortcut.id());
+  }
+  if let Err(error) = save(&app_handle, &bindings) {
+    bindings.insert(index, removed);
+    return Err(error);
+  }
+  Ok(())
+}
+
+/// 列出已保存的快捷键及其注册状态
+#[tauri::command]
+pub fn shortcut_list(
+  app_handle: AppHandle,
+  registry: State<'_, ShortcutRegistry>,
+) -> Result<Vec<ShortcutStatus>, ShortcutError> {
+  let bindings = registry.bindings.lock().map_err(|e| ShortcutError::Storage {
+    message: e.to_string(),
+  })?;
+  let failures = registry.failures.lock().map_err(|e| ShortcutError::Storage {
+    message: e.to_string(),
+  })?;
+
+  Ok(
+    bindings
+      .iter()
+      .map(|binding| {
+        let parsed = parse(&binding.accelerator);
+        let (registered, error) = match &parsed {
+          Ok(shortcut) => (
+            app_handle.global_shortcut().is_registered(*shortcut),
+            failures.get(&shortcut.id()).cloned(),
+          ),
+          Err(ShortcutError::InvalidAccelerator { message, .. }) => (false, Some(message.clone())),
+          Err(_) => (false, None),
+        };
+        ShortcutStatus {
+          accelerator: binding.accelerator.clone(),
+          action: binding.action.clone(),
+          registered,
+          error,
+        }
+      })
+      .collect(),
+  )
+}
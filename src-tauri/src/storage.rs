@@ -0,0 +1,48 @@
+use std::path::{Path, PathBuf};
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use tauri::{AppHandle, Manager, Runtime};
+
+/// 应用配置目录下的文件路径，目录不存在时自动创建
+pub fn config_file<R: Runtime>(app: &AppHandle<R>, name: &str) -> Result<PathBuf, String> {
+  let dir = app.path().app_config_dir().map_err(|e| e.to_string())?;
+  std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+  Ok(dir.join(name))
+}
+
+/// 应用数据目录下的文件路径（数据库等较大的数据），目录不存在时自动创建
+pub fn data_file<R: Runtime>(app: &AppHandle<R>, name: &str) -> Result<PathBuf, String> {
+  let dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+  std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+  Ok(dir.join(name))
+}
+
+/// 读取 JSON 文件，文件不存在时返回 None
+pub fn read_json<T: DeserializeOwned>(path: &Path) -> Result<Option<T>, String> {
+  match std::fs::read_to_string(path) {
+    Ok(content) => serde_json::from_str(&content)
+      .map(Some)
+      .map_err(|e| format!("Failed to parse {}: {e}", path.display())),
+    Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(None),
+    Err(error) => Err(format!("Failed to read {}: {error}", path.display())),
+  }
+}
+
+/// 原子写入：先写同目录临时文件再改名，避免中途崩溃留下损坏的配置
+pub fn write_atomic(path: &Path, content: &[u8]) -> Result<(), String> {
+  if let Some(parent) = path.parent() {
+    std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+  }
+  let temp = path.with_extension("tmp");
+  std::fs::write(&temp, content).map_err(|e| format!("Failed to write {}: {e}", temp.display()))?;
+  std::fs::rename(&temp, path).map_err(|e| {
+    let _ = std::fs::remove_file(&temp);
+    format!("Failed to write {}: {e}", path.display())
+  })
+}
+
+pub fn write_json<T: Serialize>(path: &Path, value: &T) -> Result<(), String> {
+  let content = serde_json::to_vec_pretty(value).map_err(|e| e.to_string())?;
+  write_atomic(path, &content)
+}
@@ -0,0 +1,404 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Runtime, State};
+
+use crate::logging;
+
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+const MAX_FINISHED_TASKS: usize = 100;
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum TaskStatus {
+  Running,
+  Completed,
+  Failed,
+  Cancelled,
+}
+
+/// 任务快照，同时也是 `task:progress` 事件的负载
+#[derive(Serialize, Clone, Debug)]
+pub struct TaskInfo {
+  pub id: String,
+  pub kind: String,
+  pub stage: String,
+  pub percent: Option<f64>,
+  pub status: TaskStatus,
+  pub error: Option<String>,
+  pub started_at: u64,
+}
+
+/// 任务进度输出端，桌面端通过 `AppHandle` 发事件，测试中可替换为内存实现
+pub trait TaskEmitter: Send + Sync {
+  fn emit_progress(&self, info: &TaskInfo);
+}
+
+impl<R: Runtime> TaskEmitter for AppHandle<R> {
+  fn emit_progress(&self, info: &TaskInfo) {
+    let _ = self.emit("task:progress", info);
+  }
+}
+
+struct TaskEntry {
+  info: TaskInfo,
+  cancelled: Arc<AtomicBool>,
+}
+
+struct Inner {
+  emitter: Arc<dyn TaskEmitter>,
+  tasks: Mutex<HashMap<String, TaskEntry>>,
+  next_id: AtomicU64,
+}
+
+/// 后台任务注册表，作为 managed state 在各原生命令间共享
+#[derive(Clone)]
+pub struct TaskRegistry {
+  inner: Arc<Inner>,
+}
+
+fn now_millis() -> u64 {
+  SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map(|d| d.as_millis() as u64)
+    .unwrap_or(0)
+}
+
+impl TaskRegistry {
+  pub fn new(emitter: Arc<dyn TaskEmitter>) -> Self {
+    Self {
+      inner: Arc::new(Inner {
+        emitter,
+        tasks: Mutex::new(HashMap::new()),
+        next_id: AtomicU64::new(1),
+      }),
+    }
+  }
+
+  /// 登记一个新任务；`id` 为空时自动生成，同名任务仍在运行时返回错误
+  pub fn start(&self, id: Option<String>, kind: &str) -> Result<TaskHandle, String> {
+    let id = id.unwrap_or_else(|| {
+      format!(
+        "{kind}-{}",
+        self.inner.next_id.fetch_add(1, Ordering::Relaxed)
+      )
+    });
+    let cancelled = Arc::new(AtomicBool::new(false));
+    let info = TaskInfo {
+      id: id.clone(),
+      kind: kind.to_string(),
+      stage: "started".to_string(),
+      percent: Some(0.0),
+      status: TaskStatus::Running,
+      error: None,
+      started_at: now_millis(),
+    };
+
+    {
+      let mut tasks = self.inner.tasks.lock().map_err(|e| e.to_string())?;
+      if tasks
+        .get(&id)
+        .is_some_and(|entry| entry.info.status == TaskStatus::Running)
+      {
+        return Err(format!("Task {id} is already running"));
+      }
+      Self::prune(&mut tasks);
+      tasks.insert(
+        id.clone(),
+        TaskEntry {
+          info: info.clone(),
+          cancelled: cancelled.clone(),
+        },
+      );
+    }
+    self.emit(&info);
+
+    Ok(TaskHandle {
+      registry: self.clone(),
+      id,
+      cancelled,
+      last_emit: Instant::now(),
+      finished: false,
+    })
+  }
+
+  /// 只保留最近的若干个已结束任务，避免注册表无限增长
+  fn prune(tasks: &mut HashMap<String, TaskEntry>) {
+    let mut finished: Vec<(String, u64)> = tasks
+      .values()
+      .filter(|entry| entry.info.status != TaskStatus::Running)
+      .map(|entry| (entry.info.id.clone(), entry.info.started_at))
+      .collect();
+    if finished.len() < MAX_FINISHED_TASKS {
+      return;
+    }
+    finished.sort_by_key(|(_, started_at)| *started_at);
+    for (id, _) in finished.iter().take(finished.len() + 1 - MAX_FINISHED_TASKS) {
+      tasks.remove(id);
+    }
+  }
+
+  /// 请求取消任务，任务需要自行检查 `TaskHandle::is_cancelled`
+  pub fn cancel(&self, id: &str) -> bool {
+    let Ok(tasks) = self.inner.tasks.lock() else {
+      return false;
+    };
+    match tasks.get(id) {
+      Some(entry) if entry.info.status == TaskStatus::Running => {
+        entry.cancelled.store(true, Ordering::Relaxed);
+        true
+      }
+      _ => false,
+    }
+  }
+
+  pub fn status(&self, id: &str) -> Option<TaskInfo> {
+    let tasks = self.inner.tasks.lock().ok()?;
+    tasks.get(id).map(|entry| entry.info.clone())
+  }
+
+  pub fn list(&self) -> Vec<TaskInfo> {
+    let Ok(tasks) = self.inner.tasks.lock() else {
+      return Vec::new();
+    };
+    let mut list: Vec<TaskInfo> = tasks.values().map(|entry| entry.info.clone()).collect();
+    list.sort_by_key(|info| info.started_at);
+    list
+  }
+
+  fn update(&self, id: &str, apply: impl FnOnce(&mut TaskInfo)) -> Option<TaskInfo> {
+    let mut tasks = self.inner.tasks.lock().ok()?;
+    let entry = tasks.get_mut(id)?;
+    apply(&mut entry.info);
+    Some(entry.info.clone())
+  }
+
+  fn emit(&self, info: &TaskInfo) {
+    self.inner.emitter.emit_progress(info);
+  }
+}
+
+/// 运行中任务的句柄，由执行任务的线程持有
+pub struct TaskHandle {
+  registry: TaskRegistry,
+  id: String,
+  cancelled: Arc<AtomicBool>,
+  last_emit: Instant,
+  finished: bool,
+}
+
+impl TaskHandle {
+  pub fn id(&self) -> &str {
+    &self.id
+  }
+
+  pub fn is_cancelled(&self) -> bool {
+    self.cancelled.load(Ordering::Relaxed)
+  }
+
+  /// 更新进度；阶段不变时按固定间隔节流事件
+  pub fn progress(&mut self, stage: &str, percent: Option<f64>) {
+    let mut stage_changed = false;
+    let snapshot = self.registry.update(&self.id, |info| {
+      stage_changed = info.stage != stage;
+      info.stage = stage.to_string();
+      info.percent = percent.map(|p| p.clamp(0.0, 100.0));
+    });
+    if let Some(info) = snapshot {
+      if stage_changed || self.last_emit.elapsed() >= PROGRESS_INTERVAL {
+        self.last_emit = Instant::now();
+        self.registry.emit(&info);
+      }
+    }
+  }
+
+  /// 按比例换算百分比的便捷方法
+  pub fn progress_ratio(&mut self, stage: &str, done: u64, total: u64) {
+    let percent = if total == 0 {
+      None
+    } else {
+      Some(done as f64 / total as f64 * 100.0)
+    };
+    self.progress(stage, percent);
+  }
+
+  pub fn complete(mut self) {
+    self.finish(TaskStatus::Completed, None);
+  }
+
+  pub fn fail(mut self, error: impl Into<String>) {
+    self.finish(TaskStatus::Failed, Some(error.into()));
+  }
+
+  pub fn cancel_acknowledged(mut self) {
+    self.finish(TaskStatus::Cancelled, None);
+  }
+
+  /// 根据结果收尾：取消优先于成功/失败
+  pub fn settle<T>(self, result: &Result<T, String>) {
+    if self.is_cancelled() {
+      self.cancel_acknowledged();
+    } else {
+      match result {
+        Ok(_) => self.complete(),
+        Err(error) => self.fail(error.clone()),
+      }
+    }
+  }
+
+  fn finish(&mut self, status: TaskStatus, error: Option<String>) {
+    self.finished = true;
+    let snapshot = self.registry.update(&self.id, |info| {
+      info.status = status;
+      info.stage = match status {
+        TaskStatus::Completed => "completed".to_string(),
+        TaskStatus::Cancelled => "cancelled".to_string(),
+        _ => "failed".to_string(),
+      };
+      if status == TaskStatus::Completed {
+        info.percent = Some(100.0);
+      }
+      info.error = error;
+    });
+    if let Some(info) = snapshot {
+      if let (TaskStatus::Failed, Some(error)) = (status, &info.error) {
+        logging::record_failure(&info.kind, error);
+      }
+      self.registry.emit(&info);
+    }
+  }
+}
+
+impl Drop for TaskHandle {
+  fn drop(&mut self) {
+    if !self.finished {
+      if self.is_cancelled() {
+        self.finish(TaskStatus::Cancelled, None);
+      } else {
+        self.finish(TaskStatus::Failed, Some("Task ended unexpectedly".to_string()));
+      }
+    }
+  }
+}
+
+/// 列出当前及最近结束的任务
+#[tauri::command]
+pub fn task_list(tasks: State<'_, TaskRegistry>) -> Vec<TaskInfo> {
+  tasks.list()
+}
+
+/// 查询单个任务状态
+#[tauri::command]
+pub fn task_status(tasks: State<'_, TaskRegistry>, id: String) -> Option<TaskInfo> {
+  tasks.status(&id)
+}
+
+/// 取消任务，任务不存在或已结束时返回 false
+#[tauri::command]
+pub fn task_cancel(tasks: State<'_, TaskRegistry>, id: String) -> bool {
+  tasks.cancel(&id)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// 内存中的进度输出端，记录所有推送过的快照
+  #[derive(Default)]
+  struct Recorder(Mutex<Vec<TaskInfo>>);
+
+  impl TaskEmitter for Recorder {
+    fn emit_progress(&self, info: &TaskInfo) {
+      self.0.lock().unwrap().push(info.clone());
+    }
+  }
+
+  impl Recorder {
+    fn events(&self) -> Vec<TaskInfo> {
+      self.0.lock().unwrap().clone()
+    }
+  }
+
+  fn registry() -> (TaskRegistry, Arc<Recorder>) {
+    let recorder = Arc::new(Recorder::default());
+    (TaskRegistry::new(recorder.clone()), recorder)
+  }
+
+  #[test]
+  fn progress_is_throttled_within_a_stage() {
+    let (registry, recorder) = registry();
+    let mut task = registry.start(Some("t".to_string()), "test").unwrap();
+    task.progress("working", Some(10.0));
+    task.progress("working", Some(20.0));
+    task.progress("working", Some(30.0));
+    // started + 阶段切换各一次，同阶段的后续更新被节流
+    assert_eq!(recorder.events().len(), 2);
+    assert_eq!(registry.status("t").unwrap().percent, Some(30.0));
+
+    std::thread::sleep(PROGRESS_INTERVAL);
+    task.progress("working", Some(40.0));
+    assert_eq!(recorder.events().len(), 3);
+    task.progress("writing", Some(50.0));
+    assert_eq!(recorder.events().len(), 4);
+
+    task.complete();
+    let last = recorder.events().pop().unwrap();
+    assert_eq!(last.status, TaskStatus::Completed);
+    assert_eq!(last.percent, Some(100.0));
+  }
+
+  #[test]
+  fn cancel_marks_running_task() {
+    let (registry, recorder) = registry();
+    let task = registry.start(Some("t".to_string()), "test").unwrap();
+    assert!(!registry.cancel("missing"));
+    assert!(registry.cancel("t"));
+    assert!(task.is_cancelled());
+
+    task.settle::<()>(&Ok(()));
+    assert_eq!(registry.status("t").unwrap().status, TaskStatus::Cancelled);
+    assert_eq!(recorder.events().last().unwrap().stage, "cancelled");
+    assert!(!registry.cancel("t"));
+  }
+
+  #[test]
+  fn duplicate_running_id_is_rejected() {
+    let (registry, _) = registry();
+    let task = registry.start(Some("t".to_string()), "test").unwrap();
+    assert!(registry.start(Some("t".to_string()), "test").is_err());
+    task.complete();
+    assert!(registry.start(Some("t".to_string()), "test").is_ok());
+  }
+
+  #[test]
+  fn dropped_handle_fails_task() {
+    let (registry, recorder) = registry();
+    let task = registry.start(Some("t".to_string()), "test").unwrap();
+    drop(task);
+    let info = registry.status("t").unwrap();
+    assert_eq!(info.status, TaskStatus::Failed);
+    assert_eq!(info.error.as_deref(), Some("Task ended unexpectedly"));
+    assert_eq!(recorder.events().last().unwrap().status, TaskStatus::Failed);
+
+    let task = registry.start(Some("c".to_string()), "test").unwrap();
+    registry.cancel("c");
+    drop(task);
+    assert_eq!(registry.status("c").unwrap().status, TaskStatus::Cancelled);
+  }
+
+  #[test]
+  fn finished_tasks_are_pruned() {
+    let (registry, _) = registry();
+    for _ in 0..MAX_FINISHED_TASKS + 10 {
+      registry.start(None, "test").unwrap().complete();
+    }
+    let running = registry.start(Some("running".to_string()), "test").unwrap();
+    let list = registry.list();
+    assert_eq!(list.len(), MAX_FINISHED_TASKS);
+    assert!(list.iter().any(|info| info.id == "running"));
+    running.complete();
+  }
+}
@@ -0,0 +1,508 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+
+use printpdf::{BuiltinFont, IndirectFontRef, Mm, PdfDocument, Pt};
+use pulldown_cmark::{Event, HeadingLevel, Parser, Tag, TagEnd};
+use serde::{Deserialize, Serialize};
+
+/// 未指定字体且文本包含非拉丁字符时依次尝试的系统 CJK 字体
+const CJK_FONT_CANDIDATES: &[&str] = &[
+  "C:\\Windows\\Fonts\\msyh.ttf",
+  "C:\\Windows\\Fonts\\simhei.ttf",
+  "/System/Library/Fonts/Supplemental/Arial Unicode.ttf",
+  "/Library/Fonts/Arial Unicode.ttf",
+  "/usr/share/fonts/truetype/droid/DroidSansFallbackFull.ttf",
+  "/usr/share/fonts/opentype/noto/NotoSansCJK-Regular.ttc",
+  "/usr/share/fonts/noto-cjk/NotoSansCJK-Regular.ttc",
+];
+
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "lowercase", tag = "kind")]
+pub enum TextPdfPageSize {
+  #[default]
+  A4,
+  A5,
+  Letter,
+  Legal,
+  Custom {
+    width_mm: f32,
+    height_mm: f32,
+  },
+}
+
+impl TextPdfPageSize {
+  fn dimensions_mm(self) -> (f32, f32) {
+    match self {
+      TextPdfPageSize::A4 => (210.0, 297.0),
+      TextPdfPageSize::A5 => (148.0, 210.0),
+      TextPdfPageSize::Letter => (215.9, 279.4),
+      TextPdfPageSize::Legal => (215.9, 355.6),
+      TextPdfPageSize::Custom {
+        width_mm,
+        height_mm,
+      } => (width_mm, height_mm),
+    }
+  }
+}
+
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TextPdfFormat {
+  #[default]
+  Plain,
+  Markdown,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct TextPdfMargins {
+  pub top: f32,
+  pub right: f32,
+  pub bottom: f32,
+  pub left: f32,
+}
+
+impl Default for TextPdfMargins {
+  fn default() -> Self {
+    Self {
+      top: 20.0,
+      right: 20.0,
+      bottom: 20.0,
+      left: 20.0,
+    }
+  }
+}
+
+/// 页眉页脚文本，支持 `{page}` 与 `{pages}` 占位符
+#[derive(Deserialize, Clone, Debug, Default)]
+pub struct TextPdfOptions {
+  #[serde(default)]
+  pub format: TextPdfFormat,
+  #[serde(default)]
+  pub page_size: TextPdfPageSize,
+  #[serde(default)]
+  pub margins: TextPdfMargins,
+  pub font_path: Option<String>,
+  pub font_size: Option<f32>,
+  pub title: Option<String>,
+  pub header: Option<String>,
+  pub footer: Option<String>,
+}
+
+/// 渲染结果；未指定输出路径时 `data` 为 base64 编码的 PDF 内容，供前端直接预览与下载
+#[derive(Serialize, Clone, Debug)]
+pub struct TextPdfResult {
+  pub path: Option<String>,
+  pub pages: u32,
+  pub data: Option<String>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum BlockStyle {
+  Body,
+  Heading,
+  Code,
+}
+
+struct Block {
+  text: String,
+  size: f32,
+  style: BlockStyle,
+  indent: f32,
+}
+
+struct Line {
+  text: String,
+  size: f32,
+  style: BlockStyle,
+  x: f32,
+  /// 基线距页面顶部的距离（pt）
+  y: f32,
+}
+
+/// 文本宽度测量：嵌入字体按字形步进计算，内置字体按平均字宽估算
+enum Metrics<'a> {
+  Builtin,
+  Face(Box<ttf_parser::Face<'a>>),
+}
+
+impl Metrics<'_> {
+  fn width(&self, text: &str, size: f32, style: BlockStyle) -> f32 {
+    match self {
+      Metrics::Builtin => {
+        let factor = if style == BlockStyle::Code { 0.6 } else { 0.52 };
+        text.chars().count() as f32 * size * factor
+      }
+      Metrics::Face(face) => {
+        let units = face.units_per_em() as f32;
+        text
+          .chars()
+          .map(|ch| {
+            face
+              .glyph_index(ch)
+              .and_then(|id| face.glyph_hor_advance(id))
+              .unwrap_or(face.units_per_em() / 2) as f32
+          })
+          .sum::<f32>()
+          * size
+          / units
+      }
+    }
+  }
+}
+
+fn is_wide(ch: char) -> bool {
+  ch as u32 >= 0x2E80
+}
+
+/// 按可用宽度折行：CJK 字符可在任意处断开，拉丁文本在空白处断开，超长单词强制拆分
+fn wrap(text: &str, max_width: f32, size: f32, style: BlockStyle, metrics: &Metrics) -> Vec<String> {
+  let mut tokens: Vec<String> = Vec::new();
+  for ch in text.chars() {
+    let extend = matches!(tokens.last(), Some(last) if !is_wide(ch) && !ch.is_whitespace()
+      && last.chars().last().is_some_and(|prev| !is_wide(prev) && !prev.is_whitespace()));
+    if extend {
+      tokens.last_mut().unwrap().push(ch);
+    } else {
+      tokens.push(ch.to_string());
+    }
+  }
+
+  let mut lines = Vec::new();
+  let mut current = String::new();
+  for token in tokens {
+    let candidate = format!("{current}{token}");
+    if metrics.width(&candidate, size, style) <= max_width || current.is_empty() {
+      if metrics.width(&token, size, style) > max_width && current.is_empty() {
+        for ch in token.chars() {
+          let candidate = format!("{current}{ch}");
+          if metrics.width(&candidate, size, style) > max_width && !current.is_empty() {
+            lines.push(std::mem::take(&mut current));
+          }
+          current.push(ch);
+        }
+      } else {
+        current = candidate;
+      }
+    } else {
+      lines.push(current.trim_end().to_string());
+      current = if token.trim().is_empty() {
+        String::new()
+      } else {
+        token
+      };
+    }
+  }
+  if !current.is_empty() || lines.is_empty() {
+    lines.push(current.trim_end().to_string());
+  }
+  lines
+}
+
+fn plain_blocks(text: &str, size: f32) -> Vec<Block> {
+  text
+    .lines()
+    .map(|line| Block {
+      text: line.replace('\t', "    "),
+      size,
+      style: BlockStyle::Body,
+      indent: 0.0,
+    })
+    .collect()
+}
+
+fn markdown_blocks(text: &str, size: f32) -> Vec<Block> {
+  let mut blocks = Vec::new();
+  let mut current = String::new();
+  let mut style = BlockStyle::Body;
+  let mut block_size = size;
+  let mut list_depth = 0_usize;
+
+  let flush = |blocks: &mut Vec<Block>, current: &mut String, size: f32, style: BlockStyle, depth: usize| {
+    if style == BlockStyle::Code {
+      for line in current.trim_end_matches('\n').lines() {
+        blocks.push(Block {
+          text: line.replace('\t', "    "),
+          size,
+          style,
+          indent: 12.0,
+        });
+      }
+    } else if !current.trim().is_empty() {
+      blocks.push(Block {
+        text: current.trim().to_string(),
+        size,
+        style,
+        indent: depth as f32 * 14.0,
+      });
+    }
+    current.clear();
+  };
+
+  for event in Parser::new(text) {
+    match event {
+      Event::Start(Tag::Heading { level, .. }) => {
+        style = BlockStyle::Heading;
+        block_size = size
+          * match level {
+            HeadingLevel::H1 => 1.8,
+            HeadingLevel::H2 => 1.5,
+            HeadingLevel::H3 => 1.25,
+            _ => 1.1,
+          };
+      }
+      Event::Start(Tag::CodeBlock(_)) => style = BlockStyle::Code,
+      Event::Start(Tag::List(_)) => {
+        flush(&mut blocks, &mut current, block_size, style, list_depth);
+        list_depth += 1;
+      }
+      Event::End(TagEnd::List(_)) => list_depth = list_depth.saturating_sub(1),
+      Event::Start(Tag::Item) => {
+        flush(&mut blocks, &mut current, block_size, style, list_depth);
+        current.push_str("• ");
+      }
+      Event::End(TagEnd::Heading(_))
+      | Event::End(TagEnd::Paragraph)
+      | Event::End(TagEnd::CodeBlock)
+      | Event::End(TagEnd::Item) => {
+        flush(&mut blocks, &mut current, block_size, style, list_depth);
+        if !matches!(event, Event::End(TagEnd::Item)) {
+          blocks.push(Block {
+            text: String::new(),
+            size: size * 0.5,
+            style: BlockStyle::Body,
+            indent: 0.0,
+          });
+        }
+        style = BlockStyle::Body;
+        block_size = size;
+      }
+      Event::Text(text) | Event::Code(text) => current.push_str(&text),
+      Event::SoftBreak => current.push(' '),
+      Event::HardBreak => flush(&mut blocks, &mut current, block_size, style, list_depth),
+      Event::Rule => blocks.push(Block {
+        text: "—".repeat(24),
+        size,
+        style: BlockStyle::Body,
+        indent: 0.0,
+      }),
+      _ => {}
+    }
+  }
+  flush(&mut blocks, &mut current, block_size, style, list_depth);
+  blocks
+}
+
+fn resolve_font(options: &TextPdfOptions, text: &str) -> Result<Option<Vec<u8>>, String> {
+  if let Some(path) = options.font_path.as_deref().filter(|p| !p.is_empty()) {
+    return std::fs::read(path)
+      .map(Some)
+      .map_err(|e| format!("Failed to read font {path}: {e}"));
+  }
+  // 内置字体只覆盖 Latin-1，超出范围时回退到系统 CJK 字体
+  if text.chars().all(|ch| (ch as u32) < 0x100) {
+    return Ok(None);
+  }
+  CJK_FONT_CANDIDATES
+    .iter()
+    .find_map(|path| std::fs::read(path).ok())
+    .map(Some)
+    .ok_or_else(|| "Text contains characters outside Latin-1, please choose a font file".to_string())
+}
+
+/// 在本地把纯文本或 Markdown 渲染为 PDF，写入 `writer` 并返回页数
+pub fn render<W: Write>(text: &str, options: &TextPdfOptions, writer: &mut BufWriter<W>) -> Result<u32, String> {
+  let font_size = options.font_size.unwrap_or(11.0).max(4.0);
+  let (page_width_mm, page_height_mm) = options.page_size.dimensions_mm();
+  let page_width = Pt::from(Mm(page_width_mm)).0;
+  let page_height = Pt::from(Mm(page_height_mm)).0;
+  let margins = &options.margins;
+  let (top, right, bottom, left) = (
+    Pt::from(Mm(margins.top)).0,
+    Pt::from(Mm(margins.right)).0,
+    Pt::from(Mm(margins.bottom)).0,
+    Pt::from(Mm(margins.left)).0,
+  );
+  let content_width = page_width - left - right;
+  let content_height = page_height - top - bottom;
+  if content_width <= font_size || content_height <= font_size {
+    return Err("Margins leave no room for content".to_string());
+  }
+
+  let extra_text = format!(
+    "{}{}",
+    options.header.as_deref().unwrap_or_default(),
+    options.footer.as_deref().unwrap_or_default()
+  );
+  let font_data = resolve_font(options, &format!("{text}{extra_text}"))?;
+  let face = match &font_data {
+    Some(data) => Some(ttf_parser::Face::parse(data, 0).map_err(|e| format!("Invalid font file: {e}"))?),
+    None => None,
+  };
+  let metrics = match face {
+    Some(face) => Metrics::Face(Box::new(face)),
+    None => Metrics::Builtin,
+  };
+
+  let blocks = match options.format {
+    TextPdfFormat::Plain => plain_blocks(text, font_size),
+    TextPdfFormat::Markdown => markdown_blocks(text, font_size),
+  };
+
+  // 先完成排版，才能在页眉页脚中填入总页数
+  let mut pages: Vec<Vec<Line>> = vec![Vec::new()];
+  let mut cursor = 0.0_f32;
+  for block in &blocks {
+    let line_height = block.size * 1.4;
+    for text in wrap(&block.text, content_width - block.indent, block.size, block.style, &metrics) {
+      if cursor + line_height > content_height && cursor > 0.0 {
+        pages.push(Vec::new());
+        cursor = 0.0;
+      }
+      cursor += line_height;
+      pages.last_mut().unwrap().push(Line {
+        text,
+        size: block.size,
+        style: block.style,
+        x: left + block.indent,
+        y: top + cursor - (line_height - block.size),
+      });
+    }
+  }
+
+  let title = options.title.clone().unwrap_or_default();
+  let (document, first_page, first_layer) =
+    PdfDocument::new(&title, Mm(page_width_mm), Mm(page_height_mm), "Layer 1");
+  let fonts: [IndirectFontRef; 3] = match &font_data {
+    Some(data) => {
+      let font = document
+        .add_external_font(data.as_slice())
+        .map_err(|e| e.to_string())?;
+      [font.clone(), font.clone(), font]
+    }
+    None => [
+      document
+        .add_builtin_font(BuiltinFont::Helvetica)
+        .map_err(|e| e.to_string())?,
+      document
+        .add_builtin_font(BuiltinFont::HelveticaBold)
+        .map_err(|e| e.to_string())?,
+      document
+        .add_builtin_font(BuiltinFont::Courier)
+        .map_err(|e| e.to_string())?,
+    ],
+  };
+  let font_for = |style: BlockStyle| match style {
+    BlockStyle::Body => &fonts[0],
+    BlockStyle::Heading => &fonts[1],
+    BlockStyle::Code => &fonts[2],
+  };
+
+  let total = pages.len();
+  for (index, lines) in pages.iter().enumerate() {
+    let (page, layer) = if index == 0 {
+      (first_page, first_layer)
+    } else {
+      document.add_page(Mm(page_width_mm), Mm(page_height_mm), "Layer 1")
+    };
+    let layer = document.get_page(page).get_layer(layer);
+    let to_mm = |pt: f32| Mm::from(Pt(pt));
+
+    for line in lines {
+      if line.text.is_empty() {
+        continue;
+      }
+      layer.use_text(
+        line.text.clone(),
+        line.size,
+        to_mm(line.x),
+        to_mm(page_height - line.y),
+        font_for(line.style),
+      );
+    }
+
+    let decoration_size = font_size * 0.8;
+    let fill = |template: &str| {
+      template
+        .replace("{page}", &(index + 1).to_string())
+        .replace("{pages}", &total.to_string())
+    };
+    if let Some(header) = options.header.as_deref().filter(|h| !h.is_empty()) {
+      layer.use_text(
+        fill(header),
+        decoration_size,
+        to_mm(left),
+        to_mm(page_height - top / 2.0),
+        font_for(BlockStyle::Body),
+      );
+    }
+    if let Some(footer) = options.footer.as_deref().filter(|f| !f.is_empty()) {
+      let footer = fill(footer);
+      let width = metrics.width(&footer, decoration_size, BlockStyle::Body);
+      layer.use_text(
+        footer,
+        decoration_size,
+        to_mm((page_width - width) / 2.0),
+        to_mm(bottom / 2.0),
+        font_for(BlockStyle::Body),
+      );
+    }
+  }
+
+  document
+    .save(writer)
+    .map_err(|e| format!("Failed to write PDF: {e}"))?;
+  Ok(total as u32)
+}
+
+fn render_output(text: &str, output: Option<&str>, options: &TextPdfOptions) -> Result<TextPdfResult, String> {
+  let Some(output) = output else {
+    let mut writer = BufWriter::new(Vec::new());
+    let pages = render(text, options, &mut writer)?;
+    let bytes = writer.into_inner().map_err(|e| e.to_string())?;
+    return Ok(TextPdfResult {
+      path: None,
+      pages,
+      data: Some(STANDARD.encode(bytes)),
+    });
+  };
+
+  if let Some(parent) = Path::new(output).parent() {
+    std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+  }
+  // 未指定标题时以输出文件名作为文档标题
+  let mut options = options.clone();
+  if options.title.is_none() {
+    options.title = Path::new(output)
+      .file_stem()
+      .map(|stem| stem.to_string_lossy().into_owned());
+  }
+  let file = File::create(output).map_err(|e| format!("Failed to create {output}: {e}"))?;
+  let mut writer = BufWriter::new(file);
+  let pages = render(text, &options, &mut writer)?;
+  writer
+    .flush()
+    .map_err(|e| format!("Failed to write {output}: {e}"))?;
+  Ok(TextPdfResult {
+    path: Some(output.to_string()),
+    pages,
+    data: None,
+  })
+}
+
+/// 文本转 PDF，替代原先的远程导出服务；`output` 为空时直接返回 PDF 内容
+#[tauri::command]
+pub async fn text_to_pdf(
+  text: String,
+  output: Option<String>,
+  options: Option<TextPdfOptions>,
+) -> Result<TextPdfResult, String> {
+  tauri::async_runtime::spawn_blocking(move || {
+    render_output(&text, output.as_deref(), &options.unwrap_or_default())
+  })
+  .await
+  .map_err(|e| e.to_string())?
+}
@@ -0,0 +1,177 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, Runtime, State, WebviewUrl, WebviewWindow, WebviewWindowBuilder};
+
+use crate::launch::is_valid_slug;
+use crate::{storage, window_state};
+
+const TOOL_WINDOWS_FILE: &str = "tool-windows.json";
+/// 独立工具窗口的 label 前缀，后接工具 slug
+pub const TOOL_WINDOW_PREFIX: &str = "tool-";
+pub const DEFAULT_WIDTH: u32 = 960;
+pub const DEFAULT_HEIGHT: u32 = 720;
+const MIN_OPACITY: f64 = 0.2;
+
+/// 每个工具窗口的显示选项，按 slug 持久化
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub struct ToolWindowOptions {
+  pub always_on_top: bool,
+  pub opacity: f64,
+}
+
+impl Default for ToolWindowOptions {
+  fn default() -> Self {
+    Self {
+      always_on_top: false,
+      opacity: 1.0,
+    }
+  }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct WindowInfo {
+  pub label: String,
+  pub slug: Option<String>,
+  pub title: String,
+  pub visible: bool,
+  pub focused: bool,
+  pub options: Option<ToolWindowOptions>,
+}
+
+#[derive(Default)]
+pub struct ToolWindowState {
+  options: Mutex<HashMap<String, ToolWindowOptions>>,
+}
+
+pub fn label_for(slug: &str) -> String {
+  format!("{TOOL_WINDOW_PREFIX}{slug}")
+}
+
+pub fn slug_of(label: &str) -> Option<&str> {
+  label.strip_prefix(TOOL_WINDOW_PREFIX)
+}
+
+fn save<R: Runtime>(app: &AppHandle<R>, options: &HashMap<String, ToolWindowOptions>) -> Result<(), String> {
+  let path = storage::config_file(app, TOOL_WINDOWS_FILE)?;
+  storage::write_json(&path, options)
+}
+
+/// 读取保存的工具窗口选项
+pub fn init<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+  let path = storage::config_file(app, TOOL_WINDOWS_FILE)?;
+  let options = storage::read_json(&path).ok().flatten().unwrap_or_default();
+  *app
+    .state::<ToolWindowState>()
+    .options
+    .lock()
+    .map_err(|e| e.to_string())? = options;
+  Ok(())
+}
+
+/// 把选项应用到已存在的窗口；透明度由前端在根节点上应用（窗口本身以透明背景创建）
+fn apply<R: Runtime>(window: &WebviewWindow<R>, options: &ToolWindowOptions) -> Result<(), String> {
+  window
+    .set_always_on_top(options.always_on_top)
+    .map_err(|e| e.to_string())?;
+  window
+    .emit_to(window.label(), "window:options", options)
+    .map_err(|e| e.to_string())
+}
+
+/// 在独立窗口中打开工具；窗口已存在时更新选项并聚焦
+#[tauri::command]
+pub async fn window_open_tool(
+  app_handle: AppHandle,
+  state: State<'_, ToolWindowState>,
+  slug: String,
+  title: Option<String>,
+  always_on_top: Option<bool>,
+  opacity: Option<f64>,
+) -> Result<WindowInfo, String> {
+  if !is_valid_slug(&slug) {
+    return Err(format!("Invalid tool slug: {slug}"));
+  }
+
+  let options = {
+    let mut saved = state.options.lock().map_err(|e| e.to_string())?;
+    let mut options = saved.get(&slug).copied().unwrap_or_default();
+    if let Some(always_on_top) = always_on_top {
+      options.always_on_top = always_on_top;
+    }
+    if let Some(opacity) = opacity {
+      options.opacity = opacity.clamp(MIN_OPACITY, 1.0);
+    }
+    if saved.get(&slug) != Some(&options) {
+      saved.insert(slug.clone(), options);
+      save(&app_handle, &saved)?;
+    }
+    options
+  };
+
+  let label = label_for(&slug);
+  let title = title.unwrap_or_else(|| format!("Kit - {slug}"));
+  let window = match app_handle.get_webview_window(&label) {
+    Some(window) => {
+      apply(&window, &options)?;
+      window
+    }
+    None => {
+      let config = serde_json::json!({ "slug": slug, "options": options });
+      let builder = WebviewWindowBuilder::new(&app_handle, &label, WebviewUrl::App(format!("tool/{slug}").into()))
+        .title(&title)
+        .inner_size(DEFAULT_WIDTH as f64, DEFAULT_HEIGHT as f64)
+        .min_inner_size(360.0, 240.0)
+        .decorations(false)
+        .always_on_top(options.always_on_top)
+        .visible(false)
+        .initialization_script(&format!("window.__KIT_WINDOW__ = {config};"));
+      // macOS 上透明窗口需要私有 API，透明度在该平台仅作用于页面内容
+      #[cfg(not(target_os = "macos"))]
+      let builder = builder.transparent(true);
+      let window = builder.build().map_err(|e| e.to_string())?;
+      let restored = window_state::restore(&window);
+      window.show().map_err(|e| e.to_string())?;
+      restored?;
+      window
+    }
+  };
+  let _ = window.unminimize();
+  let _ = window.set_focus();
+
+  Ok(WindowInfo {
+    label,
+    slug: Some(slug),
+    title,
+    visible: true,
+    focused: true,
+    options: Some(options),
+  })
+}
+
+/// 列出当前所有窗口（主窗口与独立工具窗口）
+#[tauri::command]
+pub fn window_list(app_handle: AppHandle, state: State<'_, ToolWindowState>) -> Result<Vec<WindowInfo>, String> {
+  let saved = state.options.lock().map_err(|e| e.to_string())?;
+  let mut windows: Vec<WindowInfo> = app_handle
+    .webview_windows()
+    .into_iter()
+    .map(|(label, window)| {
+      let slug = slug_of(&label).map(str::to_string);
+      let options = slug
+        .as_ref()
+        .map(|slug| saved.get(slug).copied().unwrap_or_default());
+      WindowInfo {
+        title: window.title().unwrap_or_default(),
+        visible: window.is_visible().unwrap_or(false),
+        focused: window.is_focused().unwrap_or(false),
+        label,
+        slug,
+        options,
+      }
+    })
+    .collect();
+  windows.sort_by(|a, b| a.label.cmp(&b.label));
+  Ok(windows)
+}
@@ -0,0 +1,215 @@
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use tauri::menu::{IsMenuItem, Menu, MenuItem, PredefinedMenuItem, Submenu};
+use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
+use tauri::{AppHandle, Emitter, Manager, Runtime, State, WebviewWindow};
+
+use crate::settings::{self, SettingsStore};
+
+const TRAY_ID: &str = "main";
+const MAIN_WINDOW: &str = "main";
+const TOOL_ITEM_PREFIX: &str = "tool:";
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct TrayTool {
+  pub slug: String,
+  pub name: String,
+}
+
+/// 托盘菜单文案，由前端按当前语言传入
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct TrayLabels {
+  pub favorites: String,
+  pub recent: String,
+  pub show_hide: String,
+  pub check_updates: String,
+  pub quit: String,
+}
+
+impl Default for TrayLabels {
+  fn default() -> Self {
+    Self {
+      favorites: "Favorites".to_string(),
+      recent: "Recent".to_string(),
+      show_hide: "Show/Hide".to_string(),
+      check_updates: "Check for updates".to_string(),
+      quit: "Quit".to_string(),
+    }
+  }
+}
+
+/// 导航事件负载，托盘与后续的深链接、命令行入口共用
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct NavigatePayload {
+  pub slug: String,
+  pub input: Option<String>,
+  pub file: Option<String>,
+}
+
+#[derive(Default)]
+pub struct TrayState {
+  labels: Mutex<TrayLabels>,
+}
+
+/// 关闭按钮是否改为隐藏到托盘，读取持久化的偏好设置
+pub fn close_to_tray<R: Runtime>(app: &AppHandle<R>) -> bool {
+  app.state::<SettingsStore>().get().preferences.close_to_tray
+}
+
+/// 显示并聚焦主窗口
+pub fn show_main_window<R: Runtime>(app: &AppHandle<R>) {
+  if let Some(window) = app.get_webview_window(MAIN_WINDOW) {
+    let _ = window.unminimize();
+    let _ = window.show();
+    let _ = window.set_focus();
+  }
+}
+
+/// 切换主窗口显示状态
+pub fn toggle_main_window<R: Runtime>(app: &AppHandle<R>) {
+  if let Some(window) = app.get_webview_window(MAIN_WINDOW) {
+    if window.is_visible().unwrap_or(false) && !window.is_minimized().unwrap_or(false) {
+      let _ = window.hide();
+    } else {
+      show_main_window(app);
+    }
+  }
+}
+
+/// 聚焦主窗口并通知前端跳转到指定工具
+pub fn navigate<R: Runtime>(app: &AppHandle<R>, payload: NavigatePayload) {
+  show_main_window(app);
+  let _ = app.emit_to(MAIN_WINDOW, "app:navigate", payload);
+}
+
+fn build_menu<R: Runtime>(
+  app: &AppHandle<R>,
+  favorites: &[TrayTool],
+  recent: &[TrayTool],
+  labels: &TrayLabels,
+) -> tauri::Result<Menu<R>> {
+  let tool_submenu = |id: &str, text: &str, tools: &[TrayTool]| -> tauri::Result<Submenu<R>> {
+    let items = tools
+      .iter()
+      .map(|tool| {
+        MenuItem::with_id(
+          app,
+          format!("{TOOL_ITEM_PREFIX}{}", tool.slug),
+          &tool.name,
+          true,
+          None::<&str>,
+        )
+      })
+      .collect::<tauri::Result<Vec<_>>>()?;
+    let refs: Vec<&dyn IsMenuItem<R>> = items.iter().map(|item| item as &dyn IsMenuItem<R>).collect();
+    Submenu::with_id_and_items(app, id, text, !tools.is_empty(), &refs)
+  };
+
+  let favorites_menu = tool_submenu("favorites", &labels.favorites, favorites)?;
+  let recent_menu = tool_submenu("recent", &labels.recent, recent)?;
+  let show_hide = MenuItem::with_id(app, "show_hide", &labels.show_hide, true, None::<&str>)?;
+  let check_updates = MenuItem::with_id(app, "check_updates", &labels.check_updates, true, None::<&str>)?;
+  let quit = MenuItem::with_id(app, "quit", &labels.quit, true, None::<&str>)?;
+
+  Menu::with_items(
+    app,
+    &[
+      &favorites_menu,
+      &recent_menu,
+      &PredefinedMenuItem::separator(app)?,
+      &show_hide,
+      &check_updates,
+      &PredefinedMenuItem::separator(app)?,
+      &quit,
+    ],
+  )
+}
+
+/// 创建托盘图标，应用启动时调用一次
+pub fn init<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<()> {
+  let menu = build_menu(app, &[], &[], &TrayLabels::default())?;
+  let mut builder = TrayIconBuilder::with_id(TRAY_ID)
+    .menu(&menu)
+    .tooltip("Kit")
+    .show_menu_on_left_click(false)
+    .on_menu_event(|app, event| {
+      let id = event.id().as_ref();
+      if let Some(slug) = id.strip_prefix(TOOL_ITEM_PREFIX) {
+        navigate(
+          app,
+          NavigatePayload {
+            slug: slug.to_string(),
+            ..Default::default()
+          },
+        );
+        return;
+      }
+      match id {
+        "show_hide" => toggle_main_window(app),
+        "check_updates" => {
+          show_main_window(app);
+          let _ = app.emit_to(MAIN_WINDOW, "updater:check-requested", ());
+        }
+        "quit" => app.exit(0),
+        _ => {}
+      }
+    })
+    .on_tray_icon_event(|tray, event| {
+      if let TrayIconEvent::Click {
+        button: MouseButton::Left,
+        button_state: MouseButtonState::Up,
+        ..
+      } = event
+      {
+        toggle_main_window(tray.app_handle());
+      }
+    });
+  if let Some(icon) = app.default_window_icon() {
+    builder = builder.icon(icon.clone());
+  }
+  builder.build(app)?;
+  Ok(())
+}
+
+/// 同步收藏与最近使用的工具到托盘菜单
+#[tauri::command]
+pub fn tray_set_tools(
+  app_handle: AppHandle,
+  state: State<'_, TrayState>,
+  favorites: Vec<TrayTool>,
+  recent: Vec<TrayTool>,
+  labels: Option<TrayLabels>,
+) -> Result<(), String> {
+  let labels = {
+    let mut current = state.labels.lock().map_err(|e| e.to_string())?;
+    if let Some(labels) = labels {
+      *current = labels;
+    }
+    current.clone()
+  };
+  let tray = app_handle
+    .tray_by_id(TRAY_ID)
+    .ok_or_else(|| "Tray icon is not available".to_string())?;
+  let menu = build_menu(&app_handle, &favorites, &recent, &labels).map_err(|e| e.to_string())?;
+  tray.set_menu(Some(menu)).map_err(|e| e.to_string())
+}
+
+/// 设置关闭按钮是否改为隐藏到托盘，写入设置文件以便下次启动沿用
+#[tauri::command]
+pub fn tray_set_close_to_tray(app_handle: AppHandle, enabled: bool) -> Result<(), String> {
+  settings::update(&app_handle, "preferences.close_to_tray", |settings| {
+    settings.preferences.close_to_tray = enabled;
+  })
+  .map(|_| ())
+}
+
+/// 关闭窗口；主窗口在启用托盘模式时改为隐藏
+pub fn close_or_hide(window: &WebviewWindow) -> tauri::Result<()> {
+  if window.label() == MAIN_WINDOW && close_to_tray(window.app_handle()) {
+    window.hide()
+  } else {
+    window.close()
+  }
+}
@@ -0,0 +1,1001 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use minisign_verify::{PublicKey, Signature};
+
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, Runtime, State, Url};
+use tauri_plugin_notification::NotificationExt;
+use tauri_plugin_updater::{Update, Updater, UpdaterExt};
+
+use crate::settings::{self, SettingsStore};
+
+/// 覆盖所有渠道的更新地址，便于对接本地模拟服务测试
+const ENDPOINT_ENV: &str = "KIT_UPDATE_ENDPOINT";
+const RELEASES_URL: &str = "https://github.com/aafnnp/kit/releases";
+/// 覆盖定时检查间隔（秒）并取消启动延迟，配合 `KIT_UPDATE_ENDPOINT` 与模拟服务测试
+const CHECK_INTERVAL_ENV: &str = "KIT_UPDATE_CHECK_INTERVAL";
+/// 启动后首次检查前的基础延迟与随机抖动上限
+const STARTUP_DELAY: Duration = Duration::from_secs(30);
+const STARTUP_JITTER_MS: u64 = 90_000;
+/// 调度线程轮询设置的间隔，修改检查间隔后最迟一个周期生效
+const POLL_INTERVAL: Duration = Duration::from_secs(60);
+const DEFAULT_CHECK_INTERVAL_HOURS: u32 = 24;
+/// 环境变量覆盖检查间隔时的下限，避免 0 或过小的值让调度线程空转并频繁请求更新服务
+const MIN_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+/// 下载进度事件的最小推送间隔
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+/// 下载速度的指数平滑系数，越大越接近瞬时速度
+const SPEED_SMOOTHING: f64 = 0.3;
+
+/// 更新渠道：稳定版、测试版与每日构建
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum UpdateChannel {
+  #[default]
+  Stable,
+  Beta,
+  Nightly,
+}
+
+impl UpdateChannel {
+  /// 各渠道的更新清单地址：稳定版沿用 `scripts/updater.mjs` 发布的 `updater` tag，
+  /// 测试版与每日构建发布在同名的滚动 tag 下
+  pub fn endpoint(self) -> String {
+    match self {
+      UpdateChannel::Stable => format!("{RELEASES_URL}/download/updater/latest.json"),
+      UpdateChannel::Beta => format!("{RELEASES_URL}/download/beta/latest.json"),
+      UpdateChannel::Nightly => format!("{RELEASES_URL}/download/nightly/latest.json"),
+    }
+  }
+}
+
+/// 更新相关的持久化设置
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(default)]
+pub struct UpdaterSettings {
+  pub channel: UpdateChannel,
+  /// 退出应用时自动安装已暂存的更新
+  pub install_on_quit: bool,
+  /// 后台定时检查更新
+  pub auto_check: bool,
+  /// 定时检查间隔（小时）
+  pub check_interval_hours: u32,
+  /// 用户选择跳过、不再提醒的版本
+  pub skipped_versions: Vec<String>,
+  /// 在此时间（毫秒时间戳）之前不提醒更新
+  pub snoozed_until: Option<i64>,
+}
+
+impl Default for UpdaterSettings {
+  fn default() -> Self {
+    Self {
+      channel: UpdateChannel::default(),
+      install_on_quit: false,
+      auto_check: true,
+      check_interval_hours: DEFAULT_CHECK_INTERVAL_HOURS,
+      skipped_versions: Vec::new(),
+      snoozed_until: None,
+    }
+  }
+}
+
+/// 更新信息，复用现有 Electron 侧的字段语义
+#[derive(Serialize, Clone)]
+pub struct UpdateInfo {
+  version: String,
+  date: Option<String>,
+  body: Option<String>,
+  channel: UpdateChannel,
+}
+
+impl UpdateInfo {
+  fn new(update: &Update, channel: UpdateChannel) -> Self {
+    Self {
+      version: update.version.clone(),
+      date: update.date.map(|date| date.to_string()),
+      body: update.body.clone(),
+      channel,
+    }
+  }
+}
+
+/// 已下载并通过签名校验、等待安装的更新
+struct StagedUpdate {
+  update: Update,
+  bytes: Vec<u8>,
+  info: UpdateInfo,
+}
+
+#[derive(Default)]
+pub struct UpdaterState {
+  staged: Mutex<Option<StagedUpdate>>,
+  /// 已发送过系统通知的版本，避免每个检查周期重复打扰
+  notified: Mutex<Option<String>>,
+}
+
+/// 更新失败所处的阶段
+#[derive(Serialize, Clone, Copy, Debug)]
+#[serde(rename_all = "snake_case")]
+enum UpdateErrorCode {
+  Check,
+  NoUpdate,
+  Download,
+  Verify,
+  Install,
+}
+
+/// `updater:progress` 事件：Started → Progress… → Finished → Verifying → Installing，任一阶段出错时为 Failed
+#[derive(Serialize, Clone)]
+#[serde(tag = "event", content = "data")]
+enum UpdateProgressEvent {
+  Started {
+    version: String,
+    content_length: Option<u64>,
+  },
+  Progress {
+    downloaded: u64,
+    content_length: Option<u64>,
+    bytes_per_second: u64,
+    eta_seconds: Option<u64>,
+  },
+  Finished {},
+  Verifying {},
+  Installing {},
+  Failed {
+    code: UpdateErrorCode,
+    message: String,
+  },
+}
+
+/// 推送进度事件；事件只用于展示，推送失败不影响更新流程
+fn emit_progress<R: Runtime>(app: &AppHandle<R>, event: UpdateProgressEvent) {
+  if let Err(error) = app.emit("updater:progress", event) {
+    log::warn!("Failed to emit updater progress: {error}");
+  }
+}
+
+/// 推送 Failed 事件并返回错误信息，便于在 `map_err` 中使用
+fn fail<R: Runtime>(app: &AppHandle<R>, code: UpdateErrorCode, message: String) -> String {
+  log::error!("Update failed ({code:?}): {message}");
+  emit_progress(
+    app,
+    UpdateProgressEvent::Failed {
+      code,
+      message: message.clone(),
+    },
+  );
+  message
+}
+
+/// 统计下载速度与剩余时间，并按 `PROGRESS_INTERVAL` 节流进度事件
+struct ProgressTracker {
+  downloaded: u64,
+  last_emit: Instant,
+  last_downloaded: u64,
+  bytes_per_second: Option<f64>,
+}
+
+impl ProgressTracker {
+  fn new() -> Self {
+    Self {
+      downloaded: 0,
+      last_emit: Instant::now(),
+      last_downloaded: 0,
+      bytes_per_second: None,
+    }
+  }
+
+  /// 记录一个数据块；到达推送间隔或下载完成时返回要推送的进度
+  fn record(&mut self, chunk_length: usize, content_length: Option<u64>) -> Option<UpdateProgressEvent> {
+    self.downloaded += chunk_length as u64;
+    let elapsed = self.last_emit.elapsed();
+    let complete = content_length.is_some_and(|length| self.downloaded >= length);
+    if elapsed < PROGRESS_INTERVAL && !complete {
+      return None;
+    }
+
+    let seconds = elapsed.as_secs_f64();
+    if seconds > 0.0 {
+      let sample = (self.downloaded - self.last_downloaded) as f64 / seconds;
+      self.bytes_per_second = Some(match self.bytes_per_second {
+        Some(speed) => SPEED_SMOOTHING * sample + (1.0 - SPEED_SMOOTHING) * speed,
+        None => sample,
+      });
+    }
+    self.last_emit = Instant::now();
+    self.last_downloaded = self.downloaded;
+
+    let speed = self.bytes_per_second.unwrap_or(0.0);
+    let eta_seconds = content_length
+      .filter(|_| speed > 0.0)
+      .map(|length| (length.saturating_sub(self.downloaded) as f64 / speed).ceil() as u64);
+    Some(UpdateProgressEvent::Progress {
+      downloaded: self.downloaded,
+      content_length,
+      bytes_per_second: speed.round() as u64,
+      eta_seconds,
+    })
+  }
+}
+
+/// 当前设置中的更新渠道
+pub fn current_channel<R: Runtime>(app: &AppHandle<R>) -> UpdateChannel {
+  app
+    .try_state::<SettingsStore>()
+    .map(|store| store.get().updater.channel)
+    .unwrap_or_default()
+}
+
+/// 按渠道构建更新器
+pub fn build<R: Runtime>(app: &AppHandle<R>, channel: UpdateChannel) -> Result<Updater, String> {
+  let endpoint = std::env::var(ENDPOINT_ENV).unwrap_or_else(|_| channel.endpoint());
+  let url = Url::parse(&endpoint).map_err(|e| format!("Invalid update endpoint {endpoint}: {e}"))?;
+  app
+    .updater_builder()
+    .endpoints(vec![url])
+    .map_err(|e| e.to_string())?
+    .build()
+    .map_err(|e| e.to_string())
+}
+
+/// 检查是否有可用更新
+#[tauri::command]
+pub async fn updater_check(app_handle: AppHandle) -> Result<Option<UpdateInfo>, String> {
+  let channel = current_channel(&app_handle);
+  let updater = build(&app_handle, channel)?;
+  let update = updater.check().await.map_err(|e| e.to_string())?;
+  Ok(update.map(|update| UpdateInfo::new(&update, channel)))
+}
+
+/// 切换更新渠道并持久化
+#[tauri::command]
+pub fn updater_set_channel(app_handle: AppHandle, channel: UpdateChannel) -> Result<UpdateChannel, String> {
+  settings::update(&app_handle, "updater", |settings| settings.updater.channel = channel)?;
+  Ok(channel)
+}
+
+/// 下载更新包并推送进度；`download` 在下载完成后、返回前按配置的公钥校验签名。
+/// Started 在收到响应后推送，携带服务端返回的实际大小
+async fn download<R: Runtime>(app: &AppHandle<R>, update: &Update) -> Result<Vec<u8>, String> {
+  let mut tracker: Option<ProgressTracker> = None;
+  let verifying = AtomicBool::new(false);
+
+  let result = update
+    .download(
+      |chunk_length, content_length| {
+        let tracker = tracker.get_or_insert_with(|| {
+          emit_progress(
+            app,
+            UpdateProgressEvent::Started {
+              version: update.version.clone(),
+              content_length,
+            },
+          );
+          ProgressTracker::new()
+        });
+        if let Some(event) = tracker.record(chunk_length, content_length) {
+          emit_progress(app, event);
+        }
+      },
+      || {
+        verifying.store(true, Ordering::Relaxed);
+        emit_progress(app, UpdateProgressEvent::Finished {});
+        emit_progress(app, UpdateProgressEvent::Verifying {});
+      },
+    )
+    .await;
+
+  result.map_err(|e| {
+    let code = if verifying.load(Ordering::Relaxed) {
+      UpdateErrorCode::Verify
+    } else {
+      UpdateErrorCode::Download
+    };
+    fail(app, code, e.to_string())
+  })
+}
+
+/// 安装更新包，推送 Installing 阶段
+fn install<R: Runtime>(app: &AppHandle<R>, update: &Update, bytes: &[u8]) -> Result<(), String> {
+  emit_progress(app, UpdateProgressEvent::Installing {});
+  update
+    .install(bytes)
+    .map_err(|e| fail(app, UpdateErrorCode::Install, e.to_string()))
+}
+
+/// 检查并下载更新，签名校验通过后暂存在内存中等待安装；同一版本已暂存时直接返回
+async fn stage<R: Runtime>(app: &AppHandle<R>) -> Result<UpdateInfo, String> {
+  let channel = current_channel(app);
+  let updater = build(app, channel).map_err(|e| fail(app, UpdateErrorCode::Check, e))?;
+  let update = updater
+    .check()
+    .await
+    .map_err(|e| fail(app, UpdateErrorCode::Check, e.to_string()))?
+    .ok_or_else(|| fail(app, UpdateErrorCode::NoUpdate, "No update available".to_string()))?;
+  let state = app.state::<UpdaterState>();
+  if let Some(staged) = state.staged.lock().map_err(|e| e.to_string())?.as_ref() {
+    if staged.update.version == update.version {
+      return Ok(staged.info.clone());
+    }
+  }
+
+  let info = UpdateInfo::new(&update, channel);
+  let bytes = download(app, &update).await?;
+  *state.staged.lock().map_err(|e| e.to_string())? = Some(StagedUpdate {
+    update,
+    bytes,
+    info: info.clone(),
+  });
+  Ok(info)
+}
+
+/// 安装已暂存的更新
+fn install_staged<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+  let staged = app
+    .state::<UpdaterState>()
+    .staged
+    .lock()
+    .map_err(|e| e.to_string())?
+    .take()
+    .ok_or_else(|| {
+      fail(
+        app,
+        UpdateErrorCode::NoUpdate,
+        "No staged update; call updater_download first".to_string(),
+      )
+    })?;
+  install(app, &staged.update, &staged.bytes)
+}
+
+/// 应用退出时安装暂存的更新（需在设置中开启 `updater.install_on_quit`）
+pub fn install_on_exit<R: Runtime>(app: &AppHandle<R>) {
+  let enabled = app
+    .try_state::<SettingsStore>()
+    .is_some_and(|store| store.get().updater.install_on_quit);
+  let has_staged = app
+    .state::<UpdaterState>()
+    .staged
+    .lock()
+    .is_ok_and(|staged| staged.is_some());
+  if enabled && has_staged {
+    if let Err(error) = install_staged(app) {
+      log::error!("Failed to install staged update on exit: {error}");
+    }
+  }
+}
+
+/// 下载并暂存更新，稍后通过 `updater_install` 或退出时安装
+#[tauri::command]
+pub async fn updater_download(app_handle: AppHandle) -> Result<UpdateInfo, String> {
+  stage(&app_handle).await
+}
+
+/// 下载并立即安装更新，同时通过事件推送进度
+#[tauri::command]
+pub async fn updater_download_and_install(app_handle: AppHandle) -> Result<(), String> {
+  stage(&app_handle).await?;
+  install_staged(&app_handle)
+}
+
+/// 安装已暂存的更新，不再重新检查或下载
+#[tauri::command]
+pub fn updater_install(app_handle: AppHandle) -> Result<(), String> {
+  install_staged(&app_handle)
+}
+
+/// 当前暂存的更新
+#[tauri::command]
+pub fn updater_staged(state: State<'_, UpdaterState>) -> Result<Option<UpdateInfo>, String> {
+  Ok(
+    state
+      .staged
+      .lock()
+      .map_err(|e| e.to_string())?
+      .as_ref()
+      .map(|staged| staged.info.clone()),
+  )
+}
+
+/// 设置是否在应用退出时自动安装暂存的更新
+#[tauri::command]
+pub fn updater_set_install_on_quit(app_handle: AppHandle, enabled: bool) -> Result<(), String> {
+  settings::update(&app_handle, "updater", |settings| settings.updater.install_on_quit = enabled)?;
+  Ok(())
+}
+
+/// 版本被跳过或处于暂停提醒期内
+fn is_suppressed(settings: &UpdaterSettings, version: &str, now_ms: i64) -> bool {
+  settings.skipped_versions.iter().any(|skipped| skipped == version)
+    || settings.snoozed_until.is_some_and(|until| until > now_ms)
+}
+
+/// 定时检查间隔，环境变量覆盖时以秒为单位且不低于 `MIN_CHECK_INTERVAL`
+fn check_interval(settings: &UpdaterSettings) -> Duration {
+  std::env::var(CHECK_INTERVAL_ENV)
+    .ok()
+    .and_then(|value| value.parse::<u64>().ok())
+    .map(|seconds| Duration::from_secs(seconds).max(MIN_CHECK_INTERVAL))
+    .unwrap_or_else(|| Duration::from_secs(u64::from(settings.check_interval_hours.max(1)) * 3600))
+}
+
+/// 启动后首次检查的延迟：基础延迟加随机抖动，避免所有客户端同时请求
+fn startup_delay() -> Duration {
+  if std::env::var(CHECK_INTERVAL_ENV).is_ok() {
+    return Duration::ZERO;
+  }
+  let jitter = (uuid::Uuid::new_v4().as_u128() % u128::from(STARTUP_JITTER_MS)) as u64;
+  STARTUP_DELAY + Duration::from_millis(jitter)
+}
+
+/// 后台检查一次：发现未被跳过或暂停的更新时推送 `updater:available`，并按偏好发送系统通知
+async fn scheduled_check<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+  let settings = app.state::<SettingsStore>().get();
+  let channel = settings.updater.channel;
+  let Some(update) = build(app, channel)?.check().await.map_err(|e| e.to_string())? else {
+    return Ok(());
+  };
+  if is_suppressed(&settings.updater, &update.version, chrono::Utc::now().timestamp_millis()) {
+    log::info!("Update {} is available but skipped or snoozed", update.version);
+    return Ok(());
+  }
+
+  let info = UpdateInfo::new(&update, channel);
+  log::info!("Update {} is available on the {channel:?} channel", info.version);
+  app.emit("updater:available", &info).map_err(|e| e.to_string())?;
+
+  let state = app.state::<UpdaterState>();
+  let mut notified = state.notified.lock().map_err(|e| e.to_string())?;
+  if settings.preferences.notifications && notified.as_deref() != Some(info.version.as_str()) {
+    app
+      .notification()
+      .builder()
+      .title(format!("Kit {} is available", info.version))
+      .body("Open Kit to download and install the update.")
+      .show()
+      .map_err(|e| e.to_string())?;
+    *notified = Some(info.version.clone());
+  }
+  Ok(())
+}
+
+/// 启动后台更新检查：启动后带抖动延迟检查一次，之后按设置的间隔检查；每轮重新读取设置
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+  let app = app.clone();
+  std::thread::spawn(move || {
+    std::thread::sleep(startup_delay());
+    let mut last_check: Option<Instant> = None;
+    loop {
+      let settings = app.state::<SettingsStore>().get().updater;
+      let interval = check_interval(&settings);
+      let due = last_check.is_none_or(|at| at.elapsed() >= interval);
+      if settings.auto_check && due {
+        if let Err(error) = tauri::async_runtime::block_on(scheduled_check(&app)) {
+          log::warn!("Scheduled update check failed: {error}");
+        }
+        last_check = Some(Instant::now());
+      }
+      std::thread::sleep(POLL_INTERVAL.min(interval));
+    }
+  });
+}
+
+/// 跳过指定版本，后台检查不再提醒该版本
+#[tauri::command]
+pub fn updater_skip_version(app_handle: AppHandle, version: String) -> Result<(), String> {
+  settings::update(&app_handle, "updater", |settings| {
+    if !settings.updater.skipped_versions.contains(&version) {
+      settings.updater.skipped_versions.push(version);
+    }
+  })?;
+  Ok(())
+}
+
+/// 暂停更新提醒若干小时，0 表示取消暂停；返回暂停截止时间（毫秒时间戳）
+#[tauri::command]
+pub fn updater_snooze(app_handle: AppHandle, hours: u32) -> Result<Option<i64>, String> {
+  let until = (hours > 0).then(|| chrono::Utc::now().timestamp_millis() + i64::from(hours) * 3_600_000);
+  settings::update(&app_handle, "updater", |settings| settings.updater.snoozed_until = until)?;
+  Ok(until)
+}
+
+/// 设置后台定时检查的开关与间隔（小时）
+#[tauri::command]
+pub fn updater_set_schedule(
+  app_handle: AppHandle,
+  auto_check: bool,
+  interval_hours: Option<u32>,
+) -> Result<UpdaterSettings, String> {
+  let settings = settings::update(&app_handle, "updater", |settings| {
+    settings.updater.auto_check = auto_check;
+    if let Some(hours) = interval_hours {
+      settings.updater.check_interval_hours = hours.max(1);
+    }
+  })?;
+  Ok(settings.updater)
+}
+
+/// 配置中的更新公钥（`plugins.updater.pubkey`），发布构建时由 CI 写入
+fn configured_pubkey<R: Runtime>(app: &AppHandle<R>) -> Result<String, String> {
+  app
+    .config()
+    .plugins
+    .0
+    .get("updater")
+    .and_then(|config| config.get("pubkey"))
+    .and_then(|pubkey| pubkey.as_str())
+    .filter(|pubkey| !pubkey.trim().is_empty())
+    .map(str::to_string)
+    .ok_or_else(|| "No updater public key is configured".to_string())
+}
+
+/// 校验 minisign 签名（包括覆盖可信注释的全局签名）；公钥与签名都是 tauri signer 生成的 base64 文本。
+/// 返回已通过校验的可信注释
+fn verify_signature(data: &[u8], signature: &str, pubkey: &str) -> Result<String, String> {
+  let decode = |value: &str, what: &str| -> Result<String, String> {
+    let bytes = STANDARD
+      .decode(value.trim())
+      .map_err(|e| format!("Invalid {what} encoding: {e}"))?;
+    String::from_utf8(bytes).map_err(|_| format!("Invalid {what} encoding"))
+  };
+  let public_key =
+    PublicKey::decode(&decode(pubkey, "public key")?).map_err(|e| format!("Invalid public key: {e}"))?;
+  let signature =
+    Signature::decode(&decode(signature, "signature")?).map_err(|e| format!("Invalid signature: {e}"))?;
+  public_key
+    .verify(data, &signature, true)
+    .map_err(|e| format!("Signature verification failed: {e}"))?;
+  Ok(signature.trusted_comment().to_string())
+}
+
+/// 被签名的文件名：`tauri signer sign` 的可信注释形如 `timestamp:1700000000\tfile:Kit_1.2.3_x64-setup.exe`
+fn signed_file_name(trusted_comment: &str) -> Option<&str> {
+  trusted_comment
+    .split('\t')
+    .find_map(|field| field.strip_prefix("file:"))
+    .and_then(|name| Path::new(name.trim()).file_name()?.to_str())
+    .filter(|name| !name.is_empty())
+}
+
+/// 从 `Kit_1.2.3_x64-setup.exe` 形式的文件名解析版本；macOS 的 `Kit.app.tar.gz` 不含版本
+fn version_from_file_name(name: &str) -> Option<Version> {
+  name.split('_').find_map(|part| Version::parse(part).ok())
+}
+
+/// 把更新包写入临时目录，文件名沿用签名中的原始文件名
+fn write_package(version: &str, file_name: &str, bytes: &[u8]) -> Result<PathBuf, String> {
+  let dir = std::env::temp_dir().join(format!("kit-update-{version}"));
+  std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+  let path = dir.join(file_name);
+  std::fs::write(&path, bytes).map_err(|e| format!("Failed to write {}: {e}", path.display()))?;
+  Ok(path)
+}
+
+/// 解压 `.tar.gz` 并返回其中第一个扩展名为 `extension` 的条目
+#[cfg(unix)]
+fn extract_tar_gz(archive: &Path, extension: &str) -> Result<PathBuf, String> {
+  let dir = archive.with_extension("extracted");
+  std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+  let status = Command::new("tar")
+    .arg("-xzf")
+    .arg(archive)
+    .arg("-C")
+    .arg(&dir)
+    .status()
+    .map_err(|e| format!("Failed to run tar: {e}"))?;
+  if !status.success() {
+    return Err(format!("Failed to extract {}", archive.display()));
+  }
+  std::fs::read_dir(&dir)
+    .map_err(|e| e.to_string())?
+    .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+    .find(|path| path.extension().is_some_and(|ext| ext == extension))
+    .ok_or_else(|| format!("No .{extension} found in {}", archive.display()))
+}
+
+/// 通过 pkexec 以管理员权限运行系统包管理器
+#[cfg(all(unix, not(target_os = "macos")))]
+fn run_privileged(program: &str, args: &[&std::ffi::OsStr]) -> Result<(), String> {
+  let status = Command::new("pkexec")
+    .arg(program)
+    .args(args)
+    .status()
+    .map_err(|e| format!("Failed to run pkexec: {e}"))?;
+  if status.success() {
+    Ok(())
+  } else {
+    Err(format!("{program} exited with {status}"))
+  }
+}
+
+/// 安装离线更新包：NSIS/MSI 交给安装程序并退出应用，安装程序会在完成后重新启动应用
+#[cfg(windows)]
+fn install_package<R: Runtime>(app: &AppHandle<R>, package: &Path) -> Result<(), String> {
+  let name = package.file_name().and_then(|name| name.to_str()).unwrap_or_default();
+  let installer = if name.ends_with(".zip") {
+    // 旧版更新产物把安装程序打包在 zip 中
+    let file = std::fs::File::open(package).map_err(|e| e.to_string())?;
+    let mut archive = zip::ZipArchive::new(file).map_err(|e| e.to_string())?;
+    let dir = package.with_extension("extracted");
+    archive.extract(&dir).map_err(|e| e.to_string())?;
+    std::fs::read_dir(&dir)
+      .map_err(|e| e.to_string())?
+      .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+      .find(|path| path.extension().is_some_and(|ext| ext == "exe" || ext == "msi"))
+      .ok_or_else(|| format!("No installer found in {name}"))?
+  } else {
+    package.to_path_buf()
+  };
+
+  let mut command = match installer.extension().and_then(|ext| ext.to_str()) {
+    Some("exe") => {
+      let mut command = Command::new(&installer);
+      command.args(["/P", "/R", "/UPDATE"]);
+      command
+    }
+    Some("msi") => {
+      let mut command = Command::new("msiexec.exe");
+      command
+        .arg("/i")
+        .arg(&installer)
+        .args(["/passive", "/promptrestart", "AUTOLAUNCHAPP=True"]);
+      command
+    }
+    _ => return Err(format!("Unsupported update package: {name}")),
+  };
+  command.spawn().map_err(|e| format!("Failed to start installer: {e}"))?;
+  app.exit(0);
+  Ok(())
+}
+
+/// 安装离线更新包：用新的 `.app` 替换当前应用包，完成后由前端重启应用
+#[cfg(target_os = "macos")]
+fn install_package<R: Runtime>(_app: &AppHandle<R>, package: &Path) -> Result<(), String> {
+  let name = package.file_name().and_then(|name| name.to_str()).unwrap_or_default();
+  if !name.ends_with(".app.tar.gz") {
+    return Err(format!("Unsupported update package: {name}"));
+  }
+  let new_bundle = extract_tar_gz(package, "app")?;
+  // 可执行文件位于 Kit.app/Contents/MacOS/Kit
+  let exe = std::env::current_exe().map_err(|e| e.to_string())?;
+  let bundle = exe
+    .ancestors()
+    .nth(3)
+    .filter(|path| path.extension().is_some_and(|ext| ext == "app"))
+    .ok_or("The running application is not inside an app bundle")?;
+  let backup = package.with_extension("previous.app");
+  std::fs::rename(bundle, &backup).map_err(|e| format!("Failed to move the current app bundle: {e}"))?;
+  if let Err(error) = std::fs::rename(&new_bundle, bundle) {
+    let _ = std::fs::rename(&backup, bundle);
+    return Err(format!("Failed to replace the app bundle: {error}"));
+  }
+  let _ = std::fs::remove_dir_all(&backup);
+  Ok(())
+}
+
+/// 安装离线更新包：deb/rpm 通过 pkexec 安装，AppImage 原位替换当前文件，完成后由前端重启应用
+#[cfg(all(unix, not(target_os = "macos")))]
+fn install_package<R: Runtime>(_app: &AppHandle<R>, package: &Path) -> Result<(), String> {
+  use std::ffi::OsStr;
+  use std::os::unix::fs::PermissionsExt;
+
+  let name = package.file_name().and_then(|name| name.to_str()).unwrap_or_default();
+  if name.ends_with(".deb") {
+    return run_privileged("dpkg", &[OsStr::new("-i"), package.as_os_str()]);
+  }
+  if name.ends_with(".rpm") {
+    return run_privileged("rpm", &[OsStr::new("-U"), package.as_os_str()]);
+  }
+  let image = if name.ends_with(".AppImage.tar.gz") {
+    extract_tar_gz(package, "AppImage")?
+  } else if name.ends_with(".AppImage") {
+    package.to_path_buf()
+  } else {
+    return Err(format!("Unsupported update package: {name}"));
+  };
+
+  let current = std::env::var_os("APPIMAGE")
+    .map(PathBuf::from)
+    .ok_or("The running application is not an AppImage")?;
+  // 先复制到同一目录再改名，替换是原子的
+  let staging = current.with_extension("AppImage.new");
+  std::fs::copy(&image, &staging).map_err(|e| format!("Failed to copy {}: {e}", image.display()))?;
+  std::fs::set_permissions(&staging, std::fs::Permissions::from_mode(0o755)).map_err(|e| e.to_string())?;
+  std::fs::rename(&staging, &current).map_err(|e| {
+    let _ = std::fs::remove_file(&staging);
+    format!("Failed to replace {}: {e}", current.display())
+  })
+}
+
+/// 从本地更新包安装（离线环境）。签名取自 `signature_path`，缺省为 `<path>.sig`；
+/// 版本取自签名可信注释中的文件名，低于或等于当前版本、或无法确定版本的包会被拒绝，除非 `force` 为真
+#[tauri::command]
+pub async fn updater_install_from_file(
+  app_handle: AppHandle,
+  path: String,
+  signature_path: Option<String>,
+  force: Option<bool>,
+) -> Result<UpdateInfo, String> {
+  let force = force.unwrap_or(false);
+  let signature_path = signature_path.unwrap_or_else(|| format!("{path}.sig"));
+  let signature = std::fs::read_to_string(&signature_path)
+    .map_err(|e| format!("Failed to read signature {signature_path}: {e}"))?;
+  let package = tauri::async_runtime::spawn_blocking({
+    let path = path.clone();
+    move || std::fs::read(&path).map_err(|e| format!("Failed to read {path}: {e}"))
+  })
+  .await
+  .map_err(|e| e.to_string())??;
+
+  emit_progress(&app_handle, UpdateProgressEvent::Verifying {});
+  let trusted_comment = configured_pubkey(&app_handle)
+    .and_then(|pubkey| verify_signature(&package, &signature, &pubkey))
+    .map_err(|e| fail(&app_handle, UpdateErrorCode::Verify, e))?;
+  let file_name = signed_file_name(&trusted_comment).ok_or_else(|| {
+    fail(
+      &app_handle,
+      UpdateErrorCode::Verify,
+      "The signature does not name the signed file".to_string(),
+    )
+  })?;
+
+  let version = version_from_file_name(file_name);
+  let current = app_handle.package_info().version.clone();
+  if !force {
+    let version = version.as_ref().ok_or_else(|| {
+      format!("Unable to determine the version of {file_name} from its signature; pass force to install anyway")
+    })?;
+    if *version <= current {
+      return Err(format!(
+        "Refusing to install {version}: it is not newer than the current version {current}"
+      ));
+    }
+  }
+  let version = version.map(|version| version.to_string()).unwrap_or_else(|| "unknown".to_string());
+
+  emit_progress(&app_handle, UpdateProgressEvent::Installing {});
+  let package_path = write_package(&version, file_name, &package)
+    .and_then(|package_path| install_package(&app_handle, &package_path).map(|_| package_path))
+    .map_err(|e| fail(&app_handle, UpdateErrorCode::Install, e))?;
+  log::info!("Installed update {version} from {}", package_path.display());
+  Ok(UpdateInfo {
+    version,
+    date: None,
+    body: Some(format!("Installed from {path}")),
+    channel: current_channel(&app_handle),
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::io::{BufRead, BufReader, Read, Write};
+  use std::net::{TcpListener, TcpStream};
+  use std::process::{Child, Stdio};
+  use std::sync::mpsc;
+
+  use tauri::test::MockRuntime;
+  use tauri::Listener;
+
+  use crate::settings::Settings;
+
+  fn settings_with(skipped: &[&str], snoozed_until: Option<i64>) -> UpdaterSettings {
+    UpdaterSettings {
+      skipped_versions: skipped.iter().map(|version| version.to_string()).collect(),
+      snoozed_until,
+      ..Default::default()
+    }
+  }
+
+  /// 把上次推送时间往前拨，模拟经过了 `elapsed`
+  fn rewind(tracker: &mut ProgressTracker, elapsed: Duration) {
+    tracker.last_emit = Instant::now() - elapsed;
+  }
+
+  #[test]
+  fn suppresses_skipped_versions() {
+    let settings = settings_with(&["1.2.0"], None);
+    assert!(is_suppressed(&settings, "1.2.0", 0));
+    assert!(!is_suppressed(&settings, "1.3.0", 0));
+  }
+
+  #[test]
+  fn suppresses_until_snooze_expires() {
+    let settings = settings_with(&[], Some(1_000));
+    assert!(is_suppressed(&settings, "1.3.0", 999));
+    assert!(!is_suppressed(&settings, "1.3.0", 1_000));
+    assert!(!is_suppressed(&settings_with(&[], None), "1.3.0", 999));
+  }
+
+  #[test]
+  fn check_interval_uses_hours_and_clamps_env_override() {
+    std::env::remove_var(CHECK_INTERVAL_ENV);
+    let mut settings = UpdaterSettings::default();
+    assert_eq!(check_interval(&settings), Duration::from_secs(24 * 3600));
+    settings.check_interval_hours = 0;
+    assert_eq!(check_interval(&settings), Duration::from_secs(3600));
+
+    std::env::set_var(CHECK_INTERVAL_ENV, "0");
+    assert_eq!(check_interval(&settings), MIN_CHECK_INTERVAL);
+    std::env::set_var(CHECK_INTERVAL_ENV, "30");
+    assert_eq!(check_interval(&settings), Duration::from_secs(30));
+    std::env::set_var(CHECK_INTERVAL_ENV, "soon");
+    assert_eq!(check_interval(&settings), Duration::from_secs(3600));
+    std::env::remove_var(CHECK_INTERVAL_ENV);
+  }
+
+  #[test]
+  fn progress_is_throttled_until_complete() {
+    let mut tracker = ProgressTracker::new();
+    assert!(tracker.record(100, Some(1_000)).is_none());
+    let Some(UpdateProgressEvent::Progress {
+      downloaded,
+      content_length,
+      eta_seconds,
+      ..
+    }) = tracker.record(900, Some(1_000))
+    else {
+      panic!("expected a progress event once the download completes");
+    };
+    assert_eq!(downloaded, 1_000);
+    assert_eq!(content_length, Some(1_000));
+    assert_eq!(eta_seconds, Some(0));
+  }
+
+  #[test]
+  fn progress_reports_smoothed_speed_and_eta() {
+    let mut tracker = ProgressTracker::new();
+    rewind(&mut tracker, Duration::from_secs(1));
+    let Some(UpdateProgressEvent::Progress {
+      bytes_per_second,
+      eta_seconds,
+      ..
+    }) = tracker.record(500, Some(2_000))
+    else {
+      panic!("expected a progress event after the interval");
+    };
+    assert!((450..=500).contains(&bytes_per_second), "{bytes_per_second}");
+    assert!(matches!(eta_seconds, Some(3..=4)), "{eta_seconds:?}");
+
+    // 第二个采样约 1000 B/s，平滑后为 0.3 × 1000 + 0.7 × 500
+    rewind(&mut tracker, Duration::from_secs(1));
+    let Some(UpdateProgressEvent::Progress { bytes_per_second, .. }) = tracker.record(1_000, Some(2_000)) else {
+      panic!("expected a progress event after the interval");
+    };
+    assert!((600..=650).contains(&bytes_per_second), "{bytes_per_second}");
+  }
+
+  #[test]
+  fn progress_without_content_length_has_no_eta() {
+    let mut tracker = ProgressTracker::new();
+    rewind(&mut tracker, Duration::from_secs(1));
+    let Some(UpdateProgressEvent::Progress { eta_seconds, .. }) = tracker.record(500, None) else {
+      panic!("expected a progress event after the interval");
+    };
+    assert_eq!(eta_seconds, None);
+  }
+
+  /// 测试结束时结束模拟服务进程
+  struct MockServer(Child);
+
+  impl Drop for MockServer {
+    fn drop(&mut self) {
+      let _ = self.0.kill();
+      let _ = self.0.wait();
+    }
+  }
+
+  fn get(port: u16, path: &str) -> String {
+    let mut stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+    // HTTP/1.0 请求让 node 直接返回完整响应体，而不是分块编码
+    write!(stream, "GET {path} HTTP/1.0\r\nHost: 127.0.0.1\r\n\r\n").unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    response
+  }
+
+  /// 启动 `scripts/mock-update-server.mjs`，返回端口
+  fn start_mock_server(version: &str) -> (u16, MockServer) {
+    let port = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+    let script = Path::new(env!("CARGO_MANIFEST_DIR")).join("../scripts/mock-update-server.mjs");
+    let mut child = Command::new("node")
+      .arg(script)
+      .args([version, &port.to_string()])
+      .stdout(Stdio::piped())
+      .spawn()
+      .expect("failed to start node");
+    let stdout = child.stdout.take().unwrap();
+    let server = MockServer(child);
+    // 服务启动后会打印一行地址
+    BufReader::new(stdout).read_line(&mut String::new()).unwrap();
+    (port, server)
+  }
+
+  /// 带更新插件与设置存储的模拟应用；关闭系统通知，避免测试中弹出
+  fn mock_app(dir: &Path) -> tauri::App<MockRuntime> {
+    let mut context = tauri::test::mock_context(tauri::test::noop_assets());
+    context
+      .config_mut()
+      .plugins
+      .0
+      .insert("updater".to_string(), serde_json::json!({ "pubkey": "" }));
+    let mut settings = Settings::default();
+    settings.preferences.notifications = false;
+    tauri::test::mock_builder()
+      .plugin(tauri_plugin_updater::Builder::new().build())
+      .manage(SettingsStore::new(dir.join("settings.json"), settings, false))
+      .manage(UpdaterState::default())
+      .build(context)
+      .unwrap()
+  }
+
+  #[test]
+  #[ignore = "需要 node：cargo test -- --ignored"]
+  fn mock_server_manifest_is_accepted() {
+    let (port, _server) = start_mock_server("99.0.0");
+
+    let response = get(port, "/latest.json");
+    let (head, body) = response.split_once("\r\n\r\n").unwrap();
+    assert!(head.starts_with("HTTP/1.1 200"), "{head}");
+    let manifest: serde_json::Value = serde_json::from_str(body).unwrap();
+
+    let version = Version::parse(manifest["version"].as_str().unwrap()).unwrap();
+    assert!(version > Version::parse(env!("CARGO_PKG_VERSION")).unwrap());
+    let platforms = manifest["platforms"].as_object().unwrap();
+    for target in ["darwin-aarch64", "linux-x86_64", "windows-x86_64"] {
+      let platform = &platforms[target];
+      assert!(platform["url"].as_str().is_some_and(|url| Url::parse(url).is_ok()), "{target}");
+      assert!(platform["signature"].is_string(), "{target}");
+    }
+
+    let version = version.to_string();
+    assert!(!is_suppressed(&UpdaterSettings::default(), &version, 0));
+    assert!(is_suppressed(&settings_with(&[&version], None), &version, 0));
+    assert!(get(port, "/missing").starts_with("HTTP/1.1 404"));
+  }
+
+  #[test]
+  #[ignore = "需要 node：cargo test -- --ignored"]
+  fn scheduled_check_uses_endpoint_override() {
+    let (port, _server) = start_mock_server("99.0.0");
+    let dir = tempfile::tempdir().unwrap();
+    let app = mock_app(dir.path());
+    let handle = app.handle();
+
+    std::env::set_var(ENDPOINT_ENV, "not a url");
+    assert!(build(handle, UpdateChannel::Stable).is_err());
+    std::env::set_var(ENDPOINT_ENV, format!("http://127.0.0.1:{port}/latest.json"));
+    assert!(build(handle, UpdateChannel::Stable).is_ok());
+
+    let (sender, receiver) = mpsc::channel();
+    handle.listen_any("updater:available", move |event| {
+      let _ = sender.send(event.payload().to_string());
+    });
+    tauri::async_runtime::block_on(scheduled_check(handle)).unwrap();
+    let payload: serde_json::Value =
+      serde_json::from_str(&receiver.recv_timeout(Duration::from_secs(5)).unwrap()).unwrap();
+    assert_eq!(payload["version"], "99.0.0");
+    assert_eq!(payload["channel"], "stable");
+
+    // 跳过或暂停后不再推送
+    settings::update(handle, "updater", |settings| {
+      settings.updater.skipped_versions.push("99.0.0".to_string())
+    })
+    .unwrap();
+    tauri::async_runtime::block_on(scheduled_check(handle)).unwrap();
+    settings::update(handle, "updater", |settings| {
+      settings.updater.skipped_versions.clear();
+      settings.updater.snoozed_until = Some(i64::MAX);
+    })
+    .unwrap();
+    tauri::async_runtime::block_on(scheduled_check(handle)).unwrap();
+    assert!(receiver.recv_timeout(Duration::from_millis(200)).is_err());
+    std::env::remove_var(ENDPOINT_ENV);
+  }
+}
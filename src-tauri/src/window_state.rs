@@ -0,0 +1,250 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, Monitor, PhysicalPosition, PhysicalSize, Runtime, State, WebviewWindow, Window};
+
+use crate::{storage, tool_window};
+
+const WINDOW_STATE_FILE: &str = "window-state.json";
+/// 移动/缩放事件非常频繁，写盘间隔至少这么久；关闭时总会写入
+const SAVE_INTERVAL: Duration = Duration::from_secs(1);
+/// 恢复时窗口至少要有这么多像素落在显示器内，否则视为离屏
+const MIN_VISIBLE: i32 = 64;
+
+/// 主窗口默认尺寸，与 `tauri.conf.json` 保持一致
+pub const DEFAULT_WIDTH: u32 = 1440;
+pub const DEFAULT_HEIGHT: u32 = 900;
+
+/// 窗口几何信息（物理像素）；最大化时保留还原后的位置与尺寸
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct WindowGeometry {
+  pub x: i32,
+  pub y: i32,
+  pub width: u32,
+  pub height: u32,
+  pub maximized: bool,
+  pub monitor: Option<String>,
+}
+
+/// 按窗口 label 保存的几何信息
+#[derive(Default)]
+pub struct WindowStateStore {
+  states: Mutex<HashMap<String, WindowGeometry>>,
+  last_saved: Mutex<Option<Instant>>,
+}
+
+impl WindowStateStore {
+  pub fn get(&self, label: &str) -> Option<WindowGeometry> {
+    self.states.lock().ok()?.get(label).cloned()
+  }
+}
+
+fn persist<R: Runtime>(app: &AppHandle<R>, store: &WindowStateStore) -> Result<(), String> {
+  let states = store.states.lock().map_err(|e| e.to_string())?.clone();
+  let path = storage::config_file(app, WINDOW_STATE_FILE)?;
+  storage::write_json(&path, &states)?;
+  if let Ok(mut last_saved) = store.last_saved.lock() {
+    *last_saved = Some(Instant::now());
+  }
+  Ok(())
+}
+
+/// 读取窗口当前的几何信息；最小化时位置不可信，返回 None
+fn capture<R: Runtime>(window: &Window<R>, previous: Option<&WindowGeometry>) -> Option<WindowGeometry> {
+  if window.is_minimized().unwrap_or(false) {
+    return None;
+  }
+  let monitor = window
+    .current_monitor()
+    .ok()
+    .flatten()
+    .and_then(|monitor| monitor.name().cloned());
+  if window.is_maximized().unwrap_or(false) {
+    // 最大化时的尺寸就是整个显示器，保留之前的还原尺寸
+    let mut geometry = previous.cloned().unwrap_or(WindowGeometry {
+      x: 0,
+      y: 0,
+      width: DEFAULT_WIDTH,
+      height: DEFAULT_HEIGHT,
+      maximized: true,
+      monitor: None,
+    });
+    geometry.maximized = true;
+    geometry.monitor = monitor.or(geometry.monitor);
+    return Some(geometry);
+  }
+  let position = window.outer_position().ok()?;
+  let size = window.inner_size().ok()?;
+  Some(WindowGeometry {
+    x: position.x,
+    y: position.y,
+    width: size.width,
+    height: size.height,
+    maximized: false,
+    monitor,
+  })
+}
+
+/// 记录窗口几何信息；`force` 为真时立即写盘（关闭窗口时）
+pub fn save<R: Runtime>(window: &Window<R>, force: bool) {
+  let app = window.app_handle();
+  let store = app.state::<WindowStateStore>();
+  let changed = {
+    let Ok(mut states) = store.states.lock() else {
+      return;
+    };
+    let Some(geometry) = capture(window, states.get(window.label())) else {
+      return;
+    };
+    states.insert(window.label().to_string(), geometry.clone()) != Some(geometry)
+  };
+  let due = store
+    .last_saved
+    .lock()
+    .map(|last_saved| last_saved.is_none_or(|time| time.elapsed() >= SAVE_INTERVAL))
+    .unwrap_or(true);
+  if force || (changed && due) {
+    let _ = persist(app, &store);
+  }
+}
+
+/// 退出前写入所有窗口的最新状态；托盘退出走 `app.exit`，不会触发 CloseRequested
+pub fn flush<R: Runtime>(app: &AppHandle<R>) {
+  let store = app.state::<WindowStateStore>();
+  if let Ok(mut states) = store.states.lock() {
+    for window in app.webview_windows().values() {
+      if let Some(geometry) = capture(&window.as_ref().window(), states.get(window.label())) {
+        states.insert(window.label().to_string(), geometry);
+      }
+    }
+  }
+  if let Err(error) = persist(app, &store) {
+    log::warn!("Failed to save window state on exit: {error}");
+  }
+}
+
+fn overlap(a_start: i32, a_len: u32, b_start: i32, b_len: u32) -> i32 {
+  let end = (a_start + a_len as i32).min(b_start + b_len as i32);
+  end - a_start.max(b_start)
+}
+
+fn is_visible_on(geometry: &WindowGeometry, monitor: &Monitor) -> bool {
+  let position = monitor.position();
+  let size = monitor.size();
+  overlap(geometry.x, geometry.width, position.x, size.width) >= MIN_VISIBLE
+    && overlap(geometry.y, geometry.height, position.y, size.height) >= MIN_VISIBLE
+}
+
+/// 把窗口限制在显示器范围内：尺寸不超过显示器，位置整体落在显示器内
+fn clamp_to(geometry: &WindowGeometry, monitor: &Monitor) -> WindowGeometry {
+  let position = monitor.position();
+  let size = monitor.size();
+  let width = geometry.width.min(size.width);
+  let height = geometry.height.min(size.height);
+  let max_x = position.x + (size.width - width) as i32;
+  let max_y = position.y + (size.height - height) as i32;
+  WindowGeometry {
+    x: geometry.x.clamp(position.x, max_x),
+    y: geometry.y.clamp(position.y, max_y),
+    width,
+    height,
+    maximized: geometry.maximized,
+    monitor: monitor.name().cloned(),
+  }
+}
+
+/// 结合当前可用的显示器修正保存的几何信息：显示器已拔出或位置离屏时移回可见区域
+fn fit_to_monitors<R: Runtime>(window: &WebviewWindow<R>, geometry: &WindowGeometry) -> WindowGeometry {
+  let monitors = window.available_monitors().unwrap_or_default();
+  if monitors.iter().any(|monitor| is_visible_on(geometry, monitor)) {
+    return geometry.clone();
+  }
+  let target = monitors
+    .iter()
+    .find(|monitor| geometry.monitor.is_some() && monitor.name() == geometry.monitor.as_ref())
+    .cloned()
+    .or_else(|| window.primary_monitor().ok().flatten())
+    .or_else(|| monitors.first().cloned());
+  match target {
+    Some(monitor) => clamp_to(geometry, &monitor),
+    None => geometry.clone(),
+  }
+}
+
+/// 恢复窗口保存的几何信息，没有记录时保持窗口当前状态
+pub fn restore<R: Runtime>(window: &WebviewWindow<R>) -> Result<(), String> {
+  let store = window.state::<WindowStateStore>();
+  let Some(saved) = store.get(window.label()) else {
+    return Ok(());
+  };
+  let geometry = fit_to_monitors(window, &saved);
+  window
+    .set_size(PhysicalSize::new(geometry.width, geometry.height))
+    .map_err(|e| e.to_string())?;
+  window
+    .set_position(PhysicalPosition::new(geometry.x, geometry.y))
+    .map_err(|e| e.to_string())?;
+  if geometry.maximized {
+    window.maximize().map_err(|e| e.to_string())?;
+  }
+  Ok(())
+}
+
+/// 读取保存的窗口状态并恢复主窗口；主窗口以隐藏状态创建，恢复后再显示以避免闪烁
+pub fn init<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+  let path = storage::config_file(app, WINDOW_STATE_FILE)?;
+  // 文件损坏时丢弃旧状态，不影响启动
+  let states: HashMap<String, WindowGeometry> = storage::read_json(&path).ok().flatten().unwrap_or_default();
+  *app
+    .state::<WindowStateStore>()
+    .states
+    .lock()
+    .map_err(|e| e.to_string())? = states;
+
+  if let Some(window) = app.get_webview_window("main") {
+    let restored = restore(&window);
+    window.show().map_err(|e| e.to_string())?;
+    restored?;
+  }
+  Ok(())
+}
+
+/// 获取窗口保存的状态，缺省为调用方窗口
+#[tauri::command]
+pub fn window_state_get(
+  window: WebviewWindow,
+  store: State<'_, WindowStateStore>,
+  label: Option<String>,
+) -> Option<WindowGeometry> {
+  store.get(label.as_deref().unwrap_or(window.label()))
+}
+
+/// 清除窗口保存的状态，并将窗口恢复为默认尺寸居中显示
+#[tauri::command]
+pub fn window_state_reset(
+  app_handle: AppHandle,
+  window: WebviewWindow,
+  store: State<'_, WindowStateStore>,
+  label: Option<String>,
+) -> Result<(), String> {
+  let label = label.unwrap_or_else(|| window.label().to_string());
+  store.states.lock().map_err(|e| e.to_string())?.remove(&label);
+  persist(&app_handle, &store)?;
+
+  if let Some(target) = app_handle.get_webview_window(&label) {
+    if target.is_maximized().unwrap_or(false) {
+      target.unmaximize().map_err(|e| e.to_string())?;
+    }
+    let (width, height) = match tool_window::slug_of(&label) {
+      Some(_) => (tool_window::DEFAULT_WIDTH, tool_window::DEFAULT_HEIGHT),
+      None => (DEFAULT_WIDTH, DEFAULT_HEIGHT),
+    };
+    target
+      .set_size(tauri::LogicalSize::new(width, height))
+      .map_err(|e| e.to_string())?;
+    target.center().map_err(|e| e.to_string())?;
+  }
+  Ok(())
+}
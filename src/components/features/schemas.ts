@@ -79,4 +79,6 @@ export interface SortableCategoryCardProps {
 export interface SettingsDialogProps {
   open: boolean
   onOpenChange: (open: boolean) => void
+  /** 每次递增都会在打开状态下触发一次检查更新（托盘菜单等外部入口使用） */
+  updateCheckRequest?: number
 }
@@ -27,13 +27,22 @@ import { ResourceOptimization } from "@/components/monitoring"
 import { CacheStrategyManager } from "@/components/monitoring"
 import type { SettingsStep, UpdateInfo } from "@/schemas/settings.schema"
 import { version } from "../../../package.json"
-import { isDesktopApp, getDesktopApi } from "@/lib/utils"
+import { isDesktopApp, getDesktopApi, formatFileSize } from "@/lib/utils"
 import { type SettingsDialogProps } from "@/components/features/schemas"
 
 // Re-export type for backward compatibility
 export type { SettingsDialogProps }
 
-export function SettingsDialog({ open, onOpenChange }: SettingsDialogProps) {
+/** 后台检查更新的间隔选项（小时） */
+const UPDATE_INTERVALS = [6, 12, 24, 72, 168]
+
+/** 剩余时间：不足一分钟显示秒数，否则显示 m:ss */
+function formatEta(seconds: number) {
+  if (seconds < 60) return `${seconds}s`
+  return `${Math.floor(seconds / 60)}:${String(seconds % 60).padStart(2, "0")}`
+}
+
+export function SettingsDialog({ open, onOpenChange, updateCheckRequest }: SettingsDialogProps) {
   const { t, i18n } = useTranslation()
   const [theme, setTheme] = useState(() => localStorage.getItem("theme") || "system")
   const locale = i18n.language.startsWith("en") ? "en" : "zh"
@@ -54,7 +63,12 @@ export function SettingsDialog({ open, onOpenChange }: SettingsDialogProps) {
   const [step, setStep] = useState<SettingsStep>("idle")
   const [contentLength, setContentLength] = useState(0)
   const [downloaded, setDownloaded] = useState(0)
+  const [bytesPerSecond, setBytesPerSecond] = useState(0)
+  const [etaSeconds, setEtaSeconds] = useState<number | null>(null)
+  const [updateError, setUpdateError] = useState("")
   const [noUpdateDialog, setNoUpdateDialog] = useState(false)
+  const [closeToTray, setCloseToTray] = useState(false)
+  const [updaterSchedule, setUpdaterSchedule] = useState<UpdaterSchedule | null>(null)
 
   const progress = useMemo(() => {
     return contentLength ? Math.round((downloaded / contentLength) * 100) : 0
@@ -96,24 +110,88 @@ export function SettingsDialog({ open, onOpenChange }: SettingsDialogProps) {
     setStep("downloading")
     setDownloaded(0)
     setContentLength(0)
-    await updateInfo.downloadAndInstall((event: any) => {
-      switch (event.event) {
-        case "Started":
-          setContentLength(event.data.contentLength || 0)
-          setDownloaded(0)
-          break
-        case "Progress":
-          if (typeof event.data.downloaded === "number") {
-            setDownloaded(event.data.downloaded)
-          } else if (typeof event.data.chunkLength === "number") {
-            setDownloaded((prev) => prev + event.data.chunkLength)
-          }
-          break
-        case "Finished":
-          setStep("finished")
-          break
-      }
-    })
+    setBytesPerSecond(0)
+    setEtaSeconds(null)
+    setUpdateError("")
+    try {
+      await updateInfo.downloadAndInstall((event: any) => {
+        switch (event.event) {
+          case "Started":
+            setContentLength(event.data.contentLength || 0)
+            setDownloaded(0)
+            break
+          case "Progress":
+            if (event.data.contentLength) {
+              setContentLength(event.data.contentLength)
+            }
+            if (typeof event.data.downloaded === "number") {
+              setDownloaded(event.data.downloaded)
+            } else if (typeof event.data.chunkLength === "number") {
+              setDownloaded((prev) => prev + event.data.chunkLength)
+            }
+            setBytesPerSecond(event.data.bytesPerSecond || 0)
+            setEtaSeconds(typeof event.data.etaSeconds === "number" ? event.data.etaSeconds : null)
+            break
+          // Finished 只表示下载结束，之后还要校验签名并安装
+          case "Verifying":
+            setStep("verifying")
+            break
+          case "Installing":
+            setStep("installing")
+            break
+          case "Failed":
+            setUpdateError(event.data.message || "")
+            setStep("failed")
+            break
+        }
+      })
+      // 命令在安装完成后才返回
+      setStep("finished")
+    } catch (error) {
+      console.error("Update failed:", error)
+      setUpdateError((error as Error)?.message || String(error))
+      setStep("failed")
+    }
+  }
+
+  useEffect(() => {
+    if (open && updateCheckRequest) {
+      checkForUpdates()
+    }
+    // eslint-disable-next-line react-hooks/exhaustive-deps
+  }, [open, updateCheckRequest])
+
+  useEffect(() => {
+    if (!open) return
+    const desktopApi = getDesktopApi()
+    desktopApi
+      ?.tray?.getCloseToTray()
+      .then(setCloseToTray)
+      .catch(() => {})
+    desktopApi?.settings
+      ?.get("updater")
+      .then(setUpdaterSchedule)
+      .catch(() => {})
+  }, [open])
+
+  const handleCloseToTrayChange = async (checked: boolean) => {
+    setCloseToTray(checked)
+    try {
+      await getDesktopApi()?.tray?.setCloseToTray(checked)
+    } catch (error) {
+      console.error("Failed to save close-to-tray setting:", error)
+      setCloseToTray(!checked)
+    }
+  }
+
+  const handleScheduleChange = async (autoCheck: boolean, intervalHours: number) => {
+    const updater = getDesktopApi()?.updater
+    if (!updater?.setSchedule) return
+    try {
+      setUpdaterSchedule(await updater.setSchedule(autoCheck, intervalHours))
+    } catch (error) {
+      console.error("Failed to save update schedule:", error)
+    }
   }
 
   const handleRelaunch = async () => {
@@ -179,8 +257,18 @@ export function SettingsDialog({ open, onOpenChange }: SettingsDialogProps) {
       document.documentElement.classList.add(theme)
     }
     localStorage.setItem("theme", theme)
+    getDesktopApi()
+      ?.settings?.set("preferences.theme", theme)
+      .catch((error) => console.error("Failed to save theme:", error))
   }, [theme])
 
+  const changeLanguage = (language: "zh" | "en") => {
+    i18n.changeLanguage(language)
+    getDesktopApi()
+      ?.settings?.set("preferences.language", language)
+      .catch((error) => console.error("Failed to save language:", error))
+  }
+
   if (!open) return null
 
   return (
@@ -298,14 +386,14 @@ export function SettingsDialog({ open, onOpenChange }: SettingsDialogProps) {
                         <div className="flex gap-2">
                           <Button
                             variant={locale === "zh" ? "default" : "outline"}
-                            onClick={() => i18n.changeLanguage("zh")}
+                            onClick={() => changeLanguage("zh")}
                             disabled={locale === "zh"}
                           >
                             {t("settings.chinese")}
                           </Button>
                           <Button
                             variant={locale === "en" ? "default" : "outline"}
-                            onClick={() => i18n.changeLanguage("en")}
+                            onClick={() => changeLanguage("en")}
                             disabled={locale === "en"}
                           >
                             {t("settings.english")}
@@ -329,6 +417,54 @@ export function SettingsDialog({ open, onOpenChange }: SettingsDialogProps) {
                           )}
                         </div>
                       </div>
+
+                      {isDesktop && updaterSchedule && (
+                        <>
+                          <Separator />
+
+                          <div className="flex items-center justify-between">
+                            <div>
+                              <Label htmlFor="auto-check-updates">{t("updater.autoCheck")}</Label>
+                              <p className="text-sm text-muted-foreground">{t("updater.autoCheckDesc")}</p>
+                            </div>
+                            <Switch
+                              id="auto-check-updates"
+                              checked={updaterSchedule.auto_check}
+                              onCheckedChange={(checked) =>
+                                handleScheduleChange(checked, updaterSchedule.check_interval_hours)
+                              }
+                            />
+                          </div>
+
+                          <div>
+                            <Label htmlFor="update-interval">{t("updater.checkInterval")}</Label>
+                            <Select
+                              value={String(updaterSchedule.check_interval_hours)}
+                              disabled={!updaterSchedule.auto_check}
+                              onValueChange={(value) => handleScheduleChange(updaterSchedule.auto_check, parseInt(value))}
+                            >
+                              <SelectTrigger
+                                id="update-interval"
+                                className="mt-2 w-40"
+                              >
+                                <SelectValue />
+                              </SelectTrigger>
+                              <SelectContent>
+                                {[...new Set([...UPDATE_INTERVALS, updaterSchedule.check_interval_hours])]
+                                  .sort((a, b) => a - b)
+                                  .map((hours) => (
+                                    <SelectItem
+                                      key={hours}
+                                      value={String(hours)}
+                                    >
+                                      {t("updater.everyHours", { count: hours })}
+                                    </SelectItem>
+                                  ))}
+                              </SelectContent>
+                            </Select>
+                          </div>
+                        </>
+                      )}
                     </div>
                   </Card>
                 </TabsContent>
@@ -552,6 +688,24 @@ export function SettingsDialog({ open, onOpenChange }: SettingsDialogProps) {
 
                         <Separator />
 
+                        {isDesktop && (
+                          <>
+                            <div className="flex items-center justify-between">
+                              <div>
+                                <Label htmlFor="close-to-tray">{t("tray.closeToTray")}</Label>
+                                <p className="text-sm text-muted-foreground">{t("tray.closeToTrayDesc")}</p>
+                              </div>
+                              <Switch
+                                id="close-to-tray"
+                                checked={closeToTray}
+                                onCheckedChange={handleCloseToTrayChange}
+                              />
+                            </div>
+
+                            <Separator />
+                          </>
+                        )}
+
                         <div>
                           <Label htmlFor="history-limit">{t("settings.preferences.historyLimit")}</Label>
                           <p className="text-sm text-muted-foreground mb-2">
@@ -603,13 +757,19 @@ export function SettingsDialog({ open, onOpenChange }: SettingsDialogProps) {
             <AlertDialogTitle>
               {step === "confirm" && t("settingsDialog.new-version-found")}
               {step === "downloading" && t("settingsDialog.downloading-update")}
-              {step === "finished" && t("settingsDialog.download-complete")}
+              {step === "verifying" && t("settingsDialog.verifying-update")}
+              {step === "installing" && t("settingsDialog.installing-update")}
+              {step === "finished" && t("settingsDialog.update-installed")}
+              {step === "failed" && t("settingsDialog.update-failed")}
             </AlertDialogTitle>
             <AlertDialogDescription>
               {step === "confirm" &&
                 `${t("settingsDialog.new-version-detected")} ${updateInfo?.version}，${t("settingsDialog.release-date")}：${updateInfo?.date}。\n${t("settingsDialog.update-content")}：${updateInfo?.body}`}
               {step === "downloading" && t("settingsDialog.downloading-package")}
-              {step === "finished" && t("settingsDialog.update-package-downloaded")}
+              {step === "verifying" && t("settingsDialog.verifying-package")}
+              {step === "installing" && t("settingsDialog.installing-package")}
+              {step === "finished" && t("settingsDialog.update-package-installed")}
+              {step === "failed" && (updateError || t("settingsDialog.update-failed"))}
             </AlertDialogDescription>
           </AlertDialogHeader>
           {step === "confirm" && (
@@ -631,9 +791,25 @@ export function SettingsDialog({ open, onOpenChange }: SettingsDialogProps) {
                   style={{ width: `${progress}%` }}
                 />
               </div>
-              <div className="text-sm text-muted-foreground">{progress}%</div>
+              <div className="text-sm text-muted-foreground">
+                {progress}%
+                {bytesPerSecond > 0 && ` · ${formatFileSize(bytesPerSecond)}/s`}
+                {etaSeconds !== null &&
+                  ` · ${t("settingsDialog.eta", { time: formatEta(etaSeconds) })}`}
+              </div>
             </div>
           )}
+          {step === "failed" && (
+            <AlertDialogFooter>
+              <Button
+                onClick={() => setDialogOpen(false)}
+                variant="secondary"
+              >
+                {t("common.cancel")}
+              </Button>
+              <Button onClick={handleUpdate}>{t("common.retry")}</Button>
+            </AlertDialogFooter>
+          )}
           {step === "finished" && (
             <AlertDialogFooter>
               <Button onClick={handleRelaunch}>{t("restartApp")}</Button>
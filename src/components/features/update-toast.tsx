@@ -0,0 +1,76 @@
+import { toast } from "sonner"
+import { useTranslation } from "react-i18next"
+import { Button } from "@/components/ui/button"
+import { getDesktopApi } from "@/lib/utils"
+
+/** 暂停提醒的时长（小时） */
+const SNOOZE_HOURS = 24
+
+interface UpdateToastProps {
+  id: string | number
+  version: string
+  onInstall: () => void
+}
+
+function UpdateToast({ id, version, onInstall }: UpdateToastProps) {
+  const { t } = useTranslation()
+  const updater = getDesktopApi()?.updater
+
+  const close = (action?: () => Promise<unknown>) => {
+    toast.dismiss(id)
+    action?.().catch((error) => console.error("Failed to save update reminder:", error))
+  }
+
+  return (
+    <div className="w-[356px] rounded-md border border-border bg-popover p-4 text-popover-foreground shadow-lg">
+      <div className="text-sm font-medium">{t("updater.available", { version })}</div>
+      <p className="mt-1 text-sm text-muted-foreground">{t("updater.availableDesc")}</p>
+      <div className="mt-3 flex flex-wrap justify-end gap-2">
+        {updater?.snooze && (
+          <Button
+            size="sm"
+            variant="ghost"
+            onClick={() => close(() => updater.snooze!(SNOOZE_HOURS))}
+          >
+            {t("updater.snooze")}
+          </Button>
+        )}
+        {updater?.skipVersion && (
+          <Button
+            size="sm"
+            variant="outline"
+            onClick={() => close(() => updater.skipVersion!(version))}
+          >
+            {t("updater.skipVersion")}
+          </Button>
+        )}
+        <Button
+          size="sm"
+          onClick={() => {
+            close()
+            onInstall()
+          }}
+        >
+          {t("updater.install")}
+        </Button>
+      </div>
+    </div>
+  )
+}
+
+/**
+ * 后台检查发现新版本时的非阻塞提示：安装、跳过此版本或稍后提醒。
+ * 同一版本只保留一条提示，后续检查周期的广播会替换而不是叠加
+ */
+export function showUpdateToast(version: string, onInstall: () => void) {
+  toast.custom(
+    (id) => (
+      <UpdateToast
+        id={id}
+        version={version}
+        onInstall={onInstall}
+      />
+    ),
+    { id: `updater:${version}`, duration: Infinity }
+  )
+}
@@ -6,6 +6,7 @@ import { Textarea } from "@/components/ui/textarea"
 import { Select, SelectContent, SelectItem, SelectTrigger, SelectValue } from "@/components/ui/select"
 import { Tabs, TabsContent, TabsList, TabsTrigger } from "@/components/ui/tabs"
 import { toast } from "sonner"
+import { useLaunchInput } from "@/hooks/use-launch-input"
 import {
   Download,
   FileText,
@@ -47,6 +48,7 @@ import {
 const Base64EncodeCore = () => {
   const [activeTab, setActiveTab] = useState<"encoder" | "files">("encoder")
   const [input, setInput] = useState("")
+  useLaunchInput(setInput)
   const [operation, setOperation] = useState<EncodingOperation>("encode")
   const [inputFormat, setInputFormat] = useState<EncodingFormat>("text")
   const [outputFormat, setOutputFormat] = useState<EncodingFormat>("base64")
@@ -7,6 +7,7 @@ import { Textarea } from "@/components/ui/textarea"
 import { Select, SelectContent, SelectItem, SelectTrigger, SelectValue } from "@/components/ui/select"
 import { Tabs, TabsContent, TabsList, TabsTrigger } from "@/components/ui/tabs"
 import { toast } from "sonner"
+import { useLaunchInput } from "@/hooks/use-launch-input"
 import { useTranslation } from "react-i18next"
 import {
   Download,
@@ -525,6 +526,7 @@ const JSONPrettyCore = () => {
   const { i18n } = useTranslation()
   const [activeTab, setActiveTab] = useState<"processor" | "batch" | "analyzer" | "templates">("processor")
   const [input, setInput] = useState("")
+  useLaunchInput(setInput)
   const [currentResult, setCurrentResult] = useState<JSONProcessingResult | null>(null)
   const [batches, setBatches] = useState<JSONBatch[]>([])
   const [batchInput, setBatchInput] = useState("")
@@ -5,6 +5,7 @@ import { Label } from "@/components/ui/label"
 import { Textarea } from "@/components/ui/textarea"
 import { Tabs, TabsContent, TabsList, TabsTrigger } from "@/components/ui/tabs"
 import { toast } from "sonner"
+import { useLaunchInput } from "@/hooks/use-launch-input"
 import {
   Download,
   Trash2,
@@ -1102,6 +1103,7 @@ const JWTDecodeCore = () => {
   const [selectedTemplate, setSelectedTemplate] = useState<string>("")
   const [currentToken, setCurrentToken] = useState<JWTToken | null>(null)
   const [inputToken, setInputToken] = useState("")
+  useLaunchInput(setInputToken)
 
   const { tokens, isProcessing, decodeToken, removeToken } = useJWTDecoder()
   const { exportToken } = useJWTExport()
@@ -35,7 +35,7 @@ import type {
   FontFamily,
   TextAlign,
 } from "@/components/tools/text-to-pdf/schema"
-import { formatFileSize } from "@/lib/utils"
+import { formatFileSize, getDesktopApi } from "@/lib/utils"
 
 // Utility functions
 
@@ -167,56 +167,136 @@ const pdfTemplates: PDFTemplate[] = [
   },
 ]
 
-// PDF Generation Engine (using jsPDF as fallback for client-side generation)
-const generatePDF = async (content: string, settings: PDFSettings, filename: string): Promise<PDFResult> => {
-  const startTime = performance.now()
+// 页面尺寸（毫米，纵向）
+const pageSizesMm: Record<PageSize, [number, number]> = {
+  A3: [297, 420],
+  A4: [210, 297],
+  A5: [148, 210],
+  Letter: [215.9, 279.4],
+  Legal: [215.9, 355.6],
+  Tabloid: [279.4, 431.8],
+}
 
-  try {
-    // For now, we'll use the existing API but with enhanced settings
-    const BASE_URL = "https://services-iota-sand.vercel.app"
-
-    const requestBody = {
-      title: settings.metadata.title || filename,
-      content,
-      settings: {
-        pageSize: settings.pageSize,
-        orientation: settings.orientation,
-        margins: settings.margins,
-        font: settings.font,
-        styling: settings.styling,
-        header: settings.header,
-        footer: settings.footer,
-        tableOfContents: settings.tableOfContents,
-        metadata: settings.metadata,
-      },
-    }
+const toTextPdfOptions = (settings: PDFSettings, filename: string, sourceName: string): TextPdfOptions => {
+  const [width, height] = pageSizesMm[settings.pageSize] ?? pageSizesMm.A4
+  const landscape = settings.orientation === "landscape"
+  const footer = settings.footer?.enabled
+    ? [settings.footer.text, settings.footer.showPageNumbers ? "{page} / {pages}" : ""].filter(Boolean).join("  ")
+    : undefined
 
-    const response = await fetch(`${BASE_URL}/pdf/export`, {
-      method: "POST",
-      headers: {
-        "Content-Type": "application/json",
-      },
-      body: JSON.stringify(requestBody),
-    })
+  return {
+    format: /\.(md|markdown)$/i.test(sourceName) ? "markdown" : "plain",
+    page_size: {
+      kind: "custom",
+      width_mm: landscape ? height : width,
+      height_mm: landscape ? width : height,
+    },
+    margins: settings.margins,
+    font_size: settings.font.size,
+    title: settings.metadata.title || filename.replace(/\.pdf$/i, ""),
+    header: settings.header?.enabled ? settings.header.text : undefined,
+    footer: footer || undefined,
+  }
+}
 
-    if (!response.ok) {
-      throw new Error(`PDF generation failed: ${response.statusText}`)
-    }
+const base64ToBytes = (data: string): Uint8Array => {
+  const binary = atob(data)
+  const bytes = new Uint8Array(binary.length)
+  for (let i = 0; i < binary.length; i++) {
+    bytes[i] = binary.charCodeAt(i)
+  }
+  return bytes
+}
 
-    const blob = await response.blob()
-    const url = URL.createObjectURL(blob)
+// 浏览器版本使用远程导出服务，支持字体、对齐、样式与目录等完整排版选项
+const generateRemotePDF = async (
+  content: string,
+  settings: PDFSettings,
+  filename: string
+): Promise<{ blob: Blob; pageCount: number }> => {
+  const BASE_URL = "https://services-iota-sand.vercel.app"
+
+  const requestBody = {
+    title: settings.metadata.title || filename,
+    content,
+    settings: {
+      pageSize: settings.pageSize,
+      orientation: settings.orientation,
+      margins: settings.margins,
+      font: settings.font,
+      styling: settings.styling,
+      header: settings.header,
+      footer: settings.footer,
+      tableOfContents: settings.tableOfContents,
+      metadata: settings.metadata,
+    },
+  }
+
+  const response = await fetch(`${BASE_URL}/pdf/export`, {
+    method: "POST",
+    headers: {
+      "Content-Type": "application/json",
+    },
+    body: JSON.stringify(requestBody),
+  })
+
+  if (!response.ok) {
+    throw new Error(`PDF generation failed: ${response.statusText}`)
+  }
+
+  // Estimate page count (rough calculation)
+  const wordsPerPage = 250
+  const wordCount = content.split(/\s+/).length
 
-    // Estimate page count (rough calculation)
-    const wordsPerPage = 250
-    const wordCount = content.split(/\s+/).length
-    const estimatedPageCount = Math.max(1, Math.ceil(wordCount / wordsPerPage))
+  return {
+    blob: await response.blob(),
+    pageCount: Math.max(1, Math.ceil(wordCount / wordsPerPage)),
+  }
+}
+
+// 桌面版调用本地的 text_to_pdf 命令，离线生成且支持中日韩字体
+const generateNativePDF = async (
+  pdf: NonNullable<NonNullable<Window["desktopApi"]>["pdf"]>,
+  content: string,
+  settings: PDFSettings,
+  filename: string,
+  sourceName: string
+): Promise<{ blob: Blob; pageCount: number }> => {
+  const output = await pdf.textToPdf(content, toTextPdfOptions(settings, filename, sourceName))
+  if (!output.data) {
+    throw new Error("PDF generation returned no data")
+  }
+  return {
+    blob: new Blob([base64ToBytes(output.data)], { type: "application/pdf" }),
+    pageCount: output.pages,
+  }
+}
+
+// 本地生成只支持页面、边距、字号与页眉页脚，其余排版选项仅浏览器版本可用
+const hasNativePDF = (): boolean => Boolean(getDesktopApi()?.pdf)
+
+// PDF 生成：桌面端走本地命令，浏览器回退到远程导出服务
+const generatePDF = async (
+  content: string,
+  settings: PDFSettings,
+  filename: string,
+  sourceName: string = filename
+): Promise<PDFResult> => {
+  const startTime = performance.now()
+
+  try {
+    const pdf = getDesktopApi()?.pdf
+    const { blob, pageCount } = pdf
+      ? await generateNativePDF(pdf, content, settings, filename, sourceName)
+      : await generateRemotePDF(content, settings, filename)
+    const url = URL.createObjectURL(blob)
 
     const result: PDFResult = {
       blob,
       url,
       filename,
       size: blob.size,
-      pageCount: estimatedPageCount,
+      pageCount,
       generationTime: performance.now() - startTime,
       settings,
     }
@@ -224,7 +304,7 @@ const generatePDF = async (content: string, settings: PDFSettings, filename: str
     return result
   } catch (error) {
     console.error("PDF generation error:", error)
-    throw new Error(error instanceof Error ? error.message : "PDF generation failed")
+    throw new Error(error instanceof Error ? error.message : String(error || "PDF generation failed"))
   }
 }
 
@@ -241,9 +321,9 @@ const downloadPDF = (result: PDFResult): void => {
 // Custom hooks
 const usePDFGeneration = () => {
   const generateSinglePDF = useCallback(
-    async (content: string, settings: PDFSettings, filename: string): Promise<PDFResult> => {
+    async (content: string, settings: PDFSettings, filename: string, sourceName?: string): Promise<PDFResult> => {
       try {
-        return await generatePDF(content, settings, filename)
+        return await generatePDF(content, settings, filename, sourceName)
       } catch (error) {
         console.error("PDF generation error:", error)
         throw new Error(error instanceof Error ? error.message : "PDF generation failed")
@@ -260,7 +340,7 @@ const usePDFGeneration = () => {
 
           try {
             const filename = file.name.replace(/\.[^/.]+$/, ".pdf")
-            const result = await generateSinglePDF(file.content, settings, filename)
+            const result = await generateSinglePDF(file.content, settings, filename, file.name)
 
             return {
               ...file,
@@ -535,6 +615,7 @@ const useDragAndDrop = (onFilesDropped: (files: File[]) => void) => {
  * Features: Real-time preview, file upload, batch processing, customizable templates
  */
 const TextToPDFCore = () => {
+  const nativePdf = hasNativePDF()
   const [activeTab, setActiveTab] = useState<"converter" | "files">("converter")
   const [text, setText] = useState(
     "Welcome to the Enhanced Text to PDF Converter!\n\nThis tool allows you to convert text to PDF with advanced customization options including:\n\n• Multiple page sizes and orientations\n• Custom fonts and styling\n• Headers and footers\n• Table of contents\n• Batch processing\n• Professional templates\n\nTry editing this text and see the real-time statistics update!"
@@ -831,30 +912,32 @@ const TextToPDFCore = () => {
                     </Select>
                   </div>
 
-                  <div className="space-y-2">
-                    <Label htmlFor="fontFamily">Font Family</Label>
-                    <Select
-                      value={settings.font.family}
-                      onValueChange={(value: FontFamily) =>
-                        setSettings((prev) => ({
-                          ...prev,
-                          font: { ...prev.font, family: value },
-                        }))
-                      }
-                    >
-                      <SelectTrigger id="fontFamily">
-                        <SelectValue />
-                      </SelectTrigger>
-                      <SelectContent>
-                        <SelectItem value="Arial">Arial</SelectItem>
-                        <SelectItem value="Times">Times New Roman</SelectItem>
-                        <SelectItem value="Courier">Courier New</SelectItem>
-                        <SelectItem value="Helvetica">Helvetica</SelectItem>
-                        <SelectItem value="Georgia">Georgia</SelectItem>
-                        <SelectItem value="Verdana">Verdana</SelectItem>
-                      </SelectContent>
-                    </Select>
-                  </div>
+                  {!nativePdf && (
+                    <div className="space-y-2">
+                      <Label htmlFor="fontFamily">Font Family</Label>
+                      <Select
+                        value={settings.font.family}
+                        onValueChange={(value: FontFamily) =>
+                          setSettings((prev) => ({
+                            ...prev,
+                            font: { ...prev.font, family: value },
+                          }))
+                        }
+                      >
+                        <SelectTrigger id="fontFamily">
+                          <SelectValue />
+                        </SelectTrigger>
+                        <SelectContent>
+                          <SelectItem value="Arial">Arial</SelectItem>
+                          <SelectItem value="Times">Times New Roman</SelectItem>
+                          <SelectItem value="Courier">Courier New</SelectItem>
+                          <SelectItem value="Helvetica">Helvetica</SelectItem>
+                          <SelectItem value="Georgia">Georgia</SelectItem>
+                          <SelectItem value="Verdana">Verdana</SelectItem>
+                        </SelectContent>
+                      </Select>
+                    </div>
+                  )}
 
                   <div className="space-y-2">
                     <Label htmlFor="fontSize">Font Size</Label>
@@ -874,48 +957,50 @@ const TextToPDFCore = () => {
                   </div>
                 </div>
 
-                <div className="grid grid-cols-1 md:grid-cols-2 gap-4">
-                  <div className="space-y-2">
-                    <Label htmlFor="textAlign">Text Alignment</Label>
-                    <Select
-                      value={settings.styling.textAlign}
-                      onValueChange={(value: TextAlign) =>
-                        setSettings((prev) => ({
-                          ...prev,
-                          styling: { ...prev.styling, textAlign: value },
-                        }))
-                      }
-                    >
-                      <SelectTrigger id="textAlign">
-                        <SelectValue />
-                      </SelectTrigger>
-                      <SelectContent>
-                        <SelectItem value="left">Left</SelectItem>
-                        <SelectItem value="center">Center</SelectItem>
-                        <SelectItem value="right">Right</SelectItem>
-                        <SelectItem value="justify">Justify</SelectItem>
-                      </SelectContent>
-                    </Select>
-                  </div>
+                {!nativePdf && (
+                  <div className="grid grid-cols-1 md:grid-cols-2 gap-4">
+                    <div className="space-y-2">
+                      <Label htmlFor="textAlign">Text Alignment</Label>
+                      <Select
+                        value={settings.styling.textAlign}
+                        onValueChange={(value: TextAlign) =>
+                          setSettings((prev) => ({
+                            ...prev,
+                            styling: { ...prev.styling, textAlign: value },
+                          }))
+                        }
+                      >
+                        <SelectTrigger id="textAlign">
+                          <SelectValue />
+                        </SelectTrigger>
+                        <SelectContent>
+                          <SelectItem value="left">Left</SelectItem>
+                          <SelectItem value="center">Center</SelectItem>
+                          <SelectItem value="right">Right</SelectItem>
+                          <SelectItem value="justify">Justify</SelectItem>
+                        </SelectContent>
+                      </Select>
+                    </div>
 
-                  <div className="space-y-2">
-                    <Label htmlFor="lineHeight">Line Height</Label>
-                    <Input
-                      id="lineHeight"
-                      type="number"
-                      min="1"
-                      max="3"
-                      step="0.1"
-                      value={settings.font.lineHeight}
-                      onChange={(e) =>
-                        setSettings((prev) => ({
-                          ...prev,
-                          font: { ...prev.font, lineHeight: Number(e.target.value) },
-                        }))
-                      }
-                    />
+                    <div className="space-y-2">
+                      <Label htmlFor="lineHeight">Line Height</Label>
+                      <Input
+                        id="lineHeight"
+                        type="number"
+                        min="1"
+                        max="3"
+                        step="0.1"
+                        value={settings.font.lineHeight}
+                        onChange={(e) =>
+                          setSettings((prev) => ({
+                            ...prev,
+                            font: { ...prev.font, lineHeight: Number(e.target.value) },
+                          }))
+                        }
+                      />
+                    </div>
                   </div>
-                </div>
+                )}
 
                 {/* Margins */}
                 <div className="space-y-2">
@@ -6,6 +6,7 @@ import { Textarea } from "@/components/ui/textarea"
 import { Select, SelectContent, SelectItem, SelectTrigger, SelectValue } from "@/components/ui/select"
 import { Tabs, TabsContent, TabsList, TabsTrigger } from "@/components/ui/tabs"
 import { toast } from "sonner"
+import { useLaunchInput } from "@/hooks/use-launch-input"
 import {
   Download,
   Trash2,
@@ -774,6 +775,7 @@ const useCopyToClipboard = () => {
 const URLEncodeCore = () => {
   const [activeTab, setActiveTab] = useState<"processor" | "batch" | "analyzer" | "templates">("processor")
   const [input, setInput] = useState("")
+  useLaunchInput(setInput)
   const [output, setOutput] = useState("")
   const [currentResult, setCurrentResult] = useState<URLProcessingResult | null>(null)
   const [batches, setBatches] = useState<URLBatch[]>([])
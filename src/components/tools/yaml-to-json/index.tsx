@@ -6,6 +6,7 @@ import { Textarea } from "@/components/ui/textarea"
 import { Select, SelectContent, SelectItem, SelectTrigger, SelectValue } from "@/components/ui/select"
 import { Tabs, TabsContent, TabsList, TabsTrigger } from "@/components/ui/tabs"
 import { toast } from "sonner"
+import { useLaunchInput } from "@/hooks/use-launch-input"
 import {
   Download,
   Trash2,
@@ -1036,6 +1037,7 @@ const useCopyToClipboard = () => {
 const YAMLToJSONCore = () => {
   const [activeTab, setActiveTab] = useState<"converter" | "batch" | "templates">("converter")
   const [yamlInput, setYamlInput] = useState("")
+  useLaunchInput(setYamlInput)
   const [jsonInput, setJsonInput] = useState("")
   const [currentResult, setCurrentResult] = useState<ConversionResult | null>(null)
   const [batches, setBatches] = useState<ConversionBatch[]>([])
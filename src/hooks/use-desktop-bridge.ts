@@ -0,0 +1,152 @@
+import { useEffect } from "react"
+import { useRouter } from "@tanstack/react-router"
+import { useTranslation } from "react-i18next"
+import { getDesktopApi } from "@/lib/utils"
+import { FAVORITES_KEY, RECENT_KEY, TOOLS_CHANGED_EVENT } from "@/hooks/use-favorites"
+import { showUpdateToast } from "@/components/features/update-toast"
+
+interface NavigatePayload {
+  slug: string
+  input?: string | null
+  file?: string | null
+}
+
+function readTools(key: string): { slug: string; name: string }[] {
+  try {
+    const stored = JSON.parse(localStorage.getItem(key) || "[]")
+    return Array.isArray(stored) ? stored.filter((tool) => tool && typeof tool.slug === "string") : []
+  } catch {
+    return []
+  }
+}
+
+// 旧版设置在 localStorage 中的键，对应 Rust 侧 `migrate_v0` 的输入字段
+const LEGACY_SETTINGS_KEYS = {
+  preferences: "kit-user-preferences",
+  configs: "kit-tool-configs",
+  favorites: FAVORITES_KEY,
+  recentTools: RECENT_KEY,
+  customCategories: "kit-custom-categories",
+} as const
+
+function readLegacySettings(): Record<string, unknown> {
+  const legacy: Record<string, unknown> = {}
+  for (const [field, key] of Object.entries(LEGACY_SETTINGS_KEYS)) {
+    try {
+      const stored = localStorage.getItem(key)
+      if (stored) legacy[field] = JSON.parse(stored)
+    } catch {
+      // 单项损坏时跳过，其余字段照常导入
+    }
+  }
+  return legacy
+}
+
+/**
+ * 桌面端事件桥接：
+ * - 收藏/最近使用变化时同步托盘菜单
+ * - 响应托盘、深链接发出的 `app:navigate`，并取走冷启动时的导航请求；`input`/`file` 作为路由查询参数交给工具
+ * - 托盘“检查更新”发出的 `updater:check-requested` 交给 `onCheckUpdates`；后台检查发现新版本时的 `updater:available`
+ *   以非阻塞提示展示，选择安装时再交给 `onCheckUpdates`
+ * - 首次启动时把 localStorage 中的旧版设置导入 Rust 侧；其他窗口修改语言时经 `settings:changed` 同步
+ * - 独立工具窗口按 `__KIT_WINDOW__` 与 `window:options` 应用透明度
+ */
+export function useDesktopBridge(onCheckUpdates: () => void) {
+  const router = useRouter()
+  const { t, i18n } = useTranslation()
+
+  useEffect(() => {
+    const tray = getDesktopApi()?.tray
+    if (!tray) return
+
+    const sync = () => {
+      const toTrayTools = (key: string) =>
+        readTools(key).map((tool) => ({ slug: tool.slug, name: t(`tools.${tool.slug}`, tool.name) }))
+      tray
+        .setTools(toTrayTools(FAVORITES_KEY), toTrayTools(RECENT_KEY), {
+          favorites: t("favorites.title"),
+          recent: t("recent.title"),
+          show_hide: t("tray.showHide"),
+          check_updates: t("settings.checkForUpdates"),
+          quit: t("tray.quit"),
+        })
+        .catch((error) => console.error("Failed to sync tray menu:", error))
+    }
+
+    sync()
+    window.addEventListener(TOOLS_CHANGED_EVENT, sync)
+    // 其他窗口修改 localStorage 时同样需要刷新
+    window.addEventListener("storage", sync)
+    return () => {
+      window.removeEventListener(TOOLS_CHANGED_EVENT, sync)
+      window.removeEventListener("storage", sync)
+    }
+  }, [t, i18n.language])
+
+  useEffect(() => {
+    const desktopApi = getDesktopApi()
+    if (!desktopApi) return
+
+    const navigate = (payload: NavigatePayload | null) => {
+      if (payload?.slug) {
+        router.navigate({
+          to: "/tool/$tool",
+          params: { tool: payload.slug },
+          search: { input: payload.input ?? undefined, file: payload.file ?? undefined },
+        })
+      }
+    }
+    const unlisteners = [
+      desktopApi.listen("app:navigate", navigate),
+      desktopApi.listen("updater:check-requested", () => onCheckUpdates()),
+      // 其他窗口切换语言后同步到当前窗口
+      desktopApi.listen("settings:changed", (change: { key: string | null; value: unknown } | null) => {
+        const language = change?.key === "preferences.language" ? change.value : null
+        if (typeof language === "string" && !i18n.language.startsWith(language)) {
+          i18n.changeLanguage(language)
+        }
+      }),
+    ]
+    // 后台检查的结果广播给所有窗口，只在主窗口里提示
+    if (!window.__KIT_WINDOW__) {
+      unlisteners.push(
+        desktopApi.listen("updater:available", (update: { version: string } | null) => {
+          if (update?.version) showUpdateToast(update.version, onCheckUpdates)
+        })
+      )
+    }
+    // Rust 侧只在没有设置文件时接受一次导入，独立工具窗口不参与
+    if (!window.__KIT_WINDOW__) {
+      desktopApi.settings
+        ?.importLegacy(readLegacySettings())
+        .catch((error) => console.error("Failed to import legacy settings:", error))
+    }
+    // 冷启动时的导航请求可能早于监听注册，主动取一次
+    desktopApi.launch
+      ?.takePending()
+      .then(navigate)
+      .catch(() => {})
+    return () => {
+      unlisteners.forEach((unlisten) => unlisten.then((fn) => fn()).catch(() => {}))
+    }
+  }, [router, onCheckUpdates, i18n])
+
+  useEffect(() => {
+    const desktopApi = getDesktopApi()
+    const initial = window.__KIT_WINDOW__
+    if (!desktopApi || !initial) return
+
+    // 窗口以透明背景创建，降低根节点不透明度即可透出桌面
+    const apply = (options: ToolWindowOptions | null) => {
+      if (typeof options?.opacity === "number") {
+        document.documentElement.style.opacity = String(options.opacity)
+      }
+    }
+    apply(initial.options)
+    const unlisten = desktopApi.listen("window:options", apply)
+    return () => {
+      unlisten.then((fn) => fn()).catch(() => {})
+      document.documentElement.style.opacity = ""
+    }
+  }, [])
+}
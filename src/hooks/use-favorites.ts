@@ -1,14 +1,31 @@
 import { useState, useEffect } from "react"
 import type { Tool } from "@/schemas/tool.schema"
+import { getDesktopApi } from "@/lib/utils"
 
 interface RecentTool extends Tool {
   lastUsed: number
 }
 
-const FAVORITES_KEY = "kit-favorites"
-const RECENT_KEY = "kit-recent"
+export const FAVORITES_KEY = "kit-favorites"
+export const RECENT_KEY = "kit-recent"
+/** 收藏或最近使用变化时在 window 上派发，供托盘菜单等同步 */
+export const TOOLS_CHANGED_EVENT = "kit:tools-changed"
 const MAX_RECENT = 10
 
+const notifyToolsChanged = () => window.dispatchEvent(new Event(TOOLS_CHANGED_EVENT))
+
+/**
+ * 保存收藏或最近使用：localStorage 保留完整的工具对象供界面使用，
+ * 桌面端同时把 slug 列表写入 Rust 侧设置（`favorites` / `recent_tools`）
+ */
+function saveTools(key: string, settingsKey: string, tools: Tool[]) {
+  localStorage.setItem(key, JSON.stringify(tools))
+  getDesktopApi()
+    ?.settings?.set(settingsKey, tools.map((tool) => tool.slug))
+    .catch((error) => console.error(`Failed to save ${settingsKey}:`, error))
+  notifyToolsChanged()
+}
+
 // 收藏功能
 export function useFavorites() {
   const [favorites, setFavorites] = useState<Tool[]>([])
@@ -28,13 +45,13 @@ export function useFavorites() {
     const exists = favorites.some((t) => t.slug === tool.slug)
     const newFavorites = exists ? favorites : [...favorites, tool]
     setFavorites(newFavorites)
-    localStorage.setItem(FAVORITES_KEY, JSON.stringify(newFavorites))
+    saveTools(FAVORITES_KEY, "favorites", newFavorites)
   }
 
   const removeFromFavorites = (slug: string) => {
     const newFavorites = favorites.filter((tool) => tool.slug !== slug)
     setFavorites(newFavorites)
-    localStorage.setItem(FAVORITES_KEY, JSON.stringify(newFavorites))
+    saveTools(FAVORITES_KEY, "favorites", newFavorites)
   }
 
   const isFavorite = (slug: string) => {
@@ -91,12 +108,12 @@ export function useRecentTools() {
     }
 
     setRecentTools(newRecentTools)
-    localStorage.setItem(RECENT_KEY, JSON.stringify(newRecentTools))
+    saveTools(RECENT_KEY, "recent_tools", newRecentTools)
   }
 
   const clearRecent = () => {
     setRecentTools([])
-    localStorage.removeItem(RECENT_KEY)
+    saveTools(RECENT_KEY, "recent_tools", [])
   }
 
   return {
@@ -0,0 +1,33 @@
+import { useEffect, useRef } from "react"
+import { useSearch } from "@tanstack/react-router"
+import { getDesktopApi } from "@/lib/utils"
+
+/**
+ * 读取命令行或深链接（`kit://tool/<slug>?input=...&file=...`）携带的初始输入：
+ * `input` 直接交给工具，`file` 由 Rust 侧读取文本内容。同一组参数只应用一次
+ */
+export function useLaunchInput(onInput: (text: string) => void) {
+  // 工具也可能渲染在其他路由下（如独立窗口），因此不限定来源路由
+  const { input, file } = useSearch({ strict: false })
+  const onInputRef = useRef(onInput)
+  onInputRef.current = onInput
+  const appliedRef = useRef<string | null>(null)
+
+  useEffect(() => {
+    if (input == null && file == null) return
+    const key = JSON.stringify([input, file])
+    if (appliedRef.current === key) return
+    appliedRef.current = key
+
+    if (input != null) {
+      onInputRef.current(input)
+      return
+    }
+    const launch = getDesktopApi()?.launch
+    if (!launch || !file) return
+    launch
+      .readFile(file)
+      .then((text) => onInputRef.current(text))
+      .catch((error) => console.error("Failed to read launch file:", error))
+  }, [input, file])
+}
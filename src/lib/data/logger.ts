@@ -4,6 +4,8 @@
  * 支持生产环境敏感信息过滤和性能监控集成
  */
 
+import { getDesktopApi } from '../utils/utils'
+
 export enum LogLevel {
   DEBUG = 0,
   INFO = 1,
@@ -228,6 +230,11 @@ class Logger {
       this.logs.shift()
     }
 
+    // 警告与错误同时写入桌面端日志文件，随诊断包导出
+    if (level >= LogLevel.WARN) {
+      this.forward(level, message, sanitizedContext as Record<string, unknown> | undefined, stack)
+    }
+
     // 控制台输出
     const levelName = LogLevel[level]
     const consoleMethod = this.getConsoleMethod(level)
@@ -240,6 +247,20 @@ class Logger {
     }
   }
 
+  /**
+   * 转发到 Rust 侧日志（仅桌面端）
+   */
+  private forward(level: LogLevel, message: string, context?: Record<string, unknown>, stack?: string): void {
+    const log = getDesktopApi()?.log
+    if (!log) {
+      return
+    }
+    const fields = context || stack ? { ...context, stack } : undefined
+    log.write(level === LogLevel.ERROR ? 'error' : 'warn', message, { fields }).catch(() => {
+      // 转发失败时只保留控制台输出，避免递归记录
+    })
+  }
+
   /**
    * 获取控制台方法
    */
@@ -1,4 +1,5 @@
 import { useEffect, useState } from 'react'
+import { getDesktopApi } from '@/lib/utils'
 
 type Theme = 'dark' | 'light' | 'system'
 
@@ -46,5 +47,13 @@ export function useTheme() {
     localStorage.setItem('theme', newTheme)
   }
 
-  return { theme, setTheme }
+  // 桌面端同时写入 Rust 侧设置，供其他窗口同步
+  function changeTheme(newTheme: Theme) {
+    setTheme(newTheme)
+    getDesktopApi()
+      ?.settings?.set('preferences.theme', newTheme)
+      .catch((error) => console.error('Failed to save theme:', error))
+  }
+
+  return { theme, setTheme: changeTheme }
 }
\ No newline at end of file
@@ -71,7 +71,25 @@ function createTauriDesktopApi(): DesktopApiType | null {
     return null
   }
 
-  const invoke: (cmd: string, args?: Record<string, unknown>) => Promise<any> = tauri.core.invoke
+  const rawInvoke: (cmd: string, args?: Record<string, unknown>) => Promise<any> = tauri.core.invoke
+  const writeLog: NonNullable<DesktopApiType["log"]>["write"] = (level, message, options) =>
+    rawInvoke("log_write", {
+      level,
+      message,
+      target: options?.target ?? null,
+      fields: options?.fields ?? null,
+      command: options?.command ?? null,
+    })
+  // 所有命令都经由这里调用：失败时记入 Rust 日志与诊断包的命令失败列表，再把错误抛给调用方
+  const invoke = async (cmd: string, args?: Record<string, unknown>): Promise<any> => {
+    try {
+      return await rawInvoke(cmd, args)
+    } catch (error) {
+      const message = error instanceof Error ? error.message : String(error)
+      writeLog("error", message, { target: "command", command: cmd }).catch(() => {})
+      throw error
+    }
+  }
   const listen: (event: string, handler: (event: { payload: any }) => void) => Promise<() => void> = tauri.event.listen
   const relaunchProcess: (() => Promise<void>) | undefined = tauri.process?.relaunch
 
@@ -92,6 +110,29 @@ function createTauriDesktopApi(): DesktopApiType | null {
       close: () => invoke("window_close"),
       isMaximized: () => invoke("window_is_maximized"),
     },
+    listen: (event: string, handler: (payload: any) => void) => listen(event, (event) => handler(event.payload)),
+    launch: {
+      takePending: () => invoke("launch_take_pending"),
+      readFile: (path: string) => invoke("launch_read_file", { path }),
+    },
+    tray: {
+      setTools: (favorites, recent, labels) => invoke("tray_set_tools", { favorites, recent, labels }),
+      setCloseToTray: (enabled: boolean) => invoke("tray_set_close_to_tray", { enabled }),
+      getCloseToTray: async () => Boolean(await invoke("settings_get", { key: "preferences.close_to_tray" })),
+    },
+    settings: {
+      importLegacy: (legacy: Record<string, unknown>) => invoke("settings_import_legacy", { legacy }),
+      get: (key?: string) => invoke("settings_get", { key: key ?? null }),
+      set: (key: string | null, value: unknown) => invoke("settings_set", { key, value }),
+      watch: (keys?: string[]) => invoke("settings_watch", { keys: keys ?? null }),
+    },
+    log: {
+      write: writeLog,
+      diagnosticsBundle: (path?: string) => invoke("diagnostics_bundle", { path: path ?? null }),
+    },
+    pdf: {
+      textToPdf: (text: string, options?: TextPdfOptions) => invoke("text_to_pdf", { text, output: null, options }),
+    },
     updater: {
       check: async () => {
         const result = await invoke("updater_check")
@@ -104,7 +145,7 @@ function createTauriDesktopApi(): DesktopApiType | null {
       },
       downloadAndInstall: (cb: (event: any) => void) => {
         return new Promise<void>(async (resolve, reject) => {
-          let isResolved = false
+          let isSettled = false
           let unlisten: (() => void) | null = null
 
           const clear = () => {
@@ -118,6 +159,17 @@ function createTauriDesktopApi(): DesktopApiType | null {
             }
           }
 
+          const settle = (error?: unknown) => {
+            if (isSettled) return
+            isSettled = true
+            clear()
+            if (error) {
+              reject(error)
+            } else {
+              resolve()
+            }
+          }
+
           try {
             unlisten = await listen("updater:progress", (event) => {
               try {
@@ -132,6 +184,7 @@ function createTauriDesktopApi(): DesktopApiType | null {
                           return {
                             event: "Started",
                             data: {
+                              version: data.version,
                               contentLength: data.content_length ?? data.contentLength ?? 0,
                             },
                           }
@@ -142,56 +195,48 @@ function createTauriDesktopApi(): DesktopApiType | null {
                             data: {
                               downloaded: data.downloaded ?? 0,
                               contentLength: data.content_length ?? data.contentLength ?? 0,
-                              chunkLength: data.chunkLength ?? 0,
+                              bytesPerSecond: data.bytes_per_second ?? 0,
+                              etaSeconds: data.eta_seconds ?? null,
                             },
                           }
                         }
-                        if (evt === "Finished") {
+                        if (evt === "Failed") {
                           return {
-                            event: "Finished",
-                            data: {},
+                            event: "Failed",
+                            data: {
+                              code: data.code,
+                              message: data.message,
+                            },
                           }
                         }
-                        return payload
+                        // Finished / Verifying / Installing 无附加数据
+                        return { event: evt, data: {} }
                       })()
                     : payload
 
                 cb(mapped)
 
-                if (mapped && mapped.event === "Finished" && !isResolved) {
-                  isResolved = true
-                  clear()
-                  resolve()
+                if (mapped && mapped.event === "Failed") {
+                  settle(new Error(mapped.data.message || "Update failed"))
                 }
               } catch (error) {
-                if (!isResolved) {
-                  isResolved = true
-                  clear()
-                  reject(error)
-                }
+                settle(error)
               }
             })
 
+            // 安装完成后命令才返回，失败时同时收到 Failed 事件与命令错误
             await invoke("updater_download_and_install")
-
-            // 防止长时间无响应导致挂起
-            setTimeout(() => {
-              if (!isResolved) {
-                isResolved = true
-                clear()
-                reject(new Error("Download timeout: No progress events received"))
-              }
-            }, 300000)
+            settle()
           } catch (error) {
-            if (!isResolved) {
-              isResolved = true
-              clear()
-              reject(error)
-            }
+            settle(error)
           }
         })
       },
       install: () => invoke("updater_install"),
+      skipVersion: (version: string) => invoke("updater_skip_version", { version }),
+      snooze: (hours: number) => invoke("updater_snooze", { hours }),
+      setSchedule: (autoCheck: boolean, intervalHours?: number) =>
+        invoke("updater_set_schedule", { autoCheck, intervalHours: intervalHours ?? null }),
     },
   }
 
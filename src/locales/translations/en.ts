@@ -65,6 +65,23 @@ export default {
     empty: "No recent tools",
     "start-using": "Start using tools to see recent history",
   },
+  tray: {
+    showHide: "Show/Hide",
+    quit: "Quit",
+    closeToTray: "Close to tray",
+    closeToTrayDesc: "Keep running in the system tray when the main window is closed",
+  },
+  updater: {
+    available: "Kit {{version}} is available",
+    availableDesc: "Install it now, or skip this version.",
+    install: "Install",
+    skipVersion: "Skip this version",
+    snooze: "Remind me tomorrow",
+    autoCheck: "Check for updates automatically",
+    autoCheckDesc: "Look for new versions in the background",
+    checkInterval: "Check interval",
+    everyHours: "Every {{count}} hours",
+  },
   allTools: "All Tools",
   categories: "Categories",
   settings: {
@@ -311,6 +328,14 @@ export default {
     "update-package-downloaded": "Update package downloaded, click below to restart the app.",
     "check-update": "Check for Updates",
     "no-new-version": "No new version found",
+    "verifying-update": "Verifying Update",
+    "verifying-package": "Verifying the update signature...",
+    "installing-update": "Installing Update",
+    "installing-package": "Installing the update, please wait...",
+    "update-installed": "Update Installed",
+    "update-package-installed": "The update has been installed, click below to restart the app.",
+    "update-failed": "Update Failed",
+    "eta": "{{time}} remaining",
     "import-data": "Import Data",
     "import-data-confirm": "Please confirm the data content to import. This operation will overwrite all current data",
     "confirm-import": "Confirm Import",
@@ -67,6 +67,23 @@ export default {
     empty: "暂无使用记录",
     "start-using": "开始使用工具来查看最近记录",
   },
+  tray: {
+    showHide: "显示/隐藏",
+    quit: "退出",
+    closeToTray: "关闭时最小化到托盘",
+    closeToTrayDesc: "关闭主窗口后继续在系统托盘中运行",
+  },
+  updater: {
+    available: "Kit {{version}} 已发布",
+    availableDesc: "可以立即安装，也可以跳过此版本。",
+    install: "安装",
+    skipVersion: "跳过此版本",
+    snooze: "明天提醒",
+    autoCheck: "自动检查更新",
+    autoCheckDesc: "在后台定期检查新版本",
+    checkInterval: "检查间隔",
+    everyHours: "每 {{count}} 小时",
+  },
   category: {
     management: "分类管理",
     create: "创建分类",
@@ -312,6 +329,14 @@ export default {
     "update-package-downloaded": "更新包已下载完成，点击下方按钮重启应用。",
     "check-update": "检查更新",
     "no-new-version": "没有检测到新版本",
+    "verifying-update": "正在校验更新",
+    "verifying-package": "正在校验更新包签名...",
+    "installing-update": "正在安装更新",
+    "installing-package": "正在安装更新，请稍候...",
+    "update-installed": "更新已安装",
+    "update-package-installed": "更新已安装完成，点击下方按钮重启应用。",
+    "update-failed": "更新失败",
+    "eta": "剩余 {{time}}",
     "import-data": "导入数据",
     "import-data-confirm": "请确认要导入的数据内容，此操作将覆盖当前所有数据",
     "confirm-import": "确认导入",
@@ -1,11 +1,14 @@
 import { createRootRoute, Outlet } from "@tanstack/react-router"
 import "../App.css"
 import { CustomTitleBar } from "@/components/layout"
+import { SettingsDialog } from "@/components/features/settings-dialog"
 import { PerformanceMonitor } from "@/components/monitoring"
 import { isDesktopApp } from "@/lib/utils"
-import { useState, useEffect } from "react"
+import { useState, useEffect, useCallback } from "react"
 import { scheduleTTIMeasure, initWebVitals, initLongTaskObserver } from "@/lib/performance"
 import { useTranslation } from "react-i18next"
+import { useDesktopBridge } from "@/hooks/use-desktop-bridge"
+import { Toaster } from "@/components/ui/sonner"
 
 export const Route = createRootRoute({
   head: () => ({
@@ -28,6 +31,13 @@ export const Route = createRootRoute({
   component: () => {
     const { t } = useTranslation()
     const [showPerformanceMonitor, setShowPerformanceMonitor] = useState(false)
+    const [settingsOpen, setSettingsOpen] = useState(false)
+    const [updateCheckRequest, setUpdateCheckRequest] = useState(0)
+    const requestUpdateCheck = useCallback(() => {
+      setSettingsOpen(true)
+      setUpdateCheckRequest((count) => count + 1)
+    }, [])
+    useDesktopBridge(requestUpdateCheck)
     useEffect(() => {
       scheduleTTIMeasure()
       initWebVitals()
@@ -51,6 +61,16 @@ export const Route = createRootRoute({
           </main>
         </div>
 
+        {isDesktopApp() && (
+          <SettingsDialog
+            open={settingsOpen}
+            onOpenChange={setSettingsOpen}
+            updateCheckRequest={updateCheckRequest}
+          />
+        )}
+
+        <Toaster />
+
         <PerformanceMonitor
           isVisible={showPerformanceMonitor}
           onToggle={() => setShowPerformanceMonitor(false)}
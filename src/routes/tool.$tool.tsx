@@ -31,7 +31,17 @@ function isToolCategory(obj: unknown): obj is ToolCategory {
   )
 }
 
+/** 命令行或深链接携带的初始输入，由 `useLaunchInput` 读取 */
+export interface ToolSearch {
+  input?: string
+  file?: string
+}
+
 export const Route = createFileRoute("/tool/$tool")({
+  validateSearch: (search: Record<string, unknown>): ToolSearch => ({
+    input: typeof search.input === "string" ? search.input : undefined,
+    file: typeof search.file === "string" ? search.file : undefined,
+  }),
   loader: async ({ context, params }) => {
     const { queryClient } = context as { queryClient: QueryClient }
     const slug = params.tool
@@ -3,7 +3,7 @@
 /**
  * Settings Step type
  */
-export type SettingsStep = "idle" | "confirm" | "downloading" | "finished"
+export type SettingsStep = "idle" | "confirm" | "downloading" | "verifying" | "installing" | "finished" | "failed"
 
 /**
  * Update Info type
@@ -1,8 +1,44 @@
 /// <reference types="vite/client" />
 
 declare global {
+  /** 与 Rust 侧 `text_pdf::TextPdfOptions` 对应，页面尺寸与页边距单位为毫米 */
+  interface TextPdfOptions {
+    format?: "plain" | "markdown"
+    page_size?: { kind: "a4" | "a5" | "letter" | "legal" } | { kind: "custom"; width_mm: number; height_mm: number }
+    margins?: { top: number; right: number; bottom: number; left: number }
+    font_path?: string
+    font_size?: number
+    title?: string
+    header?: string
+    footer?: string
+  }
+
+  /** 托盘菜单中的工具项 */
+  interface TrayTool {
+    slug: string
+    name: string
+  }
+
+  /** 与 Rust 侧 `tool_window::ToolWindowOptions` 对应 */
+  interface ToolWindowOptions {
+    always_on_top: boolean
+    opacity: number
+  }
+
+  /** 更新相关设置，对应 Rust 侧的 `UpdaterSettings` */
+  interface UpdaterSchedule {
+    channel: "stable" | "beta" | "nightly"
+    install_on_quit: boolean
+    auto_check: boolean
+    check_interval_hours: number
+    skipped_versions: string[]
+    snoozed_until: number | null
+  }
+
   interface Window {
     adsbygoogle?: any
+    /** 独立工具窗口创建时注入的初始配置 */
+    __KIT_WINDOW__?: { slug: string; options: ToolWindowOptions }
     __TAURI__?: {
       core?: {
         invoke: (cmd: string, args?: Record<string, unknown>) => Promise<any>
@@ -19,9 +55,55 @@ declare global {
         check: () => Promise<{ version: string; date: string; body: string } | null>
         downloadAndInstall: (cb: (event: any) => void) => Promise<void>
         install?: () => Promise<void>
+        /** 后台检查不再提醒该版本 */
+        skipVersion?: (version: string) => Promise<void>
+        /** 暂停更新提醒若干小时，0 表示取消暂停；返回暂停截止时间（毫秒时间戳） */
+        snooze?: (hours: number) => Promise<number | null>
+        /** 设置后台定时检查的开关与间隔（小时） */
+        setSchedule?: (autoCheck: boolean, intervalHours?: number) => Promise<UpdaterSchedule>
       }
       relaunch: () => Promise<void>
       openExternal?: (url: string) => Promise<void>
+      /** 订阅 Rust 侧事件，返回取消订阅函数 */
+      listen: (event: string, handler: (payload: any) => void) => Promise<() => void>
+      launch?: {
+        /** 取走冷启动时命令行或深链接携带的导航请求 */
+        takePending: () => Promise<{ slug: string; input: string | null; file: string | null } | null>
+        /** 读取命令行或深链接传入的文件内容 */
+        readFile: (path: string) => Promise<string>
+      }
+      tray?: {
+        setTools: (
+          favorites: TrayTool[],
+          recent: TrayTool[],
+          labels?: { favorites: string; recent: string; show_hide: string; check_updates: string; quit: string }
+        ) => Promise<void>
+        setCloseToTray: (enabled: boolean) => Promise<void>
+        getCloseToTray: () => Promise<boolean>
+      }
+      settings?: {
+        /** 首次启动时把 localStorage 中的旧版设置导入 Rust 侧，返回是否发生了导入 */
+        importLegacy: (legacy: Record<string, unknown>) => Promise<boolean>
+        /** 读取设置，`key` 为点分路径（如 `preferences.theme`），省略时返回全部 */
+        get: (key?: string) => Promise<any>
+        /** 写入设置并广播 `settings:changed`，`key` 为 null 时整体替换 */
+        set: (key: string | null, value: unknown) => Promise<unknown>
+        /** 只接收匹配键路径的 `settings:changed`，返回当前全部设置 */
+        watch: (keys?: string[]) => Promise<any>
+      }
+      log?: {
+        /** 写入 Rust 侧日志文件；错误级别同时记入诊断包，带 `command` 时计为命令失败 */
+        write: (
+          level: "trace" | "debug" | "info" | "warn" | "error",
+          message: string,
+          options?: { target?: string; fields?: unknown; command?: string }
+        ) => Promise<void>
+        /** 生成诊断包，返回 zip 文件路径 */
+        diagnosticsBundle: (path?: string) => Promise<string>
+      }
+      pdf?: {
+        textToPdf: (text: string, options?: TextPdfOptions) => Promise<{ path: string | null; pages: number; data: string | null }>
+      }
       window?: {
         minimize: () => Promise<void>
         maximize: () => Promise<void>